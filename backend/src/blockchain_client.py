@@ -3,9 +3,46 @@ from substrateinterface.exceptions import SubstrateRequestException
 import json
 import logging
 from pathlib import Path
+import hashlib
 
 logger = logging.getLogger(__name__)
 
+# Contract logic versions this client was built against (major, minor, patch).
+# A deployment is compatible when the major matches and it is not older.
+REQUIRED_CONTRACT_VERSIONS = {
+    'token': (0, 1, 0),
+    'registry': (0, 1, 0),
+    'grid_service': (0, 1, 0),
+    'governance': (0, 1, 0),
+}
+
+
+def is_version_compatible(deployed: tuple, required: tuple) -> bool:
+    """Same rule as ContractVersion::is_compatible_with on-chain"""
+    return deployed[0] == required[0] and tuple(deployed[1:]) >= tuple(required[1:])
+
+
+def _scale_compact_len(n: int) -> bytes:
+    """SCALE compact encoding of a length prefix"""
+    if n < 1 << 6:
+        return bytes([n << 2])
+    if n < 1 << 14:
+        return ((n << 2) | 0b01).to_bytes(2, 'little')
+    return ((n << 2) | 0b10).to_bytes(4, 'little')
+
+
+def location_commitment(salt_hex: str, location: str) -> str:
+    """blake2_256(SCALE(salt, location)), matching the on-chain commitment.
+
+    Keep the salt private: it is needed to reveal the location for an audit.
+    """
+    salt = bytes.fromhex(salt_hex.removeprefix('0x'))
+    if len(salt) != 32:
+        raise ValueError("location_salt must be 32 bytes")
+    encoded_location = location.encode('utf-8')
+    payload = salt + _scale_compact_len(len(encoded_location)) + encoded_location
+    return '0x' + hashlib.blake2b(payload, digest_size=32).hexdigest()
+
 
 class BlockchainClient:
     """Client for interacting with PowerGrid smart contracts"""
@@ -84,12 +121,51 @@ class BlockchainClient:
                 )
                 logger.info(f"✅ Governance contract loaded: {governance_addr}")
             
-            return True
+            return self.check_contract_versions()
             
         except Exception as e:
             logger.error(f"❌ Failed to load contracts: {e}")
             return False
     
+    def get_contract_version(self, contract) -> tuple:
+        """Read `get_version` from a loaded contract as (major, minor, patch)"""
+        result = contract.read(self.keypair, 'get_version')
+        data = result.contract_result_data
+        if hasattr(data, 'value'):
+            data = data.value
+        if isinstance(data, dict) and 'Ok' in data:
+            data = data['Ok']
+        return (int(data['major']), int(data['minor']), int(data['patch']))
+
+    def check_contract_versions(self) -> bool:
+        """Refuse to run against contracts with an incompatible logic version"""
+        contracts = {
+            'token': self.token_contract,
+            'registry': self.registry_contract,
+            'grid_service': self.grid_service_contract,
+            'governance': self.governance_contract,
+        }
+        compatible = True
+        for name, contract in contracts.items():
+            if contract is None:
+                continue
+            required = REQUIRED_CONTRACT_VERSIONS[name]
+            try:
+                deployed = self.get_contract_version(contract)
+            except Exception as e:
+                logger.error(f"❌ Could not read {name} version: {e}")
+                compatible = False
+                continue
+            if is_version_compatible(deployed, required):
+                logger.info(f"✅ {name} v{'.'.join(map(str, deployed))}")
+            else:
+                logger.error(
+                    f"❌ {name} v{'.'.join(map(str, deployed))} is incompatible "
+                    f"with required v{'.'.join(map(str, required))}"
+                )
+                compatible = False
+        return compatible
+
     # ===== REGISTRY CONTRACT METHODS =====
     
     def is_device_registered(self) -> bool:
@@ -153,7 +229,8 @@ class BlockchainClient:
             device_metadata = {
                 'device_type': {'SmartPlug': None},  # Enum format
                 'capacity_watts': metadata['capacity_watts'],
-                'location': metadata['location'],
+                'location_commitment': location_commitment(metadata['location_salt'], metadata['location']),
+                'zone': metadata['zone'],
                 'manufacturer': metadata['manufacturer'],
                 'model': metadata['model'],
                 'firmware_version': metadata['firmware_version'],
@@ -0,0 +1,535 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod fee_distributor {
+    use ink::storage::Mapping;
+    use powergrid_shared::{ContractVersion, WiringReport};
+    use ink::prelude::vec::Vec;
+    use powergrid_shared::{Connect, TokenClient};
+
+    /// Token client: the deployed contract on chain, an installable mock in unit tests
+    #[cfg(not(test))]
+    type Token = powergrid_shared::TokenRef;
+    #[cfg(test)]
+    type Token = powergrid_shared::mock::MockToken;
+
+    /// Fixed-point scale of `acc_reward_per_token`
+    pub const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+    /// Default epoch length (7 days)
+    pub const DEFAULT_EPOCH_LENGTH_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// A staker's position and reward bookkeeping
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct StakeInfo {
+        /// Stake held for at least one full epoch, earning fees
+        pub amount: Balance,
+        /// Stake added during `activating_epoch`; earns from the following epoch on
+        pub activating: Balance,
+        pub activating_epoch: u32,
+        /// `acc_reward_per_token` at the last settlement of this position
+        pub reward_per_token_paid: u128,
+        /// Rewards settled but not yet claimed
+        pub owed: Balance,
+    }
+
+    /// Collects protocol fees and pays them out to PGT stakers once per epoch
+    #[ink(storage)]
+    pub struct FeeDistributor {
+        /// Simple reentrancy flag
+        entered: bool,
+        owner: AccountId,
+        /// PGT token, both the staked asset and the fee currency
+        token_address: AccountId,
+        /// Contracts allowed to report fees (grid service, registry)
+        fee_sources: Mapping<AccountId, bool>,
+        epoch_length_ms: u64,
+        current_epoch: u32,
+        epoch_started_at: u64,
+        /// Fees received during the current epoch, distributed at the next checkpoint
+        pending_fees: Balance,
+        /// Cumulative rewards per staked token, scaled by `PRECISION`
+        acc_reward_per_token: u128,
+        /// `acc_reward_per_token` right after each epoch's checkpoint
+        epoch_reward_per_token: Mapping<u32, u128>,
+        /// All stake held, including stake still activating
+        total_staked: Balance,
+        /// Stake added during the current epoch, excluded from its distribution
+        total_activating: Balance,
+        /// Rewards distributed but not yet claimed
+        total_unclaimed: Balance,
+        stakes: Mapping<AccountId, StakeInfo>,
+    }
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+        pub total_staked: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+        pub total_staked: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FeesNotified {
+        #[ink(topic)]
+        pub source: AccountId,
+        pub amount: Balance,
+        pub epoch: u32,
+    }
+
+    #[ink(event)]
+    pub struct EpochCheckpointed {
+        #[ink(topic)]
+        pub epoch: u32,
+        pub distributed: Balance,
+        pub total_staked: Balance,
+        pub acc_reward_per_token: u128,
+    }
+
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Errors
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        Unauthorized,
+        ZeroAmount,
+        InsufficientStake,
+        EpochNotElapsed,
+        NothingToClaim,
+        TransferFailed,
+        InvalidEpochLength,
+        ReentrantCall,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl FeeDistributor {
+        #[ink(constructor)]
+        pub fn new(token_address: AccountId, epoch_length_ms: u64) -> Self {
+            let epoch_length_ms = if epoch_length_ms == 0 { DEFAULT_EPOCH_LENGTH_MS } else { epoch_length_ms };
+            let instance = Self {
+                entered: false,
+                owner: Self::env().caller(),
+                token_address,
+                fee_sources: Mapping::default(),
+                epoch_length_ms,
+                current_epoch: 0,
+                epoch_started_at: Self::env().block_timestamp(),
+                pending_fees: 0,
+                acc_reward_per_token: 0,
+                epoch_reward_per_token: Mapping::default(),
+                total_staked: 0,
+                total_activating: 0,
+                total_unclaimed: 0,
+                stakes: Mapping::default(),
+            };
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
+            instance
+        }
+
+        /// Stake PGT to earn a share of protocol fees; requires a prior token approval.
+        /// New stake only shares in the fees of epochs that start after it was added.
+        #[ink(message)]
+        pub fn stake(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            let caller = self.env().caller();
+            let mut info = self.settled(caller);
+
+            self.entered = true;
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.transfer_from(caller, self.env().account_id(), amount, Vec::new()).is_err() {
+                    self.entered = false;
+                    return Err(Error::TransferFailed);
+                }
+            }
+
+            info.activating = info.activating.saturating_add(amount);
+            info.activating_epoch = self.current_epoch;
+            self.total_activating = self.total_activating.saturating_add(amount);
+            self.total_staked = self.total_staked.saturating_add(amount);
+            self.stakes.insert(caller, &info);
+            self.env().emit_event(Staked { account: caller, amount, total_staked: self.total_staked });
+            self.entered = false;
+            Ok(())
+        }
+
+        /// Withdraw staked PGT, still-activating stake first; accrued rewards stay claimable
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            let caller = self.env().caller();
+            let mut info = self.settled(caller);
+            if info.amount.saturating_add(info.activating) < amount {
+                return Err(Error::InsufficientStake);
+            }
+
+            let from_activating = amount.min(info.activating);
+            info.activating = info.activating.saturating_sub(from_activating);
+            info.amount = info.amount.saturating_sub(amount.saturating_sub(from_activating));
+            self.total_activating = self.total_activating.saturating_sub(from_activating);
+            self.total_staked = self.total_staked.saturating_sub(amount);
+            self.stakes.insert(caller, &info);
+
+            self.entered = true;
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.transfer(caller, amount, Vec::new()).is_err() {
+                    self.entered = false;
+                    return Err(Error::TransferFailed);
+                }
+            }
+
+            self.env().emit_event(Unstaked { account: caller, amount, total_staked: self.total_staked });
+            self.entered = false;
+            Ok(())
+        }
+
+        /// Record fees already transferred to this contract (fee sources or owner only)
+        #[ink(message)]
+        pub fn notify_fees(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner && !self.fee_sources.get(caller).unwrap_or(false) {
+                return Err(Error::Unauthorized);
+            }
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.pending_fees = self.pending_fees.saturating_add(amount);
+            self.env().emit_event(FeesNotified { source: caller, amount, epoch: self.current_epoch });
+            Ok(())
+        }
+
+        /// Close the current epoch and distribute its fees pro-rata to the stake held through
+        /// the whole epoch (anyone, once elapsed); stake added during the epoch activates afterwards
+        ///
+        /// Token inflow that was never reported via `notify_fees` is swept into the epoch too.
+        /// With nothing staked the fees roll over to the next epoch.
+        #[ink(message)]
+        pub fn checkpoint(&mut self) -> Result<Balance> {
+            let now = self.env().block_timestamp();
+            if now < self.epoch_started_at.saturating_add(self.epoch_length_ms) {
+                return Err(Error::EpochNotElapsed);
+            }
+
+            if let Some(token) = Token::connect(self.token_address) {
+                let balance = token.balance_of(self.env().account_id());
+                let tracked = self.total_staked.saturating_add(self.total_unclaimed).saturating_add(self.pending_fees);
+                self.pending_fees = self.pending_fees.saturating_add(balance.saturating_sub(tracked));
+            }
+
+            let mut distributed = 0;
+            let active = self.total_staked.saturating_sub(self.total_activating);
+            if active > 0 && self.pending_fees > 0 {
+                let increment = self.pending_fees.saturating_mul(PRECISION) / active;
+                // Remainders below one unit per staked token stay pending for the next epoch
+                distributed = increment.saturating_mul(active) / PRECISION;
+                self.acc_reward_per_token = self.acc_reward_per_token.saturating_add(increment);
+                self.pending_fees = self.pending_fees.saturating_sub(distributed);
+                self.total_unclaimed = self.total_unclaimed.saturating_add(distributed);
+            }
+
+            let epoch = self.current_epoch;
+            self.epoch_reward_per_token.insert(epoch, &self.acc_reward_per_token);
+            self.total_activating = 0;
+            self.current_epoch = self.current_epoch.saturating_add(1);
+            self.epoch_started_at = now;
+            self.env().emit_event(EpochCheckpointed {
+                epoch,
+                distributed,
+                total_staked: active,
+                acc_reward_per_token: self.acc_reward_per_token,
+            });
+            Ok(distributed)
+        }
+
+        /// Claim all rewards distributed to the caller so far
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<Balance> {
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            let caller = self.env().caller();
+            let mut info = self.settled(caller);
+            let amount = info.owed;
+            if amount == 0 {
+                return Err(Error::NothingToClaim);
+            }
+
+            info.owed = 0;
+            self.stakes.insert(caller, &info);
+            self.total_unclaimed = self.total_unclaimed.saturating_sub(amount);
+
+            self.entered = true;
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.transfer(caller, amount, Vec::new()).is_err() {
+                    self.entered = false;
+                    return Err(Error::TransferFailed);
+                }
+            }
+
+            self.env().emit_event(Claimed { account: caller, amount });
+            self.entered = false;
+            Ok(amount)
+        }
+
+        /// Rewards the account could claim right now
+        #[ink(message)]
+        pub fn claimable(&self, account: AccountId) -> Balance {
+            self.settled(account).owed
+        }
+
+        /// All stake of an account, including stake still activating
+        #[ink(message)]
+        pub fn get_stake(&self, account: AccountId) -> Balance {
+            self.stakes.get(account).map(|info| info.amount.saturating_add(info.activating)).unwrap_or(0)
+        }
+
+        /// Stake of an account that earns from the current epoch's fees
+        #[ink(message)]
+        pub fn get_active_stake(&self, account: AccountId) -> Balance {
+            self.settled(account).amount
+        }
+
+        #[ink(message)]
+        pub fn get_total_staked(&self) -> Balance {
+            self.total_staked
+        }
+
+        #[ink(message)]
+        pub fn get_pending_fees(&self) -> Balance {
+            self.pending_fees
+        }
+
+        #[ink(message)]
+        pub fn get_current_epoch(&self) -> u32 {
+            self.current_epoch
+        }
+
+        /// Timestamp from which the next checkpoint is allowed
+        #[ink(message)]
+        pub fn next_checkpoint_at(&self) -> u64 {
+            self.epoch_started_at.saturating_add(self.epoch_length_ms)
+        }
+
+        #[ink(message)]
+        pub fn get_acc_reward_per_token(&self) -> u128 {
+            self.acc_reward_per_token
+        }
+
+        /// Allow or revoke a contract reporting fees (owner only)
+        #[ink(message)]
+        pub fn set_fee_source(&mut self, source: AccountId, allowed: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if allowed {
+                self.fee_sources.insert(source, &true);
+            } else {
+                self.fee_sources.remove(source);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_fee_source(&self, source: AccountId) -> bool {
+            self.fee_sources.get(source).unwrap_or(false)
+        }
+
+        /// Change the epoch length; applies from the current epoch (owner only)
+        #[ink(message)]
+        pub fn set_epoch_length(&mut self, epoch_length_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            if epoch_length_ms == 0 {
+                return Err(Error::InvalidEpochLength);
+            }
+            self.epoch_length_ms = epoch_length_ms;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_token_address(&self) -> AccountId {
+            self.token_address
+        }
+
+        #[ink(message)]
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Check that the token contract answers and what it holds covers every tracked balance
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            if let Some(token) = Token::connect(self.token_address) {
+                let balance = token.balance_of(self.env().account_id());
+                let tracked = self.total_staked.saturating_add(self.total_unclaimed).saturating_add(self.pending_fees);
+                report.check("token_solvent", balance >= tracked);
+            }
+            report
+        }
+
+        /// Stake info with rewards accrued since its last settlement moved into `owed`, and
+        /// stake added in an already closed epoch activated
+        fn settled(&self, account: AccountId) -> StakeInfo {
+            let mut info = self.stakes.get(account).unwrap_or_default();
+            let delta = self.acc_reward_per_token.saturating_sub(info.reward_per_token_paid);
+            let accrued = info.amount.saturating_mul(delta) / PRECISION;
+            info.owed = info.owed.saturating_add(accrued);
+            if info.activating > 0 && info.activating_epoch < self.current_epoch {
+                // Earns from the distributions after the checkpoint that closed its epoch
+                let activated_at = self.epoch_reward_per_token.get(info.activating_epoch).unwrap_or(0);
+                let delta = self.acc_reward_per_token.saturating_sub(activated_at);
+                info.owed = info.owed.saturating_add(info.activating.saturating_mul(delta) / PRECISION);
+                info.amount = info.amount.saturating_add(info.activating);
+                info.activating = 0;
+            }
+            info.reward_per_token_paid = self.acc_reward_per_token;
+            info
+        }
+
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{default_accounts, set_block_timestamp, set_caller, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        #[ink::test]
+        fn test_epoch_distribution_is_pro_rata() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut distributor = FeeDistributor::new(accounts.django, 1_000);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            distributor.stake(300).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            distributor.stake(100).unwrap();
+            // Both stakes activate once the epoch they were added in closes
+            set_block_timestamp::<DefaultEnvironment>(1_000);
+            assert_eq!(distributor.checkpoint(), Ok(0));
+
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(distributor.notify_fees(400), Err(Error::Unauthorized));
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            distributor.set_fee_source(accounts.eve, true).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            distributor.notify_fees(400).unwrap();
+
+            assert_eq!(distributor.checkpoint(), Err(Error::EpochNotElapsed));
+            set_block_timestamp::<DefaultEnvironment>(2_000);
+            assert_eq!(distributor.checkpoint(), Ok(400));
+            assert_eq!(distributor.get_current_epoch(), 2);
+            assert_eq!(distributor.claimable(accounts.bob), 300);
+            assert_eq!(distributor.claimable(accounts.charlie), 100);
+
+            // A stake added after the checkpoint earns nothing from the closed epoch
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            distributor.stake(400).unwrap();
+            assert_eq!(distributor.claimable(accounts.frank), 0);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            distributor.unstake(300).unwrap();
+            assert_eq!(distributor.claim(), Ok(300));
+            assert_eq!(distributor.claim(), Err(Error::NothingToClaim));
+        }
+
+        #[ink::test]
+        fn test_fees_roll_over_without_stakers() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut distributor = FeeDistributor::new(accounts.django, 1_000);
+            distributor.notify_fees(500).unwrap();
+
+            set_block_timestamp::<DefaultEnvironment>(1_000);
+            assert_eq!(distributor.checkpoint(), Ok(0));
+            assert_eq!(distributor.get_pending_fees(), 500);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            distributor.stake(50).unwrap();
+            set_block_timestamp::<DefaultEnvironment>(2_000);
+            assert_eq!(distributor.checkpoint(), Ok(0));
+            set_block_timestamp::<DefaultEnvironment>(3_000);
+            assert_eq!(distributor.checkpoint(), Ok(500));
+            assert_eq!(distributor.claimable(accounts.bob), 500);
+        }
+
+
+        #[ink::test]
+        fn test_stake_added_before_checkpoint_earns_nothing() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut distributor = FeeDistributor::new(accounts.django, 1_000);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            distributor.stake(100).unwrap();
+            set_block_timestamp::<DefaultEnvironment>(1_000);
+            distributor.checkpoint().unwrap();
+            assert_eq!(distributor.get_active_stake(accounts.bob), 100);
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            distributor.notify_fees(600).unwrap();
+
+            // A large stake right before the checkpoint does not dilute the epoch's stakers
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            distributor.stake(900).unwrap();
+            assert_eq!(distributor.get_stake(accounts.frank), 900);
+            assert_eq!(distributor.get_active_stake(accounts.frank), 0);
+            set_block_timestamp::<DefaultEnvironment>(2_000);
+            assert_eq!(distributor.checkpoint(), Ok(600));
+            assert_eq!(distributor.claimable(accounts.bob), 600);
+            assert_eq!(distributor.claimable(accounts.frank), 0);
+
+            // Held through the next epoch, it earns its share
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            distributor.notify_fees(1_000).unwrap();
+            set_block_timestamp::<DefaultEnvironment>(3_000);
+            assert_eq!(distributor.checkpoint(), Ok(1_000));
+            assert_eq!(distributor.claimable(accounts.bob), 700);
+            assert_eq!(distributor.claimable(accounts.frank), 900);
+
+            // Stake pulled before its epoch closes never activates
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            distributor.stake(500).unwrap();
+            distributor.unstake(500).unwrap();
+            assert_eq!(distributor.get_total_staked(), 1_000);
+        }
+    }
+}
\ No newline at end of file
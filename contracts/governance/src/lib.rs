@@ -2,13 +2,302 @@
 
 #[ink::contract]
 pub mod governance {
-    use ink::prelude::{string::String, vec::Vec};
+    use ink::prelude::{format, string::String, vec, vec::Vec};
     use ink::storage::Mapping;
-    use ink::env::call::FromAccountId;
-    use powergrid_shared::{Proposal, ProposalType, ink_account_to_bytes};
-    use resource_registry::resource_registry::ResourceRegistryRef;
-    use grid_service::grid_service::GridServiceRef;
-    use powergrid_token::powergrid_token::PowergridTokenRef;
+    use powergrid_shared::{Page, Proposal, ProposalType, ContractVersion, CouncilBudget, GrantRoundParams, ONE_TOKEN, EXPECTED_BLOCK_TIME_MS, WiringReport, signed_message_hash, verify_signature, NonceDomain, NonceRegistry, HEALTH_SNAPSHOT_INTERVAL_BLOCKS};
+    use powergrid_shared::{AddressChangeError, AddressTimelock, ADDRESS_CHANGE_DELAY_MS, Connect, ExternalContract, GridClient, PendingAddressChange, RegistryClient, TokenClient};
+
+    /// Dependency clients: the deployed contracts on chain, installable mocks in unit tests
+    #[cfg(not(test))]
+    type Token = powergrid_shared::TokenRef;
+    #[cfg(test)]
+    type Token = powergrid_shared::mock::MockToken;
+    #[cfg(not(test))]
+    type Registry = powergrid_shared::RegistryRef;
+    #[cfg(test)]
+    type Registry = powergrid_shared::mock::MockRegistry;
+    #[cfg(not(test))]
+    type Grid = powergrid_shared::GridRef;
+    #[cfg(test)]
+    type Grid = powergrid_shared::mock::MockGrid;
+
+    /// Default window after the timelock during which a queued proposal must be executed (14 days)
+    pub const DEFAULT_GRACE_PERIOD_SECONDS: u64 = 14 * 24 * 60 * 60;
+
+    /// Maximum signed ballots accepted per `cast_votes_by_sig` call
+    pub const MAX_BALLOTS_PER_BATCH: usize = 50;
+
+    /// Highest conviction: a 6x vote multiplier
+    pub const MAX_CONVICTION: u8 = 6;
+
+    /// Tokens voted with conviction `c` stay locked `CONVICTION_LOCK_PERIOD_BLOCKS * 2^(c-1)` blocks (~7 days per period)
+    pub const CONVICTION_LOCK_PERIOD_BLOCKS: u32 = 100_800;
+
+    /// Blocks over which a conviction vote's weight accumulates to its full `power * conviction` (~1 day)
+    pub const CONVICTION_RAMP_BLOCKS: u32 = CONVICTION_LOCK_PERIOD_BLOCKS / 7;
+
+    /// Maximum unexpired conviction votes on one proposal
+    pub const MAX_CONVICTION_VOTES: usize = 256;
+
+    /// Net conviction a proposal needs right after creation, in basis points of total supply
+    pub const CONVICTION_THRESHOLD_START_BPS: u64 = 30_000;
+
+    /// Net conviction needed once the threshold has fully decayed
+    pub const CONVICTION_THRESHOLD_FLOOR_BPS: u64 = 5_000;
+
+    /// Blocks over which the conviction threshold falls from start to floor (~4 weeks)
+    pub const CONVICTION_DECAY_BLOCKS: u64 = 4 * CONVICTION_LOCK_PERIOD_BLOCKS as u64;
+
+    /// Maximum members of a program council
+    pub const MAX_COUNCIL_MEMBERS: usize = 16;
+
+    /// Maximum accounts delegating to one delegate
+    pub const MAX_DELEGATORS: usize = 32;
+
+    /// Maximum registered public delegates
+    pub const MAX_PUBLIC_DELEGATES: usize = 100;
+
+    /// Maximum length of a delegate statement URI
+    pub const MAX_STATEMENT_URI_LEN: usize = 256;
+
+    /// Length of a data-feed operator election (~7 days)
+    pub const FEED_ELECTION_EPOCH_BLOCKS: u64 = 100_800;
+
+    /// Feed operators elected per epoch by default
+    pub const DEFAULT_FEED_SEATS: u32 = 3;
+
+    /// Default bond a feed candidate deposits, refunded on withdrawal
+    pub const DEFAULT_FEED_CANDIDATE_BOND: Balance = 1_000 * ONE_TOKEN;
+
+    /// Maximum registered feed candidates
+    pub const MAX_FEED_CANDIDATES: usize = 32;
+
+    /// Maximum projects registered in one grant round
+    pub const MAX_GRANT_PROJECTS: usize = 32;
+
+    /// Maximum length of a grant project name
+    pub const MAX_GRANT_PROJECT_NAME_LEN: usize = 64;
+
+    /// Vote signed off-chain by `voter` and submitted by a relayer: a 64-byte sr25519
+    /// signature, or whatever a contract-account voter's `is_valid_signature` accepts
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SignedBallot {
+        pub voter: AccountId,
+        pub proposal_id: u64,
+        pub support: bool,
+        pub nonce: u64,
+        pub signature: Vec<u8>,
+    }
+
+    /// Pre-filled action that voters only parameterize with a bounded value
+    #[derive(Debug, Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ProposalTemplate {
+        pub name: String,
+        /// Action whose numeric parameter is replaced by the proposed value
+        pub action: ProposalType,
+        pub min_value: Balance,
+        pub max_value: Balance,
+    }
+
+    /// Conviction-weighted tally of a conviction proposal at the current block
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ConvictionTally {
+        pub ayes: Balance,
+        pub nays: Balance,
+        /// Block the threshold curve starts from
+        pub created_block: u64,
+    }
+
+    /// A conviction vote; it counts only until its lock ends
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ConvictionVote {
+        pub voter: AccountId,
+        pub support: bool,
+        pub voting_power: u64,
+        pub conviction: u8,
+        pub cast_block: u32,
+        pub unlock_block: u32,
+    }
+
+    /// Allowance usage of a council in its current budget period
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CouncilPeriod {
+        pub period_start: u64,
+        pub spent: Balance,
+    }
+
+    /// Treasury payout requested by a council member, paid once enough members approve
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CouncilSpend {
+        pub council_id: u32,
+        pub recipient: AccountId,
+        pub amount: Balance,
+        pub approvals: Vec<AccountId>,
+        pub executed: bool,
+    }
+
+    /// Public profile of a registered delegate
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DelegateProfile {
+        /// Hash of the display name (the name itself lives off-chain)
+        pub name_hash: [u8; 32],
+        pub statement_uri: String,
+        pub registered_at: u64,
+    }
+
+    /// Quadratic-funding round: donations to registered projects are matched from the pool at close
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct GrantRound {
+        pub matching_pool: Balance,
+        pub contributor_cap: Balance,
+        /// Donations are accepted before this block
+        pub end_block: u64,
+        pub projects: Vec<AccountId>,
+        pub closed: bool,
+        /// Matching assigned to projects at close
+        pub matched: Balance,
+    }
+
+    /// A project's standing in a grant round; the project account receives the payout
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct GrantProject {
+        pub name: String,
+        pub donations: Balance,
+        pub contributors: u32,
+        /// Capped donations from device owners, the only ones that attract matching
+        pub eligible_donations: Balance,
+        /// Sum of the square roots of each eligible contributor's capped donation
+        pub sqrt_sum: Balance,
+        /// Matching assigned at close
+        pub matching: Balance,
+        pub claimed: bool,
+    }
+
+    /// Protocol value a proposal's action changes
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum SimulatedValue {
+        MinStake,
+        DefaultCompensationRate,
+        ReputationThreshold,
+        TreasuryBalance,
+        /// PGT balance of an account
+        BalanceOf(AccountId),
+        /// Allowance a council can still spend this period
+        CouncilRemainingAllowance(u32),
+    }
+
+    /// Current and predicted value of something a proposal changes
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SimulatedChange {
+        pub value: SimulatedValue,
+        pub current: Balance,
+        pub predicted: Balance,
+    }
+
+    /// Outcome of `simulate_proposal`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ProposalSimulation {
+        pub state: ProposalState,
+        /// Whether the votes cast so far meet majority and quorum
+        pub passing: bool,
+        /// Whether the action would apply against current state; None when only the
+        /// target contract can tell (dry-run `execute_proposal` on a node for those)
+        pub action_succeeds: Option<bool>,
+        pub changes: Vec<SimulatedChange>,
+    }
+
+    /// Which clock each governance deadline is measured on. Voting windows are set in
+    /// blocks but also carry a timestamp deadline (`voting_duration_blocks` at the expected
+    /// block time) and close on whichever is reached first; execution windows are timestamps
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct TimeConfig {
+        pub expected_block_time_ms: u64,
+        /// Proposal voting window (block number and timestamp)
+        pub voting_duration_blocks: u64,
+        pub voting_duration_ms: u64,
+        /// Delay between queueing and execution (timestamp)
+        pub timelock_ms: u64,
+        /// Window after the timelock in which a queued proposal must execute (timestamp)
+        pub grace_period_ms: u64,
+        /// Delay before a proposed contract address takes effect (timestamp)
+        pub address_change_delay_ms: u64,
+        /// Base conviction lock period (block number)
+        pub conviction_lock_period_blocks: u32,
+        /// Length of a feed operator election (block number)
+        pub feed_election_epoch_blocks: u64,
+    }
+
+    /// Aggregate governance figures for UIs
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct GovernanceStats {
+        pub total_proposals: u64,
+        pub executed: u64,
+        pub defeated: u64,
+        /// Average turnout of finalized proposals, in basis points of total supply
+        pub average_turnout_bps: u32,
+    }
+
+    /// A ballot as recorded for export; delegated power is counted in the delegate's ballot
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct VoteRecord {
+        pub voter: AccountId,
+        pub support: bool,
+        pub voting_power: u64,
+        /// Conviction multiplier (0 for window-voted proposals)
+        pub conviction: u8,
+    }
+
+    /// A proposal as exported for audits
+    #[derive(Debug, Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ExportedProposal {
+        pub proposal_id: u64,
+        pub proposal: Proposal,
+        pub state: Option<ProposalState>,
+    }
+
+    /// Lifecycle state of a proposal
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ProposalState {
+        /// Voting is open
+        Active,
+        /// Voting ended without passing
+        Defeated,
+        /// Passed, waiting to be queued
+        Succeeded,
+        /// Queued; executable once the timelock has elapsed
+        Queued,
+        /// Grace period elapsed without execution
+        Expired,
+        /// Vetoed by the guardian while queued
+        Vetoed,
+        Executed,
+    }
 
     /// The Governance contract
     #[ink(storage)]
@@ -26,8 +315,7 @@ pub mod governance {
         /// Proposals mapping
         proposals: Mapping<u64, Proposal>,
         /// Voting records (proposal_id -> voter -> voted)
-        #[allow(clippy::type_complexity)]
-        votes: Mapping<(u64, [u8; 32]), bool>,
+        votes: Mapping<(u64, AccountId), bool>,
         /// Next proposal ID
         next_proposal_id: u64,
         /// Minimum voting power required to create proposals
@@ -40,50 +328,320 @@ pub mod governance {
     timelock_seconds: u64,
     /// Queue timestamps for proposals (proposal_id -> queued_at timestamp)
     queue_times: Mapping<u64, u64>,
+    /// Hash of the encoded actions and description fixed at proposal creation
+    action_hashes: Mapping<u64, [u8; 32]>,
+    /// Seconds after the timelock during which execution must happen
+    grace_period_seconds: u64,
+    /// Signed-ballot nonces per voter
+    nonces: NonceRegistry,
+    /// Turnout (basis points of total voting power) recorded when a proposal is first finalized
+    turnouts: Mapping<u64, u32>,
+    /// Number of proposals each account voted on
+    voted_counts: Mapping<AccountId, u32>,
+    executed_count: u64,
+    defeated_count: u64,
+    finalized_count: u64,
+    turnout_sum_bps: u64,
+    /// Protocol guardian: may veto queued proposals, cannot propose or execute
+    guardian: Option<AccountId>,
+    /// Proposals vetoed by the guardian
+    vetoed: Mapping<u64, bool>,
+    /// Pending changes of the token, registry and grid service addresses
+    address_timelock: AddressTimelock,
+    /// Ballots per proposal in casting order, for export
+    vote_records: Mapping<(u64, u32), VoteRecord>,
+    vote_record_counts: Mapping<u64, u32>,
+    /// Proposal templates by ID
+    templates: Mapping<u32, ProposalTemplate>,
+    /// Timestamp at which each proposal's voting window closes, alongside its block deadline
+    voting_end_times: Mapping<u64, u64>,
+    /// Creation block of proposals decided by conviction voting instead of a voting window
+    conviction_proposals: Mapping<u64, u64>,
+    /// Unexpired votes on each conviction proposal
+    conviction_votes: Mapping<u64, Vec<ConvictionVote>>,
+    /// Program council budgets funded by the treasury
+    council_budgets: Mapping<u32, CouncilBudget>,
+    council_periods: Mapping<u32, CouncilPeriod>,
+    council_spends: Mapping<u64, CouncilSpend>,
+    next_council_spend_id: u64,
+    /// Delegator -> delegate
+    delegations: Mapping<AccountId, AccountId>,
+    /// Delegate -> accounts delegating to it
+    delegators: Mapping<AccountId, Vec<AccountId>>,
+    delegate_profiles: Mapping<AccountId, DelegateProfile>,
+    /// Registered public delegates, in registration order
+    public_delegates: Vec<AccountId>,
+    /// Data-feed operator elections
+    feed_seats: u32,
+    feed_candidate_bond: Balance,
+    /// Candidate -> bond held
+    feed_candidates: Mapping<AccountId, Balance>,
+    feed_candidate_list: Vec<AccountId>,
+    /// Tokens the contract holds on behalf of others (candidate bonds, grant matching pools and
+    /// donations); not spendable as treasury
+    reserved_balance: Balance,
+    feed_election_epoch: u64,
+    feed_epoch_end_block: u64,
+    /// (epoch, voter) -> candidate voted for
+    #[allow(clippy::type_complexity)]
+    feed_votes: Mapping<(u64, AccountId), AccountId>,
+    /// (epoch, candidate) -> votes received
+    #[allow(clippy::type_complexity)]
+    feed_tallies: Mapping<(u64, AccountId), u64>,
+    /// Feeds installed on GridService by the last finalized election
+    elected_feeds: Vec<AccountId>,
+    /// GridService alarm -> proposal created for it
+    alarm_proposals: Mapping<u64, u64>,
+    /// Block of the last health snapshot
+    last_health_snapshot: Option<u32>,
+    /// Quadratic-funding grant rounds by ID
+    grant_rounds: Mapping<u64, GrantRound>,
+    next_grant_round_id: u64,
+    /// (round, project) -> project standing
+    #[allow(clippy::type_complexity)]
+    grant_projects: Mapping<(u64, AccountId), GrantProject>,
+    /// (round, project, contributor) -> donated so far
+    #[allow(clippy::type_complexity)]
+    grant_donations: Mapping<(u64, AccountId, AccountId), Balance>,
+    }
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
+    }
+
+    /// Periodic summary for off-chain monitoring
+    #[ink(event)]
+    pub struct HealthSnapshot {
+        pub block: u32,
+        pub active_proposals: u32,
+        pub queued_proposals: u32,
+        /// PGT held by governance
+        pub treasury_balance: Balance,
     }
 
     /// Events emitted by the contract
     #[ink(event)]
     pub struct ProposalCreated {
         #[ink(topic)]
-        proposal_id: u64,
+        pub proposal_id: u64,
+        #[ink(topic)]
+        pub proposer: AccountId,
+        pub proposal_type: ProposalType,
+        pub description: String,
+        pub voting_end: u64,
+        pub action_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct FeedCandidateRegistered {
+        #[ink(topic)]
+        pub candidate: AccountId,
+        pub bond: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FeedCandidateWithdrawn {
+        #[ink(topic)]
+        pub candidate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GrantRoundOpened {
+        #[ink(topic)]
+        pub round_id: u64,
+        pub matching_pool: Balance,
+        pub end_block: u64,
+    }
+
+    #[ink(event)]
+    pub struct GrantDonation {
+        #[ink(topic)]
+        pub round_id: u64,
+        #[ink(topic)]
+        pub project: AccountId,
+        pub contributor: AccountId,
+        pub amount: Balance,
+        /// Whether the donation counts toward matching
+        pub matched: bool,
+    }
+
+    #[ink(event)]
+    pub struct GrantRoundClosed {
+        #[ink(topic)]
+        pub round_id: u64,
+        pub matched: Balance,
+    }
+
+    #[ink(event)]
+    pub struct GrantClaimed {
+        #[ink(topic)]
+        pub round_id: u64,
+        #[ink(topic)]
+        pub project: AccountId,
+        pub amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FeedVoteCast {
+        #[ink(topic)]
+        pub epoch: u64,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub candidate: AccountId,
+        pub voting_power: u64,
+    }
+
+    #[ink(event)]
+    pub struct FeedElectionFinalized {
+        #[ink(topic)]
+        pub epoch: u64,
+        pub elected: Vec<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        pub delegator: AccountId,
+        pub from_delegate: Option<AccountId>,
+        pub to_delegate: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct DelegateRegistered {
+        #[ink(topic)]
+        pub delegate: AccountId,
+        pub name_hash: [u8; 32],
+        pub statement_uri: String,
+    }
+
+    #[ink(event)]
+    pub struct DelegateUnregistered {
+        #[ink(topic)]
+        pub delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CouncilBudgetUpdated {
+        #[ink(topic)]
+        pub council_id: u32,
+        pub allowance_per_period: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CouncilSpendApproved {
+        #[ink(topic)]
+        pub spend_id: u64,
+        #[ink(topic)]
+        pub approver: AccountId,
+        pub approvals: u32,
+    }
+
+    #[ink(event)]
+    pub struct CouncilSpendExecuted {
+        #[ink(topic)]
+        pub spend_id: u64,
+        #[ink(topic)]
+        pub council_id: u32,
+        pub recipient: AccountId,
+        pub amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ConvictionVoteCast {
+        #[ink(topic)]
+        pub proposal_id: u64,
         #[ink(topic)]
-        proposer: AccountId,
-        proposal_type: ProposalType,
-        description: String,
-        voting_end: u64,
+        pub voter: AccountId,
+        pub support: bool,
+        pub voting_power: u64,
+        pub conviction: u8,
+        pub unlock_block: u32,
     }
 
     #[ink(event)]
     pub struct VoteCast {
         #[ink(topic)]
-        proposal_id: u64,
+        pub proposal_id: u64,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub support: bool,
+        pub voting_power: u64,
+        pub reason: String,
+    }
+
+    #[ink(event)]
+    pub struct ProposalVetoed {
+        #[ink(topic)]
+        pub proposal_id: u64,
+        pub guardian: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeProposed {
+        pub contract: ExternalContract,
+        pub new_address: AccountId,
+        pub executable_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeExecuted {
+        pub contract: ExternalContract,
+        pub old_address: Option<AccountId>,
+        pub new_address: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeCancelled {
+        pub contract: ExternalContract,
+        #[ink(topic)]
+        pub by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GuardianUpdated {
+        pub old_guardian: Option<AccountId>,
+        pub new_guardian: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct BallotRejected {
+        #[ink(topic)]
+        pub voter: AccountId,
         #[ink(topic)]
-        voter: AccountId,
-        support: bool,
-        voting_power: u64,
-        reason: String,
+        pub proposal_id: u64,
+        pub error: Error,
     }
 
     #[ink(event)]
     pub struct ProposalExecuted {
         #[ink(topic)]
-        proposal_id: u64,
-        successful: bool,
+        pub proposal_id: u64,
+        pub successful: bool,
     }
 
     #[ink(event)]
     pub struct ProposalQueued {
         #[ink(topic)]
-        proposal_id: u64,
-        queued_at: u64,
-        execute_after: u64,
+        pub proposal_id: u64,
+        pub queued_at: u64,
+        pub execute_after: u64,
+    }
+
+    #[ink(event)]
+    pub struct GracePeriodUpdated {
+        pub old_seconds: u64,
+        pub new_seconds: u64,
     }
 
     #[ink(event)]
     pub struct TimelockUpdated {
-        old_seconds: u64,
-        new_seconds: u64,
+        pub old_seconds: u64,
+        pub new_seconds: u64,
     }
 
     /// Errors
@@ -102,6 +660,55 @@ pub mod governance {
         ExecutionFailed,
         NotQueued,
         TimelockNotElapsed,
+        ActionHashMismatch,
+        GracePeriodElapsed,
+        AlreadyQueued,
+        InvalidSignature,
+        InvalidNonce,
+        BatchTooLarge,
+        ProposalVetoed,
+        LockFailed,
+        NoPendingAddressChange,
+        NotInAddressBook,
+        TemplateNotFound,
+        InvalidTemplate,
+        ValueOutOfBounds,
+        WrongVotingScheme,
+        InvalidConviction,
+        ConvictionBelowThreshold,
+        TooManyConvictionVotes,
+        CouncilNotFound,
+        NotCouncilMember,
+        SpendNotFound,
+        SpendAlreadyExecuted,
+        AllowanceExceeded,
+        InvalidDelegate,
+        TooManyDelegators,
+        TooManyDelegates,
+        AlreadyRegistered,
+        NotRegistered,
+        StatementTooLong,
+        VotingPowerDelegated,
+        CandidateNotFound,
+        TooManyCandidates,
+        CandidateElected,
+        BondTransferFailed,
+        ElectionNotEnded,
+        ElectionEnded,
+        InvalidSeatCount,
+        InsufficientTreasury,
+        AlarmNotFound,
+        AlarmAlreadyProposed,
+        HealthSnapshotTooSoon,
+        GrantRoundNotFound,
+        GrantRoundEnded,
+        GrantRoundNotEnded,
+        TooManyProjects,
+        ProjectNotFound,
+        NameTooLong,
+        ZeroAmount,
+        DonationFailed,
+        AlreadyClaimed,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
@@ -117,7 +724,7 @@ pub mod governance {
             voting_duration_blocks: u64,
             quorum_percentage: u32,
         ) -> Self {
-            Self {
+            let instance = Self {
                 entered: false,
                 owner: Self::env().caller(),
                 token_address,
@@ -131,7 +738,51 @@ pub mod governance {
                 quorum_percentage,
                 timelock_seconds: 0,
                 queue_times: Mapping::default(),
-            }
+                action_hashes: Mapping::default(),
+                grace_period_seconds: DEFAULT_GRACE_PERIOD_SECONDS,
+                nonces: NonceRegistry::default(),
+                turnouts: Mapping::default(),
+                voted_counts: Mapping::default(),
+                executed_count: 0,
+                defeated_count: 0,
+                finalized_count: 0,
+                turnout_sum_bps: 0,
+                guardian: None,
+                address_timelock: AddressTimelock::default(),
+                templates: Mapping::default(),
+                voting_end_times: Mapping::default(),
+                vote_records: Mapping::default(),
+                vote_record_counts: Mapping::default(),
+                conviction_proposals: Mapping::default(),
+                conviction_votes: Mapping::default(),
+                council_budgets: Mapping::default(),
+                council_periods: Mapping::default(),
+                council_spends: Mapping::default(),
+                next_council_spend_id: 1,
+                delegations: Mapping::default(),
+                delegators: Mapping::default(),
+                delegate_profiles: Mapping::default(),
+                public_delegates: Vec::new(),
+                feed_seats: DEFAULT_FEED_SEATS,
+                feed_candidate_bond: DEFAULT_FEED_CANDIDATE_BOND,
+                feed_candidates: Mapping::default(),
+                feed_candidate_list: Vec::new(),
+                reserved_balance: 0,
+                feed_election_epoch: 0,
+                feed_epoch_end_block: u64::from(Self::env().block_number()).saturating_add(FEED_ELECTION_EPOCH_BLOCKS),
+                feed_votes: Mapping::default(),
+                feed_tallies: Mapping::default(),
+                elected_feeds: Vec::new(),
+                alarm_proposals: Mapping::default(),
+                last_health_snapshot: None,
+                grant_rounds: Mapping::default(),
+                next_grant_round_id: 1,
+                grant_projects: Mapping::default(),
+                grant_donations: Mapping::default(),
+                vetoed: Mapping::default(),
+            };
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
+            instance
         }
 
         /// Create a new proposal
@@ -141,21 +792,162 @@ pub mod governance {
             proposal_type: ProposalType,
             description: String,
         ) -> Result<u64> {
-            let caller = self.env().caller();
-            let caller_bytes = ink_account_to_bytes(caller);
+            let current_block = self.env().block_number();
+            let voting_end = (current_block as u64).saturating_add(self.voting_duration_blocks);
+            self.ensure_proposer_power()?;
+            self.insert_proposal(proposal_type, description, voting_end)
+        }
 
-            // Check voting power from PSP22 balance
-            let voting_power = self.get_voting_power(caller);
-            if (voting_power as u128) < self.min_voting_power {
-                return Err(Error::InsufficientVotingPower);
+        /// Create a proposal decided by conviction voting: no voting window, it can be queued
+        /// as soon as its net conviction exceeds the (decaying) threshold
+        #[ink(message)]
+        pub fn create_conviction_proposal(&mut self, proposal_type: ProposalType, description: String) -> Result<u64> {
+            self.ensure_proposer_power()?;
+            let proposal_id = self.insert_proposal(proposal_type, description, u64::MAX)?;
+            self.conviction_proposals.insert(proposal_id, &u64::from(self.env().block_number()));
+            Ok(proposal_id)
+        }
+
+        /// Vote on a conviction proposal, locking the caller's balance for
+        /// `CONVICTION_LOCK_PERIOD_BLOCKS * 2^(conviction-1)` blocks. The vote's weight accumulates
+        /// towards `conviction`x the locked power over `CONVICTION_RAMP_BLOCKS` and lapses with the
+        /// lock, after which the voter may vote again.
+        #[ink(message)]
+        pub fn vote_with_conviction(&mut self, proposal_id: u64, support: bool, conviction: u8) -> Result<()> {
+            if self.entered { return Err(Error::Unauthorized); }
+            if !self.conviction_proposals.contains(proposal_id) { return Err(Error::WrongVotingScheme); }
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.executed || self.queue_times.contains(proposal_id) { return Err(Error::AlreadyQueued); }
+            if conviction == 0 || conviction > MAX_CONVICTION { return Err(Error::InvalidConviction); }
+            let voter = self.env().caller();
+            let now = self.env().block_number();
+            let mut votes = self.conviction_votes.get(proposal_id).unwrap_or_default();
+            votes.retain(|vote| vote.unlock_block > now);
+            if votes.iter().any(|vote| vote.voter == voter) { return Err(Error::AlreadyVoted); }
+            if votes.len() >= MAX_CONVICTION_VOTES { return Err(Error::TooManyConvictionVotes); }
+            let voting_power = self.get_voting_power(voter);
+            if voting_power == 0 { return Err(Error::InsufficientVotingPower); }
+
+            self.entered = true;
+            let unlock_block = now.saturating_add(CONVICTION_LOCK_PERIOD_BLOCKS.saturating_mul(1 << (conviction - 1)));
+            if !self.with_token(|token| token.lock(voter, Balance::from(voting_power), unlock_block).is_ok()) {
+                self.entered = false;
+                return Err(Error::LockFailed);
+            }
+            if !self.votes.contains((proposal_id, voter)) {
+                self.votes.insert((proposal_id, voter), &true);
+                let voted = self.voted_counts.get(voter).unwrap_or(0);
+                self.voted_counts.insert(voter, &voted.saturating_add(1));
+            }
+            self.record_vote(proposal_id, VoteRecord { voter, support, voting_power, conviction });
+            votes.push(ConvictionVote { voter, support, voting_power, conviction, cast_block: now, unlock_block });
+
+            // Raw totals cover the votes that still count
+            let (yes_votes, no_votes) = votes.iter().fold((0u64, 0u64), |(yes, no), vote| {
+                if vote.support { (yes.saturating_add(vote.voting_power), no) } else { (yes, no.saturating_add(vote.voting_power)) }
+            });
+            proposal.yes_votes = yes_votes;
+            proposal.no_votes = no_votes;
+            proposal.total_voting_power = yes_votes.saturating_add(no_votes);
+            self.conviction_votes.insert(proposal_id, &votes);
+            self.proposals.insert(proposal_id, &proposal);
+            self.env().emit_event(ConvictionVoteCast { proposal_id, voter, support, voting_power, conviction, unlock_block });
+            self.entered = false;
+            Ok(())
+        }
+
+        /// Conviction-weighted tally of a conviction proposal at the current block
+        #[ink(message)]
+        pub fn get_conviction_tally(&self, proposal_id: u64) -> Option<ConvictionTally> {
+            let created_block = self.conviction_proposals.get(proposal_id)?;
+            let now = self.env().block_number();
+            let mut tally = ConvictionTally { created_block, ..Default::default() };
+            for vote in self.conviction_votes.get(proposal_id).unwrap_or_default() {
+                let weight = Self::conviction_weight(&vote, now);
+                if vote.support {
+                    tally.ayes = tally.ayes.saturating_add(weight);
+                } else {
+                    tally.nays = tally.nays.saturating_add(weight);
+                }
+            }
+            Some(tally)
+        }
+
+        /// Conviction votes cast on a proposal, including any whose lock has since ended
+        #[ink(message)]
+        pub fn get_conviction_votes(&self, proposal_id: u64) -> Vec<ConvictionVote> {
+            self.conviction_votes.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Weight of a conviction vote at block `now`: rises linearly to `voting_power * conviction`
+        /// over `CONVICTION_RAMP_BLOCKS` and is zero once the vote's lock has ended
+        fn conviction_weight(vote: &ConvictionVote, now: u32) -> Balance {
+            if now >= vote.unlock_block {
+                return 0;
             }
+            let full = Balance::from(vote.voting_power).saturating_mul(Balance::from(vote.conviction));
+            let elapsed = now.saturating_sub(vote.cast_block).min(CONVICTION_RAMP_BLOCKS);
+            full.saturating_mul(Balance::from(elapsed)) / Balance::from(CONVICTION_RAMP_BLOCKS)
+        }
+
+        /// Net conviction a conviction proposal currently has to exceed: the start threshold
+        /// decaying linearly to the floor over `CONVICTION_DECAY_BLOCKS`
+        #[ink(message)]
+        pub fn get_conviction_threshold(&self, proposal_id: u64) -> Option<Balance> {
+            let created_block = self.conviction_proposals.get(proposal_id)?;
+            let elapsed = u64::from(self.env().block_number())
+                .saturating_sub(created_block)
+                .min(CONVICTION_DECAY_BLOCKS);
+            let decay = (CONVICTION_THRESHOLD_START_BPS - CONVICTION_THRESHOLD_FLOOR_BPS)
+                .saturating_mul(elapsed) / CONVICTION_DECAY_BLOCKS;
+            let bps = CONVICTION_THRESHOLD_START_BPS.saturating_sub(decay);
+            Some(Balance::from(self.get_total_voting_power()).saturating_mul(Balance::from(bps)) / 10_000)
+        }
+
+        /// Whether a conviction proposal's net conviction exceeds its current threshold
+        fn conviction_passed(&self, proposal_id: u64) -> bool {
+            let (Some(tally), Some(threshold)) = (self.get_conviction_tally(proposal_id), self.get_conviction_threshold(proposal_id)) else {
+                return false;
+            };
+            tally.ayes.saturating_sub(tally.nays) > threshold
+        }
 
+        /// Turn a GridService alarm into its pre-formed proposal (anyone; once per alarm).
+        /// The action is derived from the alarm payload, so proposers cannot alter it.
+        #[ink(message)]
+        pub fn create_alarm_proposal(&mut self, alarm_id: u64) -> Result<u64> {
+            if self.alarm_proposals.contains(alarm_id) { return Err(Error::AlarmAlreadyProposed); }
+            let alarm = Grid::connect(self.grid_service_address)
+                .and_then(|grid| grid.get_alarm(alarm_id))
+                .ok_or(Error::AlarmNotFound)?;
             let current_block = self.env().block_number();
             let voting_end = (current_block as u64).saturating_add(self.voting_duration_blocks);
+            let proposal_id = self.insert_proposal(alarm.kind.proposal(), format!("Response to protocol alarm #{}", alarm_id), voting_end)?;
+            self.alarm_proposals.insert(alarm_id, &proposal_id);
+            Ok(proposal_id)
+        }
+
+        /// Proposal created for an alarm, if any
+        #[ink(message)]
+        pub fn get_alarm_proposal(&self, alarm_id: u64) -> Option<u64> {
+            self.alarm_proposals.get(alarm_id)
+        }
+
+        /// Check voting power from PSP22 balance
+        fn ensure_proposer_power(&self) -> Result<()> {
+            let voting_power = self.get_voting_power(self.env().caller());
+            if (voting_power as u128) < self.min_voting_power {
+                return Err(Error::InsufficientVotingPower);
+            }
+            Ok(())
+        }
+
+        fn insert_proposal(&mut self, proposal_type: ProposalType, description: String, voting_end: u64) -> Result<u64> {
+            let caller = self.env().caller();
             let proposal_id = self.next_proposal_id;
 
             let proposal = Proposal {
-                proposer: caller_bytes,
+                proposer: caller,
                 proposal_type: proposal_type.clone(),
                 description: description.clone(),
                 yes_votes: 0,
@@ -167,7 +959,14 @@ pub mod governance {
                 active: true,
             };
 
+            let action_hash = self.compute_action_hash(core::slice::from_ref(&proposal.proposal_type), &proposal.description);
             self.proposals.insert(proposal_id, &proposal);
+            if voting_end != u64::MAX {
+                let blocks = voting_end.saturating_sub(u64::from(self.env().block_number()));
+                let end_time = self.env().block_timestamp().saturating_add(blocks.saturating_mul(EXPECTED_BLOCK_TIME_MS));
+                self.voting_end_times.insert(proposal_id, &end_time);
+            }
+            self.action_hashes.insert(proposal_id, &action_hash);
             self.next_proposal_id = self.next_proposal_id.saturating_add(1);
 
             self.env().emit_event(ProposalCreated {
@@ -176,45 +975,153 @@ pub mod governance {
                 proposal_type,
                 description,
                 voting_end,
+                action_hash,
             });
 
             Ok(proposal_id)
         }
 
+        /// Create a proposal from a template, filling in `value` (same voting power rules as `create_proposal`)
+        #[ink(message)]
+        pub fn create_proposal_from_template(&mut self, template_id: u32, value: Balance) -> Result<u64> {
+            let template = self.templates.get(template_id).ok_or(Error::TemplateNotFound)?;
+            if value < template.min_value || value > template.max_value {
+                return Err(Error::ValueOutOfBounds);
+            }
+            let action = template.action.with_value(value).ok_or(Error::InvalidTemplate)?;
+            self.create_proposal(action, format!("{}: {}", template.name, value))
+        }
+
+        /// Add, replace or (with None) remove a proposal template (owner only)
+        #[ink(message)]
+        pub fn set_proposal_template(&mut self, template_id: u32, template: Option<ProposalTemplate>) -> Result<()> {
+            if self.env().caller() != self.owner { return Err(Error::Unauthorized); }
+            match template {
+                Some(template) => {
+                    if template.min_value > template.max_value || template.action.with_value(template.max_value).is_none() {
+                        return Err(Error::InvalidTemplate);
+                    }
+                    self.templates.insert(template_id, &template);
+                }
+                None => self.templates.remove(template_id),
+            }
+            Ok(())
+        }
+
+        /// Get a proposal template
+        #[ink(message)]
+        pub fn get_proposal_template(&self, template_id: u32) -> Option<ProposalTemplate> {
+            self.templates.get(template_id)
+        }
+
         /// Vote on a proposal
         #[ink(message)]
         pub fn vote(&mut self, proposal_id: u64, support: bool, reason: String) -> Result<()> {
-            if self.entered { self.entered = false; return Err(Error::Unauthorized); }
+            if self.entered { return Err(Error::Unauthorized); }
             self.entered = true;
-            
             let caller = self.env().caller();
-            let caller_bytes = ink_account_to_bytes(caller);
+            let result = self.cast_vote(caller, proposal_id, support, reason);
+            self.entered = false;
+            result
+        }
 
-            let mut proposal = self.proposals.get(proposal_id)
-                .ok_or(Error::ProposalNotFound)?;
+        /// Submit off-chain signed ballots in batch (any relayer). Invalid ballots are skipped;
+        /// returns the number of votes counted.
+        #[ink(message)]
+        pub fn cast_votes_by_sig(&mut self, ballots: Vec<SignedBallot>) -> Result<u32> {
+            if self.entered { return Err(Error::Unauthorized); }
+            if ballots.len() > MAX_BALLOTS_PER_BATCH { return Err(Error::BatchTooLarge); }
+            self.entered = true;
 
-            // Check if proposal is still active
-            let current_block = self.env().block_number();
-            if (current_block as u64) > proposal.voting_end { 
-                self.entered = false;
-                return Err(Error::ProposalExpired); 
+            let mut counted: u32 = 0;
+            for ballot in ballots {
+                if ballot.nonce != self.nonces.current(ballot.voter, NonceDomain::Vote) {
+                    self.env().emit_event(BallotRejected { voter: ballot.voter, proposal_id: ballot.proposal_id, error: Error::InvalidNonce });
+                    continue;
+                }
+                let message = self.ballot_hash(ballot.proposal_id, ballot.support, ballot.nonce);
+                if !verify_signature(ballot.voter, message, &ballot.signature) {
+                    self.env().emit_event(BallotRejected { voter: ballot.voter, proposal_id: ballot.proposal_id, error: Error::InvalidSignature });
+                    continue;
+                }
+                // The nonce is consumed once the signature checks out, even if the vote itself fails
+                self.nonces.use_nonce(ballot.voter, NonceDomain::Vote, ballot.nonce);
+                match self.cast_vote(ballot.voter, ballot.proposal_id, ballot.support, String::new()) {
+                    Ok(()) => counted = counted.saturating_add(1),
+                    Err(error) => self.env().emit_event(BallotRejected { voter: ballot.voter, proposal_id: ballot.proposal_id, error }),
+                }
             }
 
-            // Check if already voted
-            if self.votes.contains((proposal_id, caller_bytes)) { 
-                self.entered = false;
+            self.entered = false;
+            Ok(counted)
+        }
+
+        /// Next ballot nonce expected for a voter
+        #[ink(message)]
+        pub fn get_vote_nonce(&self, voter: AccountId) -> u64 {
+            self.nonces.current(voter, NonceDomain::Vote)
+        }
+
+        /// Message a voter signs for `cast_votes_by_sig`:
+        /// `signed_message_hash(Vote, governance_address, nonce, (proposal_id, support))`
+        #[ink(message)]
+        pub fn ballot_hash(&self, proposal_id: u64, support: bool, nonce: u64) -> [u8; 32] {
+            signed_message_hash(NonceDomain::Vote, self.env().account_id(), nonce, &(proposal_id, support))
+        }
+
+        fn cast_vote(&mut self, voter: AccountId, proposal_id: u64, support: bool, reason: String) -> Result<()> {
+            if self.conviction_proposals.contains(proposal_id) {
+                return Err(Error::WrongVotingScheme);
+            }
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            // Check if proposal is still active
+            if self.voting_clock(proposal_id, proposal.voting_end) == core::cmp::Ordering::Greater {
+                return Err(Error::ProposalExpired); 
+            }
+
+            // Check if already voted
+            if self.votes.contains((proposal_id, voter)) { 
                 return Err(Error::AlreadyVoted); 
             }
 
-            // Get voting power (simplified)
-            let voting_power = self.get_voting_power(caller);
+            if self.delegations.contains(voter) {
+                return Err(Error::VotingPowerDelegated);
+            }
+
+            // Own balance plus that of delegators who have not voted on this proposal themselves
+            let own_power = self.get_voting_power(voter);
+            let delegated: Vec<(AccountId, u64)> = self.get_delegators(voter)
+                .into_iter()
+                .filter(|delegator| !self.votes.contains((proposal_id, *delegator)))
+                .map(|delegator| (delegator, self.get_voting_power(delegator)))
+                .filter(|(_, power)| *power > 0)
+                .collect();
+            let voting_power = delegated.iter().fold(own_power, |total, (_, power)| total.saturating_add(*power));
             if voting_power == 0 { 
-                self.entered = false;
                 return Err(Error::InsufficientVotingPower); 
             }
 
+            // Keep the voted balances from moving until voting ends
+            #[allow(clippy::cast_possible_truncation)]
+            let until_block = proposal.voting_end.saturating_add(1).min(u64::from(u32::MAX)) as u32;
+            if !self.with_token(|token| token.lock(voter, Balance::from(own_power), until_block).is_ok()) {
+                return Err(Error::LockFailed);
+            }
+            for (delegator, power) in &delegated {
+                if !self.with_token(|token| token.lock(*delegator, Balance::from(*power), until_block).is_ok()) {
+                    return Err(Error::LockFailed);
+                }
+                // A delegator's power counts once per proposal
+                self.votes.insert((proposal_id, *delegator), &true);
+            }
+
             // Record vote
-            self.votes.insert((proposal_id, caller_bytes), &true);
+            self.votes.insert((proposal_id, voter), &true);
+            let voted = self.voted_counts.get(voter).unwrap_or(0);
+            self.voted_counts.insert(voter, &voted.saturating_add(1));
+            self.record_vote(proposal_id, VoteRecord { voter, support, voting_power, conviction: 0 });
 
             // Update proposal votes
             if support {
@@ -228,34 +1135,41 @@ pub mod governance {
 
             self.env().emit_event(VoteCast {
                 proposal_id,
-                voter: caller,
+                voter,
                 support,
                 voting_power,
                 reason,
             });
-            
-            self.entered = false;
             Ok(())
         }
 
         /// Queue a proposal for execution after voting period; starts the timelock countdown
         #[ink(message)]
         pub fn queue_proposal(&mut self, proposal_id: u64) -> Result<()> {
-            if self.entered { self.entered = false; return Err(Error::Unauthorized); }
+            if self.entered { return Err(Error::Unauthorized); }
             self.entered = true;
 
             let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
 
-            // Only after voting ends and not executed
-            let current_block = self.env().block_number();
-            if (current_block as u64) < proposal.voting_end { 
+            // Only after voting ends (conviction proposals: once over the threshold) and not executed
+            if self.conviction_proposals.contains(proposal_id) {
+                if !self.conviction_passed(proposal_id) {
+                    self.entered = false;
+                    return Err(Error::ConvictionBelowThreshold);
+                }
+            } else if self.voting_clock(proposal_id, proposal.voting_end) == core::cmp::Ordering::Less {
                 self.entered = false;
-                return Err(Error::ProposalNotExpired); 
+                return Err(Error::ProposalNotExpired);
             }
             if proposal.executed { 
                 self.entered = false;
                 return Err(Error::ProposalAlreadyExecuted); 
             }
+            // Re-queueing would reset the grace period of an expired proposal
+            if self.queue_times.contains(proposal_id) {
+                self.entered = false;
+                return Err(Error::AlreadyQueued);
+            }
 
             // Store queue time
             let now = self.env().block_timestamp();
@@ -278,20 +1192,310 @@ pub mod governance {
             Ok(())
         }
 
-        /// Execute a proposal
+        /// Veto a queued, not yet executed proposal (guardian only)
+        #[ink(message)]
+        pub fn veto_proposal(&mut self, proposal_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+            if Some(caller) != self.guardian { return Err(Error::Unauthorized); }
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.executed { return Err(Error::ProposalAlreadyExecuted); }
+            if !self.queue_times.contains(proposal_id) { return Err(Error::NotQueued); }
+            self.vetoed.insert(proposal_id, &true);
+            self.env().emit_event(ProposalVetoed { proposal_id, guardian: caller });
+            Ok(())
+        }
+
+        /// Set the initial guardian (owner only); later rotation goes through `ProposalType::SetGuardian`
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<()> {
+            if self.env().caller() != self.owner || self.guardian.is_some() { return Err(Error::Unauthorized); }
+            self.set_guardian_internal(guardian);
+            Ok(())
+        }
+
+        /// Get the guardian account
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<AccountId> {
+            self.guardian
+        }
+
+        /// Logic version of this contract, bumped on every upgrade
         #[ink(message)]
-        pub fn execute_proposal(&mut self, proposal_id: u64) -> Result<()> {
-            if self.entered { self.entered = false; return Err(Error::Unauthorized); }
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Dry-run the roles governance needs on the other contracts and report readiness
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            let this = self.env().account_id();
+            if let Some(token) = Token::connect(self.token_address) {
+                report.check("token_locker", token.is_locker(this));
+            }
+            if let Some(registry) = Registry::connect(self.registry_address) {
+                report.check("registry_governance", registry.get_governance_address() == Some(this));
+            }
+            if let Some(grid) = Grid::connect(self.grid_service_address) {
+                report.check("grid_governance", grid.get_governance_address() == this);
+            }
+            report
+        }
+
+        /// Queue a new token, registry or grid service address, executable after
+        /// `ADDRESS_CHANGE_DELAY_MS` (owner only)
+        #[ink(message)]
+        pub fn propose_address_change(&mut self, contract: ExternalContract, new_address: AccountId) -> Result<u64> {
+            let caller = self.env().caller();
+            if caller != self.owner { return Err(Error::Unauthorized); }
+            if contract == ExternalContract::Governance { return Err(Error::NotInAddressBook); }
+            let executable_at = self.address_timelock.propose(contract, new_address, caller, self.env().block_timestamp());
+            self.env().emit_event(AddressChangeProposed { contract, new_address, executable_at });
+            Ok(executable_at)
+        }
+
+        /// Apply a pending address change once its delay has passed (anyone)
+        #[ink(message)]
+        pub fn execute_address_change(&mut self, contract: ExternalContract) -> Result<()> {
+            let new_address = self.address_timelock
+                .take_ready(contract, self.env().block_timestamp())
+                .map_err(|e| match e {
+                    AddressChangeError::NoPendingChange => Error::NoPendingAddressChange,
+                    AddressChangeError::TimelockActive => Error::TimelockNotElapsed,
+                })?;
+            let slot = match contract {
+                ExternalContract::Token => &mut self.token_address,
+                ExternalContract::Registry => &mut self.registry_address,
+                ExternalContract::GridService => &mut self.grid_service_address,
+                ExternalContract::Governance => return Err(Error::NotInAddressBook),
+            };
+            let old_address = Some(core::mem::replace(slot, new_address));
+            self.env().emit_event(AddressChangeExecuted { contract, old_address, new_address });
+            Ok(())
+        }
+
+        /// Drop a pending address change (guardian or owner)
+        #[ink(message)]
+        pub fn cancel_address_change(&mut self, contract: ExternalContract) -> Result<()> {
+            let caller = self.env().caller();
+            if Some(caller) != self.guardian && caller != self.owner { return Err(Error::Unauthorized); }
+            if !self.address_timelock.cancel(contract) { return Err(Error::NoPendingAddressChange); }
+            self.env().emit_event(AddressChangeCancelled { contract, by: caller });
+            Ok(())
+        }
+
+        /// Address change waiting out its delay
+        #[ink(message)]
+        pub fn get_pending_address_change(&self, contract: ExternalContract) -> Option<PendingAddressChange> {
+            self.address_timelock.pending(contract)
+        }
+
+        /// External contracts this contract calls
+        #[ink(message)]
+        pub fn get_address_book(&self) -> Vec<(ExternalContract, AccountId)> {
+            vec![
+                (ExternalContract::Token, self.token_address),
+                (ExternalContract::Registry, self.registry_address),
+                (ExternalContract::GridService, self.grid_service_address),
+            ]
+        }
+
+        fn set_guardian_internal(&mut self, guardian: Option<AccountId>) {
+            let old_guardian = self.guardian;
+            self.guardian = guardian;
+            self.env().emit_event(GuardianUpdated { old_guardian, new_guardian: guardian });
+        }
+
+        /// Update the execution grace period (owner only)
+        #[ink(message)]
+        pub fn set_grace_period_seconds(&mut self, seconds: u64) -> Result<()> {
+            if self.env().caller() != self.owner { return Err(Error::Unauthorized); }
+            if seconds == 0 { return Err(Error::InvalidDuration); }
+            let old = self.grace_period_seconds;
+            self.grace_period_seconds = seconds;
+            self.env().emit_event(GracePeriodUpdated { old_seconds: old, new_seconds: seconds });
+            Ok(())
+        }
+
+        /// Where now stands relative to a proposal's voting deadline: past it once either the
+        /// block number or the timestamp deadline is passed, at it when either is reached
+        fn voting_clock(&self, proposal_id: u64, voting_end: u64) -> core::cmp::Ordering {
+            let by_block = u64::from(self.env().block_number()).cmp(&voting_end);
+            match self.voting_end_times.get(proposal_id) {
+                Some(end_time) => by_block.max(self.env().block_timestamp().cmp(&end_time)),
+                None => by_block,
+            }
+        }
+
+        /// Timestamp at which a proposal's voting closes at the latest (None for conviction proposals)
+        #[ink(message)]
+        pub fn get_voting_end_time(&self, proposal_id: u64) -> Option<u64> {
+            self.voting_end_times.get(proposal_id)
+        }
+
+        /// Durations of governance deadlines and the clock each is measured on
+        #[ink(message)]
+        pub fn get_time_config(&self) -> TimeConfig {
+            TimeConfig {
+                expected_block_time_ms: EXPECTED_BLOCK_TIME_MS,
+                voting_duration_blocks: self.voting_duration_blocks,
+                voting_duration_ms: self.voting_duration_blocks.saturating_mul(EXPECTED_BLOCK_TIME_MS),
+                timelock_ms: self.timelock_seconds.saturating_mul(1000),
+                grace_period_ms: self.grace_period_seconds.saturating_mul(1000),
+                address_change_delay_ms: ADDRESS_CHANGE_DELAY_MS,
+                conviction_lock_period_blocks: CONVICTION_LOCK_PERIOD_BLOCKS,
+                feed_election_epoch_blocks: FEED_ELECTION_EPOCH_BLOCKS,
+            }
+        }
+
+        /// Get (timelock, grace period) in seconds
+        #[ink(message)]
+        pub fn get_execution_window(&self) -> (u64, u64) {
+            (self.timelock_seconds, self.grace_period_seconds)
+        }
+
+        fn record_vote(&mut self, proposal_id: u64, record: VoteRecord) {
+            let index = self.vote_record_counts.get(proposal_id).unwrap_or(0);
+            self.vote_records.insert((proposal_id, index), &record);
+            self.vote_record_counts.insert(proposal_id, &index.saturating_add(1));
+        }
+
+        /// Export proposals with their states, `limit` at a time (at most `MAX_PAGE_SIZE`);
+        /// start at cursor 0 and continue from `next_cursor`
+        #[ink(message)]
+        pub fn export_proposals(&self, cursor: u64, limit: u32) -> Page<ExportedProposal> {
+            Page::collect(cursor, limit, self.next_proposal_id, |proposal_id| {
+                let proposal = self.proposals.get(proposal_id)?;
+                Some(ExportedProposal { proposal_id, proposal, state: self.get_proposal_state(proposal_id) })
+            })
+        }
+
+        /// Export a proposal's ballots in casting order, `limit` at a time (at most `MAX_PAGE_SIZE`)
+        #[ink(message)]
+        pub fn export_votes(&self, proposal_id: u64, cursor: u64, limit: u32) -> Page<VoteRecord> {
+            let count = self.vote_record_counts.get(proposal_id).unwrap_or(0);
+            Page::collect(cursor, limit, u64::from(count), |index| {
+                self.vote_records.get((proposal_id, u32::try_from(index).ok()?))
+            })
+        }
+
+        /// Get the lifecycle state of a proposal
+        #[ink(message)]
+        pub fn get_proposal_state(&self, proposal_id: u64) -> Option<ProposalState> {
+            let proposal = self.proposals.get(proposal_id)?;
+            if proposal.executed {
+                return Some(ProposalState::Executed);
+            }
+            if self.vetoed.contains(proposal_id) {
+                return Some(ProposalState::Vetoed);
+            }
+            if self.conviction_proposals.contains(proposal_id) {
+                if !self.queue_times.contains(proposal_id) && !self.conviction_passed(proposal_id) {
+                    return Some(ProposalState::Active);
+                }
+            } else {
+                if self.voting_clock(proposal_id, proposal.voting_end) == core::cmp::Ordering::Less {
+                    return Some(ProposalState::Active);
+                }
+                let total_supply = self.get_total_voting_power();
+                let quorum_required = total_supply.saturating_mul(self.quorum_percentage as u64).saturating_div(100);
+                if proposal.yes_votes <= proposal.no_votes || proposal.total_voting_power < quorum_required {
+                    return Some(ProposalState::Defeated);
+                }
+            }
+            let state = match self.queue_times.get(proposal_id) {
+                None => ProposalState::Succeeded,
+                Some(queued_at) => {
+                    let deadline = queued_at
+                        .saturating_add(self.timelock_seconds.saturating_mul(1000))
+                        .saturating_add(self.grace_period_seconds.saturating_mul(1000));
+                    if self.env().block_timestamp() > deadline { ProposalState::Expired } else { ProposalState::Queued }
+                }
+            };
+            Some(state)
+        }
+
+        /// Dry-run a proposal's action against current state without changing anything:
+        /// the values it would change with their predicted new values, and whether it
+        /// would apply. Queried before voting; nothing is executed or stored.
+        #[ink(message)]
+        pub fn simulate_proposal(&self, proposal_id: u64) -> Result<ProposalSimulation> {
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            let state = self.get_proposal_state(proposal_id).ok_or(Error::ProposalNotFound)?;
+            // A queued conviction proposal has passed; before that its current conviction decides
+            let passing = if self.conviction_proposals.contains(proposal_id) {
+                self.queue_times.contains(proposal_id) || self.conviction_passed(proposal_id)
+            } else {
+                let quorum_required = self.get_total_voting_power().saturating_mul(u64::from(self.quorum_percentage)) / 100;
+                proposal.yes_votes > proposal.no_votes && proposal.total_voting_power >= quorum_required
+            };
+
+            let change = |value, current, predicted| SimulatedChange { value, current, predicted };
+            let this = self.env().account_id();
+            let balance_of = |account| Token::connect(self.token_address).map_or(0, |token| token.balance_of(account));
+            let (action_succeeds, changes) = match proposal.proposal_type {
+                ProposalType::UpdateMinStake(new_min) => {
+                    let current = Registry::connect(self.registry_address).map_or(0, |registry| registry.get_min_stake());
+                    (None, vec![change(SimulatedValue::MinStake, current, new_min)])
+                }
+                ProposalType::UpdateCompensationRate(new_rate) => {
+                    let current = Grid::connect(self.grid_service_address).map_or(0, |grid| grid.get_default_compensation_rate());
+                    (None, vec![change(SimulatedValue::DefaultCompensationRate, current, new_rate)])
+                }
+                ProposalType::UpdateReputationThreshold(threshold) => {
+                    let current = Registry::connect(self.registry_address).map_or(0, |registry| registry.get_reputation_threshold());
+                    (None, vec![change(SimulatedValue::ReputationThreshold, Balance::from(current), Balance::from(threshold))])
+                }
+                ProposalType::TreasurySpend(to, amount) => {
+                    let treasury = balance_of(this);
+                    let recipient = balance_of(to);
+                    (Some(self.treasury_covers(amount)), vec![
+                        change(SimulatedValue::TreasuryBalance, treasury, treasury.saturating_sub(amount)),
+                        change(SimulatedValue::BalanceOf(to), recipient, recipient.saturating_add(amount)),
+                    ])
+                }
+                ProposalType::SetCouncilBudget(council_id, budget) => {
+                    let current = self.get_council_remaining_allowance(council_id);
+                    let valid = budget.as_ref().is_none_or(Self::council_budget_valid);
+                    // A new budget starts a fresh period with its full allowance
+                    let predicted = budget.map_or(0, |budget| budget.allowance_per_period);
+                    (Some(valid), vec![change(SimulatedValue::CouncilRemainingAllowance(council_id), current, predicted)])
+                }
+                ProposalType::ClawbackCouncilBudget(council_id, amount) => {
+                    let current = self.get_council_remaining_allowance(council_id);
+                    (
+                        Some(self.council_budgets.contains(council_id)),
+                        vec![change(SimulatedValue::CouncilRemainingAllowance(council_id), current, current.saturating_sub(amount))],
+                    )
+                }
+                ProposalType::OpenGrantRound(params) => (Some(self.grant_round_fundable(&params)), Vec::new()),
+                ProposalType::SystemUpgrade | ProposalType::Other(_) => (Some(true), Vec::new()),
+                // Reward configs, feed rate limits and emergency mint budgets are validated by the grid
+                // or token, which expose no getters for them, and their settings are not single
+                // balances a `SimulatedChange` could carry; left to a dry-run of `execute_proposal`
+                ProposalType::SetRewardConfig(_)
+                | ProposalType::SetFeedRateLimit(..)
+                | ProposalType::SetEmergencyMintBudget(..) => (None, Vec::new()),
+                _ => (None, Vec::new()),
+            };
+            Ok(ProposalSimulation { state, passing, action_succeeds, changes })
+        }
+
+        /// Execute a proposal. The caller passes the actions it expects to run (as returned by
+        /// `get_proposal_actions`); they only run if they hash to the value committed at creation.
+        #[ink(message)]
+        pub fn execute_proposal(&mut self, proposal_id: u64, actions: Vec<ProposalType>) -> Result<()> {
+            if self.entered { return Err(Error::Unauthorized); }
             self.entered = true;
             
             let mut proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
 
             // Check if proposal has expired
-            let current_block = self.env().block_number();
-            if (current_block as u64) < proposal.voting_end { 
+            let conviction = self.conviction_proposals.contains(proposal_id);
+            if !conviction && self.voting_clock(proposal_id, proposal.voting_end) == core::cmp::Ordering::Less {
                 self.entered = false;
-                return Err(Error::ProposalNotExpired); 
+                return Err(Error::ProposalNotExpired);
             }
 
             // Check if already executed
@@ -299,12 +1503,24 @@ pub mod governance {
                 self.entered = false;
                 return Err(Error::ProposalAlreadyExecuted); 
             }
+            if self.vetoed.contains(proposal_id) {
+                self.entered = false;
+                return Err(Error::ProposalVetoed);
+            }
 
             // Check quorum
             let total_supply = self.get_total_voting_power();
             let quorum_required = total_supply.saturating_mul(self.quorum_percentage as u64).saturating_div(100);
             
-            let passed = proposal.yes_votes > proposal.no_votes && proposal.total_voting_power >= quorum_required;
+            // Conviction proposals were checked against their threshold when queued
+            let passed = if conviction {
+                self.queue_times.contains(proposal_id)
+            } else {
+                proposal.yes_votes > proposal.no_votes && proposal.total_voting_power >= quorum_required
+            };
+
+            // Snapshot turnout the first time the outcome is settled
+            self.record_turnout(proposal_id, proposal.total_voting_power, total_supply, passed);
             
             // Require proposal queued and respect timelock if passed
             if passed {
@@ -319,59 +1535,27 @@ pub mod governance {
                     self.entered = false;
                     return Err(Error::TimelockNotElapsed); 
                 }
+                if now > execute_after.saturating_add(self.grace_period_seconds.saturating_mul(1000)) {
+                    self.entered = false;
+                    return Err(Error::GracePeriodElapsed);
+                }
             }
             
-            // If passed, attempt to execute side effects
-            let mut success = passed;
-            if passed {
-                #[cfg(not(test))]
-                {
-                    match proposal.proposal_type.clone() {
-                        ProposalType::UpdateMinStake(new_min) => {
-                            let mut registry = ResourceRegistryRef::from_account_id(self.registry_address);
-                            if registry.update_min_stake(new_min).is_err() { success = false; }
-                        }
-                        ProposalType::UpdateCompensationRate(new_rate) => {
-                            let mut grid = GridServiceRef::from_account_id(self.grid_service_address);
-                            if grid.update_default_compensation_rate(new_rate).is_err() { success = false; }
-                        }
-                        ProposalType::UpdateReputationThreshold(threshold) => {
-                            let mut registry = ResourceRegistryRef::from_account_id(self.registry_address);
-                            if registry.update_reputation_threshold(threshold).is_err() { success = false; }
-                        }
-                        ProposalType::TreasurySpend(to_bytes, amount) => {
-                            let to = ink::primitives::AccountId::from(to_bytes);
-                            // Use token transfer from this contract's balance
-                            let mut token = PowergridTokenRef::from_account_id(self.token_address);
-                            if token.transfer(to, amount, Vec::new()).is_err() { success = false; }
-                        }
-                        ProposalType::SetTokenMinter(account_bytes, is_minter) => {
-                            let account = ink::primitives::AccountId::from(account_bytes);
-                            let mut token = PowergridTokenRef::from_account_id(self.token_address);
-                            let r = if is_minter { token.add_minter(account) } else { token.remove_minter(account) };
-                            if r.is_err() { success = false; }
-                        }
-                        ProposalType::SetRegistryAuthorizedCaller(account_bytes, is_auth) => {
-                            let account = ink::primitives::AccountId::from(account_bytes);
-                            let mut registry = ResourceRegistryRef::from_account_id(self.registry_address);
-                            let r = if is_auth { registry.add_authorized_caller(account) } else { registry.remove_authorized_caller(account) };
-                            if r.is_err() { success = false; }
-                        }
-                        ProposalType::SetGridAuthorizedCaller(account_bytes, is_auth) => {
-                            let account = ink::primitives::AccountId::from(account_bytes);
-                            let mut grid = GridServiceRef::from_account_id(self.grid_service_address);
-                            let r = if is_auth { grid.add_authorized_caller(account) } else { grid.remove_authorized_caller(account) };
-                            if r.is_err() { success = false; }
-                        }
-                        ProposalType::SystemUpgrade | ProposalType::Other(_) => {
-                            success = true;
-                        }
-                    }
-                }
+            // What runs must be exactly what voters saw at creation
+            if passed && self.action_hashes.get(proposal_id) != Some(self.compute_action_hash(&actions, &proposal.description)) {
+                self.entered = false;
+                return Err(Error::ActionHashMismatch);
             }
 
+            // If passed, attempt to execute side effects
+            let success = passed && actions.into_iter().all(|action| self.apply_proposal(action));
+
             // Mark executed only on success; if failed, keep it active for potential retry/fix
-            if passed && success { proposal.executed = true; proposal.active = false; }
+            if passed && success {
+                proposal.executed = true;
+                proposal.active = false;
+                self.executed_count = self.executed_count.saturating_add(1);
+            }
             if passed && !success { proposal.active = true; }
             self.proposals.insert(proposal_id, &proposal);
 
@@ -390,11 +1574,555 @@ pub mod governance {
             self.proposals.get(proposal_id)
         }
 
+        /// Get the actions a proposal will run, in execution order
+        #[ink(message)]
+        pub fn get_proposal_actions(&self, proposal_id: u64) -> Option<Vec<ProposalType>> {
+            self.proposals.get(proposal_id).map(|p| vec![p.proposal_type])
+        }
+
+        /// Get the action hash committed at proposal creation
+        #[ink(message)]
+        pub fn get_proposal_action_hash(&self, proposal_id: u64) -> Option<[u8; 32]> {
+            self.action_hashes.get(proposal_id)
+        }
+
+        /// blake2_256 over the encoded actions and description
+        fn compute_action_hash(&self, actions: &[ProposalType], description: &str) -> [u8; 32] {
+            self.env().hash_encoded::<ink::env::hash::Blake2x256, _>(&(actions, description))
+        }
+
+        /// Record a proposal's turnout and outcome once; later finalizations are ignored
+        fn record_turnout(&mut self, proposal_id: u64, voting_power: u64, total_supply: u64, passed: bool) {
+            if self.turnouts.contains(proposal_id) {
+                return;
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let turnout_bps = if total_supply == 0 {
+                0
+            } else {
+                (u128::from(voting_power).saturating_mul(10_000) / u128::from(total_supply)).min(10_000) as u32
+            };
+            self.turnouts.insert(proposal_id, &turnout_bps);
+            self.finalized_count = self.finalized_count.saturating_add(1);
+            self.turnout_sum_bps = self.turnout_sum_bps.saturating_add(u64::from(turnout_bps));
+            if !passed {
+                self.defeated_count = self.defeated_count.saturating_add(1);
+            }
+        }
+
+        /// Turnout recorded at execution time, in basis points of total voting power
+        #[ink(message)]
+        pub fn get_proposal_turnout(&self, proposal_id: u64) -> Option<u32> {
+            self.turnouts.get(proposal_id)
+        }
+
+        /// Number of proposals an account has voted on
+        #[ink(message)]
+        pub fn proposals_voted_count(&self, account: AccountId) -> u32 {
+            self.voted_counts.get(account).unwrap_or(0)
+        }
+
+        /// Aggregate proposal outcomes and turnout
+        #[ink(message)]
+        pub fn get_governance_stats(&self) -> GovernanceStats {
+            #[allow(clippy::cast_possible_truncation)]
+            let average_turnout_bps = self.turnout_sum_bps
+                .checked_div(self.finalized_count)
+                .unwrap_or(0) as u32;
+            GovernanceStats {
+                total_proposals: self.next_proposal_id.saturating_sub(1),
+                executed: self.executed_count,
+                defeated: self.defeated_count,
+                average_turnout_bps,
+            }
+        }
+
+        /// Emit a `HealthSnapshot` event (anyone; at most once per `HEALTH_SNAPSHOT_INTERVAL_BLOCKS`)
+        #[ink(message)]
+        pub fn emit_health_snapshot(&mut self) -> Result<()> {
+            let now = self.env().block_number();
+            if self.last_health_snapshot.is_some_and(|last| now < last.saturating_add(HEALTH_SNAPSHOT_INTERVAL_BLOCKS)) {
+                return Err(Error::HealthSnapshotTooSoon);
+            }
+            self.last_health_snapshot = Some(now);
+            let mut active_proposals: u32 = 0;
+            let mut queued_proposals: u32 = 0;
+            for proposal_id in 1..self.next_proposal_id {
+                match self.get_proposal_state(proposal_id) {
+                    Some(ProposalState::Active) => active_proposals = active_proposals.saturating_add(1),
+                    Some(ProposalState::Queued) => queued_proposals = queued_proposals.saturating_add(1),
+                    _ => {}
+                }
+            }
+            let this = self.env().account_id();
+            self.env().emit_event(HealthSnapshot {
+                block: now,
+                active_proposals,
+                queued_proposals,
+                treasury_balance: Token::connect(self.token_address).map_or(0, |token| token.balance_of(this)),
+            });
+            Ok(())
+        }
+
+        /// Request a treasury payout from a council's allowance (council members only);
+        /// counts as the proposer's approval
+        #[ink(message)]
+        pub fn propose_council_spend(&mut self, council_id: u32, recipient: AccountId, amount: Balance) -> Result<u64> {
+            let budget = self.council_budgets.get(council_id).ok_or(Error::CouncilNotFound)?;
+            if !budget.members.contains(&self.env().caller()) {
+                return Err(Error::NotCouncilMember);
+            }
+            let spend_id = self.next_council_spend_id;
+            self.next_council_spend_id = spend_id.saturating_add(1);
+            let spend = CouncilSpend { council_id, recipient, amount, approvals: Vec::new(), executed: false };
+            self.council_spends.insert(spend_id, &spend);
+            self.approve_council_spend(spend_id)?;
+            Ok(spend_id)
+        }
+
+        /// Approve a pending council spend; pays it out once approvals of current members reach the threshold
+        #[ink(message)]
+        pub fn approve_council_spend(&mut self, spend_id: u64) -> Result<()> {
+            let mut spend = self.council_spends.get(spend_id).ok_or(Error::SpendNotFound)?;
+            if spend.executed { return Err(Error::SpendAlreadyExecuted); }
+            let budget = self.council_budgets.get(spend.council_id).ok_or(Error::CouncilNotFound)?;
+            let approver = self.env().caller();
+            if !budget.members.contains(&approver) { return Err(Error::NotCouncilMember); }
+            if spend.approvals.contains(&approver) { return Err(Error::AlreadyVoted); }
+            spend.approvals.push(approver);
+            let approvals = spend.approvals.iter().filter(|a| budget.members.contains(a)).count();
+            self.env().emit_event(CouncilSpendApproved { spend_id, approver, approvals: u32::try_from(approvals).unwrap_or(u32::MAX) });
+
+            if approvals >= usize::from(budget.threshold) {
+                let mut period = self.current_council_period(spend.council_id, &budget);
+                let spent = period.spent.saturating_add(spend.amount);
+                if spent > budget.allowance_per_period { return Err(Error::AllowanceExceeded); }
+                if !self.treasury_covers(spend.amount) { return Err(Error::InsufficientTreasury); }
+                if !self.with_token(|token| token.transfer(spend.recipient, spend.amount, Vec::new()).is_ok()) {
+                    return Err(Error::ExecutionFailed);
+                }
+                period.spent = spent;
+                self.council_periods.insert(spend.council_id, &period);
+                spend.executed = true;
+                self.env().emit_event(CouncilSpendExecuted {
+                    spend_id,
+                    council_id: spend.council_id,
+                    recipient: spend.recipient,
+                    amount: spend.amount,
+                });
+            }
+            self.council_spends.insert(spend_id, &spend);
+            Ok(())
+        }
+
+        /// Get a council budget
+        #[ink(message)]
+        pub fn get_council_budget(&self, council_id: u32) -> Option<CouncilBudget> {
+            self.council_budgets.get(council_id)
+        }
+
+        /// Allowance a council can still spend in its current period
+        #[ink(message)]
+        pub fn get_council_remaining_allowance(&self, council_id: u32) -> Balance {
+            self.council_budgets.get(council_id).map_or(0, |budget| {
+                let period = self.current_council_period(council_id, &budget);
+                budget.allowance_per_period.saturating_sub(period.spent)
+            })
+        }
+
+        /// Get a council spend request
+        #[ink(message)]
+        pub fn get_council_spend(&self, spend_id: u64) -> Option<CouncilSpend> {
+            self.council_spends.get(spend_id)
+        }
+
+        /// Treasury balance free to spend: the contract's PGT balance less the tokens it holds for others
+        #[ink(message)]
+        pub fn get_spendable_treasury(&self) -> Balance {
+            let this = self.env().account_id();
+            Token::connect(self.token_address)
+                .map_or(0, |token| token.balance_of(this))
+                .saturating_sub(self.reserved_balance)
+        }
+
+        /// Stored period of a council, reset when one or more whole periods have passed
+        fn current_council_period(&self, council_id: u32, budget: &CouncilBudget) -> CouncilPeriod {
+            let now = self.env().block_timestamp();
+            let period = self.council_periods.get(council_id).unwrap_or(CouncilPeriod { period_start: now, spent: 0 });
+            let elapsed = now.saturating_sub(period.period_start);
+            if elapsed < budget.period_ms {
+                return period;
+            }
+            CouncilPeriod { period_start: now.saturating_sub(elapsed % budget.period_ms), spent: 0 }
+        }
+
+        /// Delegate the caller's voting power on standard proposals to `to` (None revokes).
+        /// Delegation is one level deep: delegates cannot delegate onward.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: Option<AccountId>) -> Result<()> {
+            let delegator = self.env().caller();
+            if let Some(delegate) = to {
+                if delegate == delegator
+                    || self.delegations.contains(delegate)
+                    || self.delegators.get(delegator).is_some_and(|list| !list.is_empty())
+                {
+                    return Err(Error::InvalidDelegate);
+                }
+            }
+            let from_delegate = self.delegations.get(delegator);
+            if from_delegate == to {
+                return Ok(());
+            }
+            if let Some(previous) = from_delegate {
+                let mut list = self.delegators.get(previous).unwrap_or_default();
+                list.retain(|account| *account != delegator);
+                self.delegators.insert(previous, &list);
+                self.delegations.remove(delegator);
+            }
+            if let Some(delegate) = to {
+                let mut list = self.delegators.get(delegate).unwrap_or_default();
+                if list.len() >= MAX_DELEGATORS {
+                    return Err(Error::TooManyDelegators);
+                }
+                list.push(delegator);
+                self.delegators.insert(delegate, &list);
+                self.delegations.insert(delegator, &delegate);
+            }
+            self.env().emit_event(DelegateChanged { delegator, from_delegate, to_delegate: to });
+            Ok(())
+        }
+
+        /// Register (or update) the caller as a public delegate
+        #[ink(message)]
+        pub fn register_delegate(&mut self, name_hash: [u8; 32], statement_uri: String) -> Result<()> {
+            if statement_uri.len() > MAX_STATEMENT_URI_LEN { return Err(Error::StatementTooLong); }
+            let delegate = self.env().caller();
+            if self.delegations.contains(delegate) { return Err(Error::InvalidDelegate); }
+            let registered_at = match self.delegate_profiles.get(delegate) {
+                Some(profile) => profile.registered_at,
+                None => {
+                    if self.public_delegates.len() >= MAX_PUBLIC_DELEGATES { return Err(Error::TooManyDelegates); }
+                    self.public_delegates.push(delegate);
+                    self.env().block_timestamp()
+                }
+            };
+            self.delegate_profiles.insert(delegate, &DelegateProfile { name_hash, statement_uri: statement_uri.clone(), registered_at });
+            self.env().emit_event(DelegateRegistered { delegate, name_hash, statement_uri });
+            Ok(())
+        }
+
+        /// Remove the caller from the public delegate registry; existing delegations stay in place
+        #[ink(message)]
+        pub fn unregister_delegate(&mut self) -> Result<()> {
+            let delegate = self.env().caller();
+            if !self.delegate_profiles.contains(delegate) { return Err(Error::NotRegistered); }
+            self.delegate_profiles.remove(delegate);
+            self.public_delegates.retain(|account| *account != delegate);
+            self.env().emit_event(DelegateUnregistered { delegate });
+            Ok(())
+        }
+
+        /// Delegate an account votes through, if any
+        #[ink(message)]
+        pub fn get_delegate(&self, delegator: AccountId) -> Option<AccountId> {
+            self.delegations.get(delegator)
+        }
+
+        /// Accounts delegating to `delegate`
+        #[ink(message)]
+        pub fn get_delegators(&self, delegate: AccountId) -> Vec<AccountId> {
+            self.delegators.get(delegate).unwrap_or_default()
+        }
+
+        /// Public profile of a registered delegate
+        #[ink(message)]
+        pub fn get_delegate_profile(&self, delegate: AccountId) -> Option<DelegateProfile> {
+            self.delegate_profiles.get(delegate)
+        }
+
+        /// Current voting power delegated to `delegate` (delegators' balances)
+        #[ink(message)]
+        pub fn get_delegated_power(&self, delegate: AccountId) -> u64 {
+            self.get_delegators(delegate)
+                .iter()
+                .fold(0u64, |total, delegator| total.saturating_add(self.get_voting_power(*delegator)))
+        }
+
+        /// Registered public delegates with the most delegated power, highest first
+        #[ink(message)]
+        pub fn get_top_delegates(&self, limit: u32) -> Vec<(AccountId, u64)> {
+            let mut ranked: Vec<(AccountId, u64)> = self.public_delegates
+                .iter()
+                .map(|delegate| (*delegate, self.get_delegated_power(*delegate)))
+                .collect();
+            ranked.sort_by_key(|(_, power)| core::cmp::Reverse(*power));
+            ranked.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+            ranked
+        }
+
+        /// Stand for election as a data-feed operator, depositing the candidate bond
+        /// (requires a token allowance to this contract)
+        #[ink(message)]
+        pub fn register_feed_candidate(&mut self) -> Result<()> {
+            let candidate = self.env().caller();
+            if self.feed_candidates.contains(candidate) { return Err(Error::AlreadyRegistered); }
+            if self.feed_candidate_list.len() >= MAX_FEED_CANDIDATES { return Err(Error::TooManyCandidates); }
+            let bond = self.feed_candidate_bond;
+            let this = self.env().account_id();
+            if !self.with_token(|token| token.transfer_from(candidate, this, bond, Vec::new()).is_ok()) {
+                return Err(Error::BondTransferFailed);
+            }
+            self.feed_candidates.insert(candidate, &bond);
+            self.feed_candidate_list.push(candidate);
+            self.reserved_balance = self.reserved_balance.saturating_add(bond);
+            self.env().emit_event(FeedCandidateRegistered { candidate, bond });
+            Ok(())
+        }
+
+        /// Withdraw a candidacy and get the bond back; sitting feed operators must wait until voted out
+        #[ink(message)]
+        pub fn withdraw_feed_candidacy(&mut self) -> Result<()> {
+            let candidate = self.env().caller();
+            let bond = self.feed_candidates.get(candidate).ok_or(Error::CandidateNotFound)?;
+            if self.elected_feeds.contains(&candidate) { return Err(Error::CandidateElected); }
+            if !self.with_token(|token| token.transfer(candidate, bond, Vec::new()).is_ok()) {
+                return Err(Error::BondTransferFailed);
+            }
+            self.feed_candidates.remove(candidate);
+            self.feed_candidate_list.retain(|account| *account != candidate);
+            self.reserved_balance = self.reserved_balance.saturating_sub(bond);
+            self.env().emit_event(FeedCandidateWithdrawn { candidate });
+            Ok(())
+        }
+
+        /// Register the caller as a project in an open grant round
+        #[ink(message)]
+        pub fn register_grant_project(&mut self, round_id: u64, name: String) -> Result<()> {
+            let project = self.env().caller();
+            let mut round = self.open_grant_round(round_id)?;
+            if name.len() > MAX_GRANT_PROJECT_NAME_LEN { return Err(Error::NameTooLong); }
+            if self.grant_projects.contains((round_id, project)) { return Err(Error::AlreadyRegistered); }
+            if round.projects.len() >= MAX_GRANT_PROJECTS { return Err(Error::TooManyProjects); }
+            round.projects.push(project);
+            self.grant_rounds.insert(round_id, &round);
+            let standing = GrantProject {
+                name,
+                donations: 0,
+                contributors: 0,
+                eligible_donations: 0,
+                sqrt_sum: 0,
+                matching: 0,
+                claimed: false,
+            };
+            self.grant_projects.insert((round_id, project), &standing);
+            Ok(())
+        }
+
+        /// Donate PGT to a project in an open grant round (requires a token allowance to this
+        /// contract). Only device owners' donations attract matching, each capped at the round's
+        /// contributor cap, so splitting funds across accounts without devices gains nothing.
+        #[ink(message)]
+        pub fn donate_to_grant_project(&mut self, round_id: u64, project: AccountId, amount: Balance) -> Result<()> {
+            let round = self.open_grant_round(round_id)?;
+            let mut standing = self.grant_projects.get((round_id, project)).ok_or(Error::ProjectNotFound)?;
+            if amount == 0 { return Err(Error::ZeroAmount); }
+            let contributor = self.env().caller();
+            let this = self.env().account_id();
+            if !self.with_token(|token| token.transfer_from(contributor, this, amount, Vec::new()).is_ok()) {
+                return Err(Error::DonationFailed);
+            }
+
+            let previous = self.grant_donations.get((round_id, project, contributor)).unwrap_or(0);
+            let total = previous.saturating_add(amount);
+            self.grant_donations.insert((round_id, project, contributor), &total);
+            if previous == 0 {
+                standing.contributors = standing.contributors.saturating_add(1);
+            }
+            standing.donations = standing.donations.saturating_add(amount);
+            self.reserved_balance = self.reserved_balance.saturating_add(amount);
+
+            let matched = self.with_registry(|registry| registry.get_device(contributor).is_some_and(|device| device.active));
+            if matched {
+                let (old, new) = (previous.min(round.contributor_cap), total.min(round.contributor_cap));
+                standing.eligible_donations = standing.eligible_donations.saturating_add(new.saturating_sub(old));
+                standing.sqrt_sum = standing.sqrt_sum.saturating_add(isqrt(new).saturating_sub(isqrt(old)));
+            }
+            self.grant_projects.insert((round_id, project), &standing);
+            self.env().emit_event(GrantDonation { round_id, project, contributor, amount, matched });
+            Ok(())
+        }
+
+        /// Close a grant round after its end block (anyone): the matching pool is split in
+        /// proportion to each project's quadratic-funding weight, (Σ√cᵢ)² − Σcᵢ
+        #[ink(message)]
+        pub fn close_grant_round(&mut self, round_id: u64) -> Result<Balance> {
+            let mut round = self.grant_rounds.get(round_id).ok_or(Error::GrantRoundNotFound)?;
+            if round.closed { return Err(Error::GrantRoundEnded); }
+            if u64::from(self.env().block_number()) < round.end_block { return Err(Error::GrantRoundNotEnded); }
+
+            let weights: Vec<(AccountId, Balance)> = round.projects
+                .iter()
+                .filter_map(|project| {
+                    let standing = self.grant_projects.get((round_id, *project))?;
+                    let weight = standing.sqrt_sum.saturating_mul(standing.sqrt_sum).saturating_sub(standing.eligible_donations);
+                    Some((*project, weight))
+                })
+                .collect();
+            let total_weight = weights.iter().fold(0, |total: Balance, (_, weight)| total.saturating_add(*weight));
+
+            let mut matched: Balance = 0;
+            if total_weight > 0 {
+                for (project, weight) in weights {
+                    let Some(mut standing) = self.grant_projects.get((round_id, project)) else { continue };
+                    standing.matching = mul_div(round.matching_pool, weight, total_weight);
+                    matched = matched.saturating_add(standing.matching);
+                    self.grant_projects.insert((round_id, project), &standing);
+                }
+            }
+            // The unassigned part of the pool goes back to the treasury
+            self.reserved_balance = self.reserved_balance.saturating_sub(round.matching_pool.saturating_sub(matched));
+            round.closed = true;
+            round.matched = matched;
+            self.grant_rounds.insert(round_id, &round);
+            self.env().emit_event(GrantRoundClosed { round_id, matched });
+            Ok(matched)
+        }
+
+        /// Pay the caller's donations plus matching from a closed grant round
+        #[ink(message)]
+        pub fn claim_grant(&mut self, round_id: u64) -> Result<Balance> {
+            let project = self.env().caller();
+            let round = self.grant_rounds.get(round_id).ok_or(Error::GrantRoundNotFound)?;
+            if !round.closed { return Err(Error::GrantRoundNotEnded); }
+            let mut standing = self.grant_projects.get((round_id, project)).ok_or(Error::ProjectNotFound)?;
+            if standing.claimed { return Err(Error::AlreadyClaimed); }
+            let amount = standing.donations.saturating_add(standing.matching);
+            if !self.with_token(|token| token.transfer(project, amount, Vec::new()).is_ok()) {
+                return Err(Error::ExecutionFailed);
+            }
+            standing.claimed = true;
+            self.reserved_balance = self.reserved_balance.saturating_sub(amount);
+            self.grant_projects.insert((round_id, project), &standing);
+            self.env().emit_event(GrantClaimed { round_id, project, amount });
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        pub fn get_grant_round(&self, round_id: u64) -> Option<GrantRound> {
+            self.grant_rounds.get(round_id)
+        }
+
+        #[ink(message)]
+        pub fn get_grant_project(&self, round_id: u64, project: AccountId) -> Option<GrantProject> {
+            self.grant_projects.get((round_id, project))
+        }
+
+        /// Amount a contributor donated to a project in a round
+        #[ink(message)]
+        pub fn get_grant_donation(&self, round_id: u64, project: AccountId, contributor: AccountId) -> Balance {
+            self.grant_donations.get((round_id, project, contributor)).unwrap_or(0)
+        }
+
+        /// A grant round still accepting projects and donations
+        fn open_grant_round(&self, round_id: u64) -> Result<GrantRound> {
+            let round = self.grant_rounds.get(round_id).ok_or(Error::GrantRoundNotFound)?;
+            if round.closed || u64::from(self.env().block_number()) >= round.end_block {
+                return Err(Error::GrantRoundEnded);
+            }
+            Ok(round)
+        }
+
+        /// Vote for one feed candidate in the current election, locking the caller's balance until it ends
+        #[ink(message)]
+        pub fn vote_feed_candidate(&mut self, candidate: AccountId) -> Result<()> {
+            if !self.feed_candidates.contains(candidate) { return Err(Error::CandidateNotFound); }
+            // Votes after the epoch ends would count toward an election already due for finalizing
+            if u64::from(self.env().block_number()) >= self.feed_epoch_end_block { return Err(Error::ElectionEnded); }
+            let voter = self.env().caller();
+            let epoch = self.feed_election_epoch;
+            if self.feed_votes.contains((epoch, voter)) { return Err(Error::AlreadyVoted); }
+            let voting_power = self.get_voting_power(voter);
+            if voting_power == 0 { return Err(Error::InsufficientVotingPower); }
+
+            #[allow(clippy::cast_possible_truncation)]
+            let until_block = self.feed_epoch_end_block.saturating_add(1).min(u64::from(u32::MAX)) as u32;
+            if !self.with_token(|token| token.lock(voter, Balance::from(voting_power), until_block).is_ok()) {
+                return Err(Error::LockFailed);
+            }
+            self.feed_votes.insert((epoch, voter), &candidate);
+            let tally = self.feed_tallies.get((epoch, candidate)).unwrap_or(0);
+            self.feed_tallies.insert((epoch, candidate), &tally.saturating_add(voting_power));
+            self.env().emit_event(FeedVoteCast { epoch, voter, candidate, voting_power });
+            Ok(())
+        }
+
+        /// Close the current election once its epoch has ended (anyone): the top candidates by votes
+        /// replace GridService's data feeds and the next election starts
+        #[ink(message)]
+        pub fn finalize_feed_election(&mut self) -> Result<Vec<AccountId>> {
+            let now = u64::from(self.env().block_number());
+            if now < self.feed_epoch_end_block { return Err(Error::ElectionNotEnded); }
+            let epoch = self.feed_election_epoch;
+            let mut ranked: Vec<(AccountId, u64)> = self.get_feed_candidates()
+                .into_iter()
+                .filter(|(_, votes)| *votes > 0)
+                .collect();
+            // Stable sort: ties go to the earlier registration
+            ranked.sort_by_key(|(_, votes)| core::cmp::Reverse(*votes));
+            let elected: Vec<AccountId> = ranked
+                .into_iter()
+                .take(usize::try_from(self.feed_seats).unwrap_or(usize::MAX))
+                .map(|(candidate, _)| candidate)
+                .collect();
+            let installed = elected.clone();
+            if !self.with_grid(|grid| grid.set_elected_data_feeds(installed).is_ok()) {
+                return Err(Error::ExecutionFailed);
+            }
+            self.elected_feeds = elected.clone();
+            self.feed_election_epoch = epoch.saturating_add(1);
+            self.feed_epoch_end_block = now.saturating_add(FEED_ELECTION_EPOCH_BLOCKS);
+            self.env().emit_event(FeedElectionFinalized { epoch, elected: elected.clone() });
+            Ok(elected)
+        }
+
+        /// Set the number of elected feed seats and the candidate bond for new candidates (owner only)
+        #[ink(message)]
+        pub fn set_feed_election_params(&mut self, seats: u32, bond: Balance) -> Result<()> {
+            if self.env().caller() != self.owner { return Err(Error::Unauthorized); }
+            if seats == 0 || usize::try_from(seats).unwrap_or(usize::MAX) > MAX_FEED_CANDIDATES {
+                return Err(Error::InvalidSeatCount);
+            }
+            self.feed_seats = seats;
+            self.feed_candidate_bond = bond;
+            Ok(())
+        }
+
+        /// Registered feed candidates with their votes in the current election
+        #[ink(message)]
+        pub fn get_feed_candidates(&self) -> Vec<(AccountId, u64)> {
+            let epoch = self.feed_election_epoch;
+            self.feed_candidate_list
+                .iter()
+                .map(|candidate| (*candidate, self.feed_tallies.get((epoch, *candidate)).unwrap_or(0)))
+                .collect()
+        }
+
+        /// (epoch, end block, seats, candidate bond) of the current feed election
+        #[ink(message)]
+        pub fn get_feed_election(&self) -> (u64, u64, u32, Balance) {
+            (self.feed_election_epoch, self.feed_epoch_end_block, self.feed_seats, self.feed_candidate_bond)
+        }
+
+        /// Feed operators installed by the last finalized election
+        #[ink(message)]
+        pub fn get_elected_feeds(&self) -> Vec<AccountId> {
+            self.elected_feeds.clone()
+        }
+
         /// Get voting status
         #[ink(message)]
         pub fn has_voted(&self, proposal_id: u64, voter: AccountId) -> bool {
-            let voter_bytes = ink_account_to_bytes(voter);
-            self.votes.contains((proposal_id, voter_bytes))
+            self.votes.contains((proposal_id, voter))
         }
 
         /// Get governance parameters
@@ -403,11 +2131,185 @@ pub mod governance {
             (self.min_voting_power, self.voting_duration_blocks, self.quorum_percentage)
         }
 
+        /// Carry out the side effects of a passed proposal; false if any call failed
+        fn apply_proposal(&mut self, proposal_type: ProposalType) -> bool {
+            match proposal_type {
+                ProposalType::UpdateMinStake(new_min) => {
+                    self.with_registry(|registry| registry.update_min_stake(new_min).is_ok())
+                }
+                ProposalType::UpdateCompensationRate(new_rate) => {
+                    self.with_grid(|grid| grid.update_default_compensation_rate(new_rate).is_ok())
+                }
+                ProposalType::UpdateReputationThreshold(threshold) => {
+                    self.with_registry(|registry| registry.update_reputation_threshold(threshold).is_ok())
+                }
+                ProposalType::TreasurySpend(to, amount) => {
+                    // Use token transfer from this contract's balance, leaving reserved tokens alone
+                    self.treasury_covers(amount) && self.with_token(|token| token.transfer(to, amount, Vec::new()).is_ok())
+                }
+                ProposalType::SetTokenMinter(account, is_minter) => {
+                    self.with_token(|token| {
+                        let r = if is_minter { token.add_minter(account) } else { token.remove_minter(account) };
+                        r.is_ok()
+                    })
+                }
+                ProposalType::SetRegistryAuthorizedCaller(account, is_auth) => {
+                    self.with_registry(|registry| {
+                        let r = if is_auth { registry.add_authorized_caller(account) } else { registry.remove_authorized_caller(account) };
+                        r.is_ok()
+                    })
+                }
+                ProposalType::SetGridAuthorizedCaller(account, is_auth) => {
+                    self.with_grid(|grid| {
+                        let r = if is_auth { grid.add_authorized_caller(account) } else { grid.remove_authorized_caller(account) };
+                        r.is_ok()
+                    })
+                }
+                ProposalType::SetDeviceTypePolicy(device_type, policy) => {
+                    self.with_registry(|registry| registry.set_device_type_policy(device_type, policy).is_ok())
+                }
+                ProposalType::SetMinFirmwareVersion(manufacturer, model, version) => {
+                    self.with_registry(|registry| registry.set_min_firmware_version(manufacturer, model, version).is_ok())
+                }
+                ProposalType::SetCustomEventType(type_id, descriptor) => {
+                    self.with_grid(|grid| grid.set_custom_event_type(type_id, descriptor).is_ok())
+                }
+                ProposalType::SetGuardian(guardian) => {
+                    self.set_guardian_internal(guardian);
+                    let registry_ok = self.with_registry(|registry| registry.set_guardian(guardian).is_ok());
+                    let grid_ok = self.with_grid(|grid| grid.set_guardian(guardian).is_ok());
+                    let token_ok = self.with_token(|token| token.set_guardian(guardian).is_ok());
+                    registry_ok && grid_ok && token_ok
+                }
+                ProposalType::SetAutoTriggerEnabled(enabled) => {
+                    self.with_grid(|grid| grid.set_auto_trigger_enabled(enabled).is_ok())
+                }
+                ProposalType::CreateTriggerRule(params) => {
+                    self.with_grid(|grid| grid.create_trigger_rule(params).is_ok())
+                }
+                ProposalType::SetTriggerRuleActive(rule_id, active) => {
+                    self.with_grid(|grid| grid.set_trigger_rule_active(rule_id, active).is_ok())
+                }
+                ProposalType::RemoveTriggerRule(rule_id) => {
+                    self.with_grid(|grid| grid.remove_trigger_rule(rule_id).is_ok())
+                }
+                ProposalType::SetDataFeed(feed, enabled) => {
+                    self.with_grid(|grid| {
+                        let r = if enabled { grid.add_data_feed(feed) } else { grid.remove_data_feed(feed) };
+                        r.is_ok()
+                    })
+                }
+                ProposalType::SetRewardConfig(config) => {
+                    self.with_grid(|grid| grid.set_reward_config(config).is_ok())
+                }
+                ProposalType::SetFeedRateLimit(feed, limit) => {
+                    self.with_grid(|grid| grid.set_feed_rate_limit(feed, limit).is_ok())
+                }
+                ProposalType::SetCallerPermissions(caller, permissions) => {
+                    self.with_grid(|grid| grid.set_caller_permissions(caller, permissions).is_ok())
+                }
+                ProposalType::SetTypeVerifier(event_type, verifier, approved) => {
+                    self.with_grid(|grid| grid.set_type_verifier(event_type, verifier, approved).is_ok())
+                }
+                ProposalType::SetCouncilBudget(council_id, Some(budget)) => {
+                    let valid = Self::council_budget_valid(&budget);
+                    if valid {
+                        // A new or re-sized budget starts a fresh period
+                        let period = CouncilPeriod { period_start: self.env().block_timestamp(), spent: 0 };
+                        self.council_periods.insert(council_id, &period);
+                        self.env().emit_event(CouncilBudgetUpdated { council_id, allowance_per_period: budget.allowance_per_period });
+                        self.council_budgets.insert(council_id, &budget);
+                    }
+                    valid
+                }
+                ProposalType::SetCouncilBudget(council_id, None) => {
+                    self.council_budgets.remove(council_id);
+                    self.council_periods.remove(council_id);
+                    self.env().emit_event(CouncilBudgetUpdated { council_id, allowance_per_period: 0 });
+                    true
+                }
+                ProposalType::ClawbackCouncilBudget(council_id, amount) => {
+                    let Some(budget) = self.council_budgets.get(council_id) else { return false };
+                    let mut period = self.current_council_period(council_id, &budget);
+                    period.spent = period.spent.saturating_add(amount).min(budget.allowance_per_period);
+                    self.council_periods.insert(council_id, &period);
+                    true
+                }
+                ProposalType::SetEmergencyMintBudget(minter, cap_per_quarter) => {
+                    self.with_token(|token| token.set_emergency_budget(minter, cap_per_quarter).is_ok())
+                }
+                ProposalType::OpenGrantRound(params) => self.open_grant_round_from(params),
+                ProposalType::SetEmissionSchedule(schedule) => {
+                    self.with_token(|token| token.set_emission_schedule(schedule).is_ok())
+                }
+                ProposalType::SetMinterEmissionBucket(minter, bucket) => {
+                    self.with_token(|token| token.set_minter_emission_bucket(minter, bucket).is_ok())
+                }
+                ProposalType::SystemUpgrade | ProposalType::Other(_) => true,
+            }
+        }
+
+        /// Whether the treasury can pay `amount` without touching reserved tokens
+        fn treasury_covers(&self, amount: Balance) -> bool {
+            let this = self.env().account_id();
+            Token::connect(self.token_address)
+                .is_none_or(|token| token.balance_of(this).saturating_sub(self.reserved_balance) >= amount)
+        }
+
+        /// Open a grant round whose matching pool the treasury currently holds, reserving the pool
+        fn open_grant_round_from(&mut self, params: GrantRoundParams) -> bool {
+            if !self.grant_round_fundable(&params) {
+                return false;
+            }
+            let round_id = self.next_grant_round_id;
+            self.next_grant_round_id = self.next_grant_round_id.saturating_add(1);
+            let end_block = u64::from(self.env().block_number()).saturating_add(params.duration_blocks);
+            let round = GrantRound {
+                matching_pool: params.matching_pool,
+                contributor_cap: params.contributor_cap,
+                end_block,
+                projects: Vec::new(),
+                closed: false,
+                matched: 0,
+            };
+            self.grant_rounds.insert(round_id, &round);
+            self.reserved_balance = self.reserved_balance.saturating_add(params.matching_pool);
+            self.env().emit_event(GrantRoundOpened { round_id, matching_pool: params.matching_pool, end_block });
+            true
+        }
+
+        /// Council budgets need members, a reachable approval threshold and a period
+        fn council_budget_valid(budget: &CouncilBudget) -> bool {
+            !budget.members.is_empty()
+                && budget.members.len() <= MAX_COUNCIL_MEMBERS
+                && budget.threshold > 0
+                && usize::from(budget.threshold) <= budget.members.len()
+                && budget.period_ms > 0
+        }
+
+        /// Grant round parameters are usable and the treasury holds the matching pool
+        fn grant_round_fundable(&self, params: &GrantRoundParams) -> bool {
+            params.duration_blocks > 0 && params.contributor_cap > 0 && self.treasury_covers(params.matching_pool)
+        }
+
+        /// Run `f` against a dependency; one that cannot be reached (no mock installed in
+        /// unit tests) is skipped and counts as success
+        fn with_token(&self, f: impl FnOnce(&mut Token) -> bool) -> bool {
+            Token::connect(self.token_address).is_none_or(|mut token| f(&mut token))
+        }
+
+        fn with_registry(&self, f: impl FnOnce(&mut Registry) -> bool) -> bool {
+            Registry::connect(self.registry_address).is_none_or(|mut registry| f(&mut registry))
+        }
+
+        fn with_grid(&self, f: impl FnOnce(&mut Grid) -> bool) -> bool {
+            Grid::connect(self.grid_service_address).is_none_or(|mut grid| f(&mut grid))
+        }
+
         /// Get voting power from PSP22 token balance
         #[allow(clippy::cast_possible_truncation)]
         fn get_voting_power(&self, account: AccountId) -> u64 {
-            let token = PowergridTokenRef::from_account_id(self.token_address);
-            let bal: u128 = token.balance_of(account);
+            let bal: u128 = Token::connect(self.token_address).map_or(0, |token| token.balance_of(account));
             // Downcast safely; governance uses u64 voting units
             bal.min(u128::from(u64::MAX)) as u64
         }
@@ -415,9 +2317,729 @@ pub mod governance {
         /// Get total voting power from PSP22 total_supply
         #[allow(clippy::cast_possible_truncation)]
         fn get_total_voting_power(&self) -> u64 {
-            let token = PowergridTokenRef::from_account_id(self.token_address);
-            let total: u128 = token.total_supply();
+            let total: u128 = Token::connect(self.token_address).map_or(0, |token| token.total_supply());
             total.min(u128::from(u64::MAX)) as u64
         }
     }
+
+    /// Integer square root (floor)
+    fn isqrt(value: Balance) -> Balance {
+        if value < 2 {
+            return value;
+        }
+        let mut x = value;
+        let mut y = x / 2 + 1;
+        while y < x {
+            x = y;
+            y = (x + value / x) / 2;
+        }
+        x
+    }
+
+    /// `amount * part / whole` without overflow, losing low bits of `part` and `whole` when needed
+    fn mul_div(amount: Balance, part: Balance, whole: Balance) -> Balance {
+        let (mut part, mut whole) = (part, whole);
+        loop {
+            if whole == 0 {
+                return 0;
+            }
+            if let Some(product) = amount.checked_mul(part) {
+                return product / whole;
+            }
+            part >>= 1;
+            whole >>= 1;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{advance_block, default_accounts, set_block_number, set_block_timestamp, set_caller, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+        use powergrid_shared::mock::{MockGrid, MockGridState, MockRegistry, MockRegistryState, MockToken, MockTokenState};
+        use powergrid_shared::{Device, DeviceMetadata, DeviceType};
+
+        const TOKEN: [u8; 32] = [0x10; 32];
+        const REGISTRY: [u8; 32] = [0x11; 32];
+        const GRID: [u8; 32] = [0x12; 32];
+
+        /// Governance over mock dependencies: bob, charlie, django and eve hold 400/300/200/100
+        /// of a 1_000 PGT supply; 10-block voting window, 20% quorum; alice is the contract and owner
+        fn setup() -> (Governance, DefaultAccounts<DefaultEnvironment>) {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = MockTokenState { total_supply: 1_000, ..Default::default() };
+            for (account, balance) in [(accounts.bob, 400), (accounts.charlie, 300), (accounts.django, 200), (accounts.eve, 100)] {
+                token.balances.insert(account, balance);
+            }
+            MockToken::install(TOKEN.into(), token);
+            MockRegistry::install(REGISTRY.into(), MockRegistryState::default());
+            MockGrid::install(GRID.into(), MockGridState::default());
+            let governance = Governance::new(TOKEN.into(), REGISTRY.into(), GRID.into(), 1, 10, 20);
+            (governance, accounts)
+        }
+
+        fn advance_blocks(blocks: u32) {
+            for _ in 0..blocks {
+                advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        /// Create a proposal as bob, vote it through with bob's 40% and queue it
+        fn queued_proposal(governance: &mut Governance, proposal_type: ProposalType) -> u64 {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let proposal_id = governance.create_proposal(proposal_type, "Test proposal".into()).unwrap();
+            governance.vote(proposal_id, true, String::new()).unwrap();
+            advance_blocks(11);
+            governance.queue_proposal(proposal_id).unwrap();
+            proposal_id
+        }
+
+        /// An active registered device, which makes its owner eligible for grant matching
+        fn test_device() -> Device {
+            Device {
+                metadata: DeviceMetadata {
+                    device_type: DeviceType::SmartPlug,
+                    capacity_watts: 1_000,
+                    location_commitment: [0u8; 32],
+                    zone: "u4pr".into(),
+                    manufacturer: "Acme".into(),
+                    model: "T1".into(),
+                    firmware_version: "1.0.0".into(),
+                    installation_date: 0,
+                },
+                stake: 0,
+                reputation: 50,
+                total_energy_contributed: 0,
+                successful_events: 0,
+                failed_events: 0,
+                last_activity: 0,
+                active: true,
+                version: 1,
+                last_updated: 0,
+            }
+        }
+
+        #[ink::test]
+        fn test_signed_ballots_check_nonce_and_signature() {
+            let (mut governance, accounts) = setup();
+            let signer = ink_e2e::ferdie();
+            let voter = AccountId::from(signer.public_key().0);
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(voter, 250));
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let proposal_id = governance.create_proposal(ProposalType::UpdateMinStake(500), "Test proposal".into()).unwrap();
+
+            let ballot = |governance: &Governance, proposal_id: u64, nonce: u64| {
+                let hash = governance.ballot_hash(proposal_id, true, nonce);
+                SignedBallot { voter, proposal_id, support: true, nonce, signature: signer.sign(&hash).0.to_vec() }
+            };
+
+            let too_many = vec![ballot(&governance, proposal_id, 0); MAX_BALLOTS_PER_BATCH + 1];
+            assert_eq!(governance.cast_votes_by_sig(too_many), Err(Error::BatchTooLarge));
+
+            // A stale nonce or a bad signature is skipped without consuming the nonce
+            assert_eq!(governance.cast_votes_by_sig(vec![ballot(&governance, proposal_id, 1)]), Ok(0));
+            let mut forged = ballot(&governance, proposal_id, 0);
+            forged.support = false;
+            assert_eq!(governance.cast_votes_by_sig(vec![forged]), Ok(0));
+            assert_eq!(governance.get_vote_nonce(voter), 0);
+
+            assert_eq!(governance.cast_votes_by_sig(vec![ballot(&governance, proposal_id, 0)]), Ok(1));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().yes_votes, 250);
+            assert_eq!(governance.get_vote_nonce(voter), 1);
+
+            // A well-signed ballot whose vote fails still uses up its nonce
+            assert_eq!(governance.cast_votes_by_sig(vec![ballot(&governance, proposal_id, 1)]), Ok(0));
+            assert_eq!(governance.get_vote_nonce(voter), 2);
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().yes_votes, 250);
+        }
+
+        #[ink::test]
+        fn test_turnout_is_recorded_once_per_proposal() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut governance = Governance::new(accounts.django, accounts.eve, accounts.frank, 1, 10, 20);
+            assert_eq!(governance.get_governance_stats().average_turnout_bps, 0);
+
+            governance.record_turnout(1, 800, 1_000, true);
+            governance.record_turnout(2, 100, 1_000, false);
+            // A repeated finalization neither overwrites the snapshot nor counts twice
+            governance.record_turnout(2, 900, 1_000, false);
+            // Turnout is capped at the whole supply and tolerates an empty supply
+            governance.record_turnout(3, 2_000, 1_000, false);
+            governance.record_turnout(4, 50, 0, false);
+
+            assert_eq!(governance.get_proposal_turnout(1), Some(8_000));
+            assert_eq!(governance.get_proposal_turnout(2), Some(1_000));
+            assert_eq!(governance.get_proposal_turnout(3), Some(10_000));
+            assert_eq!(governance.get_proposal_turnout(4), Some(0));
+            assert_eq!(governance.get_proposal_turnout(5), None);
+            let stats = governance.get_governance_stats();
+            assert_eq!(stats.defeated, 3);
+            assert_eq!(stats.average_turnout_bps, 4_750);
+        }
+
+        #[ink::test]
+        fn test_execution_requires_committed_actions() {
+            let (mut governance, _) = setup();
+            let proposal_id = queued_proposal(&mut governance, ProposalType::UpdateMinStake(500));
+            let actions = governance.get_proposal_actions(proposal_id).unwrap();
+            assert!(matches!(actions.as_slice(), [ProposalType::UpdateMinStake(500)]));
+
+            // Anything but the committed payload is refused
+            assert_eq!(
+                governance.execute_proposal(proposal_id, vec![ProposalType::UpdateMinStake(5_000)]),
+                Err(Error::ActionHashMismatch)
+            );
+            assert_eq!(governance.execute_proposal(proposal_id, Vec::new()), Err(Error::ActionHashMismatch));
+            assert_eq!(MockRegistry::state(REGISTRY.into()).min_stake, 0);
+
+            governance.execute_proposal(proposal_id, actions).unwrap();
+            assert_eq!(MockRegistry::state(REGISTRY.into()).min_stake, 500);
+            assert_eq!(governance.get_proposal_state(proposal_id), Some(ProposalState::Executed));
+        }
+
+
+        #[ink::test]
+        fn test_queued_proposals_expire_after_grace_period() {
+            let (mut governance, accounts) = setup();
+            governance.set_timelock_seconds(10).unwrap();
+            assert_eq!(governance.set_grace_period_seconds(0), Err(Error::InvalidDuration));
+            governance.set_grace_period_seconds(100).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let executed = governance.create_proposal(ProposalType::UpdateReputationThreshold(40), "Lower".into()).unwrap();
+            let expired = governance.create_proposal(ProposalType::UpdateReputationThreshold(60), "Raise".into()).unwrap();
+            for proposal_id in [executed, expired] {
+                governance.vote(proposal_id, true, String::new()).unwrap();
+            }
+            advance_blocks(11);
+            for proposal_id in [executed, expired] {
+                governance.queue_proposal(proposal_id).unwrap();
+            }
+            let queued_at = ink::env::block_timestamp::<DefaultEnvironment>();
+            let execute_after = queued_at + 10_000;
+            let deadline = execute_after + 100_000;
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+
+            set_block_timestamp::<DefaultEnvironment>(execute_after - 1);
+            let actions = governance.get_proposal_actions(executed).unwrap();
+            assert_eq!(governance.execute_proposal(executed, actions.clone()), Err(Error::TimelockNotElapsed));
+
+            // The last millisecond of the grace period still executes
+            set_block_timestamp::<DefaultEnvironment>(deadline);
+            assert_eq!(governance.get_proposal_state(executed), Some(ProposalState::Queued));
+            governance.execute_proposal(executed, actions).unwrap();
+            assert_eq!(MockRegistry::state(REGISTRY.into()).reputation_threshold, 40);
+
+            set_block_timestamp::<DefaultEnvironment>(deadline + 1);
+            assert_eq!(governance.get_proposal_state(expired), Some(ProposalState::Expired));
+            let actions = governance.get_proposal_actions(expired).unwrap();
+            assert_eq!(governance.execute_proposal(expired, actions), Err(Error::GracePeriodElapsed));
+            // Re-queueing cannot restart the grace period
+            assert_eq!(governance.queue_proposal(expired), Err(Error::AlreadyQueued));
+            assert_eq!(governance.get_proposal_state(expired), Some(ProposalState::Expired));
+            assert_eq!(MockRegistry::state(REGISTRY.into()).reputation_threshold, 40);
+        }
+
+
+        #[ink::test]
+        fn test_turnout_and_outcome_analytics() {
+            let (mut governance, accounts) = setup();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let passed = governance.create_proposal(ProposalType::UpdateMinStake(500), "Passes".into()).unwrap();
+            let defeated = governance.create_proposal(ProposalType::UpdateMinStake(900), "Misses quorum".into()).unwrap();
+            governance.vote(passed, true, String::new()).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            governance.vote(passed, false, String::new()).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            governance.vote(passed, true, String::new()).unwrap();
+            governance.vote(defeated, true, String::new()).unwrap();
+            assert_eq!(governance.get_proposal_turnout(passed), None);
+
+            advance_blocks(11);
+            governance.queue_proposal(passed).unwrap();
+            let actions = governance.get_proposal_actions(passed).unwrap();
+            governance.execute_proposal(passed, actions).unwrap();
+            // 10% turnout is below the 20% quorum: finalized as defeated without running
+            governance.execute_proposal(defeated, Vec::new()).unwrap();
+            assert_eq!(governance.get_proposal_state(defeated), Some(ProposalState::Defeated));
+
+            assert_eq!(governance.get_proposal_turnout(passed), Some(8_000));
+            assert_eq!(governance.get_proposal_turnout(defeated), Some(1_000));
+            assert_eq!(governance.proposals_voted_count(accounts.eve), 2);
+            assert_eq!(governance.proposals_voted_count(accounts.charlie), 1);
+            assert_eq!(governance.proposals_voted_count(accounts.django), 0);
+            assert_eq!(
+                governance.get_governance_stats(),
+                GovernanceStats { total_proposals: 2, executed: 1, defeated: 1, average_turnout_bps: 4_500 }
+            );
+
+            // Turnout is recorded once; a repeated finalization does not count twice
+            governance.execute_proposal(defeated, Vec::new()).unwrap();
+            assert_eq!(governance.get_governance_stats().defeated, 1);
+        }
+
+
+        #[ink::test]
+        fn test_proposal_templates_enforce_bounds() {
+            let (mut governance, accounts) = setup();
+            let template = ProposalTemplate {
+                name: "Reputation threshold".into(),
+                action: ProposalType::UpdateReputationThreshold(0),
+                min_value: 10,
+                max_value: 90,
+            };
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.set_proposal_template(1, Some(template.clone())), Err(Error::Unauthorized));
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let inverted = ProposalTemplate { min_value: 91, ..template.clone() };
+            assert_eq!(governance.set_proposal_template(1, Some(inverted)), Err(Error::InvalidTemplate));
+            // A bound that cannot be represented by the action's parameter is rejected up front
+            let overflowing = ProposalTemplate { max_value: u32::MAX as Balance + 1, ..template.clone() };
+            assert_eq!(governance.set_proposal_template(1, Some(overflowing)), Err(Error::InvalidTemplate));
+            let unparameterized = ProposalTemplate { action: ProposalType::SystemUpgrade, ..template.clone() };
+            assert_eq!(governance.set_proposal_template(1, Some(unparameterized)), Err(Error::InvalidTemplate));
+            governance.set_proposal_template(1, Some(template)).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.create_proposal_from_template(2, 50), Err(Error::TemplateNotFound));
+            assert_eq!(governance.create_proposal_from_template(1, 9), Err(Error::ValueOutOfBounds));
+            assert_eq!(governance.create_proposal_from_template(1, 91), Err(Error::ValueOutOfBounds));
+            let proposal_id = governance.create_proposal_from_template(1, 90).unwrap();
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert!(matches!(proposal.proposal_type, ProposalType::UpdateReputationThreshold(90)));
+            assert_eq!(proposal.description, "Reputation threshold: 90");
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            governance.set_proposal_template(1, None).unwrap();
+            assert!(governance.get_proposal_template(1).is_none());
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.create_proposal_from_template(1, 50), Err(Error::TemplateNotFound));
+        }
+
+
+        #[ink::test]
+        fn test_conviction_threshold_decays_to_floor() {
+            let (mut governance, accounts) = setup();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let proposal_id = governance.create_conviction_proposal(ProposalType::UpdateMinStake(500), "Conviction".into()).unwrap();
+            let created = ink::env::block_number::<DefaultEnvironment>();
+            let decay_blocks = u32::try_from(CONVICTION_DECAY_BLOCKS).unwrap();
+            // 300% of the 1_000 supply, falling linearly to 50%
+            assert_eq!(governance.get_conviction_threshold(proposal_id), Some(3_000));
+            set_block_number::<DefaultEnvironment>(created + decay_blocks / 2);
+            assert_eq!(governance.get_conviction_threshold(proposal_id), Some(1_750));
+            set_block_number::<DefaultEnvironment>(created + decay_blocks);
+            assert_eq!(governance.get_conviction_threshold(proposal_id), Some(500));
+            set_block_number::<DefaultEnvironment>(created + 2 * decay_blocks);
+            assert_eq!(governance.get_conviction_threshold(proposal_id), Some(500));
+
+            // Full 6x conviction of 400 (2_400) passes once the threshold has fallen below it
+            set_block_number::<DefaultEnvironment>(created);
+            governance.vote_with_conviction(proposal_id, true, 6).unwrap();
+            set_block_number::<DefaultEnvironment>(created + CONVICTION_RAMP_BLOCKS);
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 2_400);
+            assert_eq!(governance.queue_proposal(proposal_id), Err(Error::ConvictionBelowThreshold));
+            // The threshold first falls below 24_000 bps (2_400) after 96_785 blocks
+            set_block_number::<DefaultEnvironment>(created + 96_784);
+            assert_eq!(governance.queue_proposal(proposal_id), Err(Error::ConvictionBelowThreshold));
+            set_block_number::<DefaultEnvironment>(created + 96_785);
+            governance.queue_proposal(proposal_id).unwrap();
+        }
+
+        #[ink::test]
+        fn test_conviction_votes_accumulate_and_lapse_with_their_lock() {
+            let (mut governance, accounts) = setup();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let proposal_id = governance.create_conviction_proposal(ProposalType::UpdateMinStake(500), "Conviction".into()).unwrap();
+            let cast = ink::env::block_number::<DefaultEnvironment>();
+            governance.vote_with_conviction(proposal_id, true, 1).unwrap();
+            assert_eq!(governance.vote_with_conviction(proposal_id, true, 2), Err(Error::AlreadyVoted));
+            assert_eq!(MockToken::state(TOKEN.into()).locks.get(&accounts.bob), Some(&(400, cast + CONVICTION_LOCK_PERIOD_BLOCKS)));
+
+            // Weight builds up over the ramp instead of counting in full at once
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 0);
+            set_block_number::<DefaultEnvironment>(cast + CONVICTION_RAMP_BLOCKS / 2);
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 200);
+            set_block_number::<DefaultEnvironment>(cast + CONVICTION_RAMP_BLOCKS);
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 400);
+
+            // Once the lock ends the vote stops counting, so the unlocked tokens cannot be counted twice
+            let unlocked = cast + CONVICTION_LOCK_PERIOD_BLOCKS;
+            set_block_number::<DefaultEnvironment>(unlocked);
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 0);
+            MockToken::update(TOKEN.into(), |token| {
+                token.balances.insert(accounts.bob, 100);
+                token.balances.insert(accounts.frank, 300);
+            });
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            governance.vote_with_conviction(proposal_id, true, 1).unwrap();
+            // The original voter may renew with what they still hold
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            governance.vote_with_conviction(proposal_id, true, 1).unwrap();
+            set_block_number::<DefaultEnvironment>(unlocked + CONVICTION_RAMP_BLOCKS);
+            assert_eq!(governance.get_conviction_tally(proposal_id).unwrap().ayes, 400);
+
+            let votes = governance.get_conviction_votes(proposal_id);
+            assert_eq!(votes.iter().map(|vote| (vote.voter, vote.voting_power)).collect::<Vec<_>>(), vec![(accounts.frank, 300), (accounts.bob, 100)]);
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.yes_votes, proposal.total_voting_power), (400, 400));
+            assert_eq!(governance.proposals_voted_count(accounts.bob), 1);
+        }
+
+
+        #[ink::test]
+        fn test_council_spends_need_threshold_within_allowance() {
+            let (mut governance, accounts) = setup();
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(accounts.alice, 1_000));
+            let budget = CouncilBudget {
+                name: "Outreach".into(),
+                members: vec![accounts.charlie, accounts.django, accounts.eve],
+                threshold: 2,
+                allowance_per_period: 100,
+                period_ms: 1_000,
+            };
+            let proposal_id = queued_proposal(&mut governance, ProposalType::SetCouncilBudget(1, Some(budget)));
+            let actions = governance.get_proposal_actions(proposal_id).unwrap();
+            governance.execute_proposal(proposal_id, actions).unwrap();
+            let period_start = ink::env::block_timestamp::<DefaultEnvironment>();
+            let balance = |account| MockToken::state(TOKEN.into()).balances.get(&account).copied().unwrap_or(0);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.propose_council_spend(1, accounts.bob, 60), Err(Error::NotCouncilMember));
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(governance.propose_council_spend(2, accounts.bob, 60), Err(Error::CouncilNotFound));
+
+            // The proposer's approval alone is below the 2-of-3 threshold
+            let first = governance.propose_council_spend(1, accounts.bob, 60).unwrap();
+            assert!(!governance.get_council_spend(first).unwrap().executed);
+            assert_eq!(governance.approve_council_spend(first), Err(Error::AlreadyVoted));
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(governance.approve_council_spend(first), Err(Error::NotCouncilMember));
+            set_caller::<DefaultEnvironment>(accounts.django);
+            governance.approve_council_spend(first).unwrap();
+            assert!(governance.get_council_spend(first).unwrap().executed);
+            assert_eq!(balance(accounts.bob), 460);
+            assert_eq!(governance.get_council_remaining_allowance(1), 40);
+            assert_eq!(governance.approve_council_spend(first), Err(Error::SpendAlreadyExecuted));
+
+            // A spend beyond what is left of the period's allowance stays pending
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let second = governance.propose_council_spend(1, accounts.bob, 50).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(governance.approve_council_spend(second), Err(Error::AllowanceExceeded));
+            assert_eq!(balance(accounts.bob), 460);
+
+            // Unspent allowance does not roll over; a new period starts from the full allowance
+            set_block_timestamp::<DefaultEnvironment>(period_start + 1_000);
+            assert_eq!(governance.get_council_remaining_allowance(1), 100);
+            governance.approve_council_spend(second).unwrap();
+            assert_eq!(balance(accounts.bob), 510);
+            assert_eq!(governance.get_council_remaining_allowance(1), 50);
+
+            let clawback = queued_proposal(&mut governance, ProposalType::ClawbackCouncilBudget(1, 30));
+            let actions = governance.get_proposal_actions(clawback).unwrap();
+            governance.execute_proposal(clawback, actions).unwrap();
+            assert_eq!(governance.get_council_remaining_allowance(1), 20);
+            assert_eq!(balance(accounts.alice), 1_000 - 110);
+        }
+
+
+        #[ink::test]
+        fn test_delegate_registry_ranks_and_caps() {
+            let (mut governance, accounts) = setup();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let too_long = "x".repeat(MAX_STATEMENT_URI_LEN + 1);
+            assert_eq!(governance.register_delegate([1; 32], too_long), Err(Error::StatementTooLong));
+            governance.register_delegate([1; 32], "ipfs://charlie".into()).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            governance.register_delegate([2; 32], "ipfs://django".into()).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            governance.delegate(Some(accounts.django)).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            governance.delegate(Some(accounts.charlie)).unwrap();
+            assert_eq!(governance.get_delegated_power(accounts.django), 400);
+            assert_eq!(governance.get_delegated_power(accounts.charlie), 100);
+            assert_eq!(governance.get_top_delegates(10), vec![(accounts.django, 400), (accounts.charlie, 100)]);
+            assert_eq!(governance.get_top_delegates(1), vec![(accounts.django, 400)]);
+
+            // Re-registering keeps the original registration and delegations survive unregistering
+            set_caller::<DefaultEnvironment>(accounts.django);
+            let registered_at = governance.get_delegate_profile(accounts.django).unwrap().registered_at;
+            advance_blocks(3);
+            governance.register_delegate([3; 32], "ipfs://django-v2".into()).unwrap();
+            let profile = governance.get_delegate_profile(accounts.django).unwrap();
+            assert_eq!((profile.name_hash, profile.registered_at), ([3; 32], registered_at));
+            governance.unregister_delegate().unwrap();
+            assert_eq!(governance.unregister_delegate(), Err(Error::NotRegistered));
+            assert_eq!(governance.get_top_delegates(10), vec![(accounts.charlie, 100)]);
+            assert_eq!(governance.get_delegate(accounts.bob), Some(accounts.django));
+
+            // One delegate accepts at most MAX_DELEGATORS delegators
+            let filler = |seed: usize| AccountId::from([0x40 + u8::try_from(seed).unwrap(); 32]);
+            for seed in 1..MAX_DELEGATORS {
+                set_caller::<DefaultEnvironment>(filler(seed));
+                governance.delegate(Some(accounts.charlie)).unwrap();
+            }
+            assert_eq!(governance.get_delegators(accounts.charlie).len(), MAX_DELEGATORS);
+            set_caller::<DefaultEnvironment>(filler(MAX_DELEGATORS));
+            assert_eq!(governance.delegate(Some(accounts.charlie)), Err(Error::TooManyDelegators));
+
+            // The public registry holds at most MAX_PUBLIC_DELEGATES delegates
+            for seed in 1..MAX_PUBLIC_DELEGATES {
+                set_caller::<DefaultEnvironment>(AccountId::from([0x80 + u8::try_from(seed).unwrap(); 32]));
+                governance.register_delegate([0; 32], String::new()).unwrap();
+            }
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            assert_eq!(governance.register_delegate([0; 32], String::new()), Err(Error::TooManyDelegates));
+        }
+
+
+        #[ink::test]
+        fn test_candidate_bonds_are_not_spendable_treasury() {
+            let (mut governance, accounts) = setup();
+            let balance = |account| MockToken::state(TOKEN.into()).balances.get(&account).copied().unwrap_or(0);
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(accounts.alice, 100));
+            governance.set_feed_election_params(3, 50).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            governance.register_feed_candidate().unwrap();
+            assert_eq!(balance(accounts.alice), 150);
+            assert_eq!(governance.get_spendable_treasury(), 100);
+
+            // A spend that would dip into the bond fails and leaves the treasury untouched
+            let spend = queued_proposal(&mut governance, ProposalType::TreasurySpend(accounts.frank, 120));
+            assert_eq!(governance.simulate_proposal(spend).unwrap().action_succeeds, Some(false));
+            let actions = governance.get_proposal_actions(spend).unwrap();
+            governance.execute_proposal(spend, actions).unwrap();
+            assert_ne!(governance.get_proposal_state(spend), Some(ProposalState::Executed));
+            assert_eq!(balance(accounts.frank), 0);
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            governance.withdraw_feed_candidacy().unwrap();
+            assert_eq!(balance(accounts.charlie), 300);
+            assert_eq!(governance.get_spendable_treasury(), 100);
+        }
+
+
+        #[ink::test]
+        fn test_feed_votes_close_when_the_epoch_ends() {
+            let (mut governance, accounts) = setup();
+            governance.set_feed_election_params(3, 50).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            governance.register_feed_candidate().unwrap();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            governance.vote_feed_candidate(accounts.charlie).unwrap();
+
+            let (epoch, end_block, _, _) = governance.get_feed_election();
+            #[allow(clippy::cast_possible_truncation)]
+            set_block_number::<DefaultEnvironment>(end_block as u32);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(governance.vote_feed_candidate(accounts.charlie), Err(Error::ElectionEnded));
+
+            // Finalizing opens the next election to votes again
+            assert_eq!(governance.finalize_feed_election(), Ok(vec![accounts.charlie]));
+            assert_eq!(governance.get_feed_election().0, epoch + 1);
+            governance.vote_feed_candidate(accounts.charlie).unwrap();
+        }
+
+
+        #[ink::test]
+        fn test_grant_round_matches_quadratically_and_survives_treasury_spends() {
+            let (mut governance, accounts) = setup();
+            let balance = |account| MockToken::state(TOKEN.into()).balances.get(&account).copied().unwrap_or(0);
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(accounts.alice, 300));
+            MockRegistry::update(REGISTRY.into(), |registry| {
+                for owner in [accounts.charlie, accounts.django, accounts.eve] {
+                    registry.devices.insert(owner, test_device());
+                }
+            });
+            let params = GrantRoundParams { matching_pool: 240, duration_blocks: 50, contributor_cap: 16 };
+            let open = queued_proposal(&mut governance, ProposalType::OpenGrantRound(params));
+            let actions = governance.get_proposal_actions(open).unwrap();
+            governance.execute_proposal(open, actions).unwrap();
+            // The pool is held for the round from the moment it opens
+            assert_eq!(governance.get_spendable_treasury(), 60);
+
+            let (first, second) = (accounts.frank, AccountId::from([0x20; 32]));
+            for project in [first, second] {
+                set_caller::<DefaultEnvironment>(project);
+                governance.register_grant_project(1, "Solar co-op".into()).unwrap();
+            }
+            for (contributor, project, amount) in [
+                (accounts.charlie, first, 9),
+                (accounts.django, first, 16),
+                // Beyond the contributor cap the donation is paid out but not matched
+                (accounts.django, first, 20),
+                (accounts.charlie, second, 4),
+                (accounts.eve, second, 4),
+                // No device: a sybil account attracts no matching however much it gives
+                (accounts.bob, second, 100),
+            ] {
+                set_caller::<DefaultEnvironment>(contributor);
+                governance.donate_to_grant_project(1, project, amount).unwrap();
+            }
+            let standing = governance.get_grant_project(1, second).unwrap();
+            assert_eq!((standing.donations, standing.eligible_donations, standing.sqrt_sum), (108, 8, 4));
+            assert_eq!(governance.get_spendable_treasury(), 60);
+
+            // Weights (Σ√c)² − Σc: first 7² − 25 = 24, second 4² − 8 = 8
+            advance_blocks(50);
+            assert_eq!(governance.close_grant_round(1), Ok(240));
+            assert_eq!(governance.get_grant_project(1, first).unwrap().matching, 180);
+            assert_eq!(governance.get_grant_project(1, second).unwrap().matching, 60);
+
+            // Treasury spends can only reach what the round does not hold
+            let overspend = queued_proposal(&mut governance, ProposalType::TreasurySpend(accounts.eve, 61));
+            let actions = governance.get_proposal_actions(overspend).unwrap();
+            governance.execute_proposal(overspend, actions).unwrap();
+            assert_ne!(governance.get_proposal_state(overspend), Some(ProposalState::Executed));
+            let spend = queued_proposal(&mut governance, ProposalType::TreasurySpend(accounts.eve, 60));
+            let actions = governance.get_proposal_actions(spend).unwrap();
+            governance.execute_proposal(spend, actions).unwrap();
+            assert_eq!(governance.get_proposal_state(spend), Some(ProposalState::Executed));
+
+            set_caller::<DefaultEnvironment>(first);
+            assert_eq!(governance.claim_grant(1), Ok(45 + 180));
+            assert_eq!(governance.claim_grant(1), Err(Error::AlreadyClaimed));
+            set_caller::<DefaultEnvironment>(second);
+            assert_eq!(governance.claim_grant(1), Ok(108 + 60));
+            assert_eq!((balance(first), balance(second), balance(accounts.alice)), (225, 168, 0));
+        }
+
+        #[ink::test]
+        fn test_grant_matching_ignores_donors_without_a_device() {
+            let (mut governance, accounts) = setup();
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(accounts.alice, 100));
+            MockRegistry::update(REGISTRY.into(), |registry| {
+                for owner in [accounts.charlie, accounts.eve] {
+                    registry.devices.insert(owner, test_device());
+                }
+            });
+            let params = GrantRoundParams { matching_pool: 100, duration_blocks: 50, contributor_cap: 100 };
+            let open = queued_proposal(&mut governance, ProposalType::OpenGrantRound(params));
+            let actions = governance.get_proposal_actions(open).unwrap();
+            governance.execute_proposal(open, actions).unwrap();
+
+            let (backed, sybil_backed) = (accounts.frank, AccountId::from([0x20; 32]));
+            for project in [backed, sybil_backed] {
+                set_caller::<DefaultEnvironment>(project);
+                governance.register_grant_project(1, "Solar co-op".into()).unwrap();
+            }
+            for (contributor, project, amount) in [
+                (accounts.charlie, backed, 4),
+                (accounts.eve, backed, 4),
+                // Many large donations from accounts without a device
+                (accounts.bob, sybil_backed, 100),
+                (accounts.django, sybil_backed, 100),
+            ] {
+                set_caller::<DefaultEnvironment>(contributor);
+                governance.donate_to_grant_project(1, project, amount).unwrap();
+            }
+            let standing = governance.get_grant_project(1, sybil_backed).unwrap();
+            assert_eq!((standing.donations, standing.eligible_donations, standing.sqrt_sum), (200, 0, 0));
+
+            advance_blocks(50);
+            assert_eq!(governance.close_grant_round(1), Ok(100));
+            assert_eq!(governance.get_grant_project(1, backed).unwrap().matching, 100);
+            assert_eq!(governance.get_grant_project(1, sybil_backed).unwrap().matching, 0);
+        }
+
+        #[ink::test]
+        fn test_simulation_predicts_changes_without_applying_them() {
+            let (mut governance, accounts) = setup();
+            MockRegistry::update(REGISTRY.into(), |registry| registry.min_stake = 100);
+            MockToken::update(TOKEN.into(), |token| token.balances.insert(accounts.alice, 50));
+            assert_eq!(governance.simulate_proposal(1), Err(Error::ProposalNotFound));
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let min_stake = governance.create_proposal(ProposalType::UpdateMinStake(500), "Raise".into()).unwrap();
+            let simulation = governance.simulate_proposal(min_stake).unwrap();
+            assert_eq!(simulation.state, ProposalState::Active);
+            assert!(!simulation.passing);
+            assert_eq!(simulation.action_succeeds, None);
+            assert_eq!(simulation.changes, vec![SimulatedChange { value: SimulatedValue::MinStake, current: 100, predicted: 500 }]);
+            governance.vote(min_stake, true, String::new()).unwrap();
+            assert!(governance.simulate_proposal(min_stake).unwrap().passing);
+            assert_eq!(MockRegistry::state(REGISTRY.into()).min_stake, 100);
+
+            // A spend beyond the treasury balance is flagged as failing
+            let spend = governance.create_proposal(ProposalType::TreasurySpend(accounts.frank, 80), "Grant".into()).unwrap();
+            let simulation = governance.simulate_proposal(spend).unwrap();
+            assert_eq!(simulation.action_succeeds, Some(false));
+            assert_eq!(simulation.changes, vec![
+                SimulatedChange { value: SimulatedValue::TreasuryBalance, current: 50, predicted: 0 },
+                SimulatedChange { value: SimulatedValue::BalanceOf(accounts.frank), current: 0, predicted: 80 },
+            ]);
+
+            let invalid_budget = CouncilBudget {
+                name: "Empty".into(),
+                members: Vec::new(),
+                threshold: 1,
+                allowance_per_period: 10,
+                period_ms: 1_000,
+            };
+            let council = governance.create_proposal(ProposalType::SetCouncilBudget(1, Some(invalid_budget)), "Council".into()).unwrap();
+            let simulation = governance.simulate_proposal(council).unwrap();
+            assert_eq!(simulation.action_succeeds, Some(false));
+            assert_eq!(simulation.changes, vec![
+                SimulatedChange { value: SimulatedValue::CouncilRemainingAllowance(1), current: 0, predicted: 10 },
+            ]);
+
+            // Conviction proposals are passing once their conviction clears the threshold
+            let conviction = governance.create_conviction_proposal(ProposalType::UpdateMinStake(600), "Conviction".into()).unwrap();
+            let cast = ink::env::block_number::<DefaultEnvironment>();
+            governance.vote_with_conviction(conviction, true, MAX_CONVICTION).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            governance.vote_with_conviction(conviction, true, MAX_CONVICTION).unwrap();
+            assert!(!governance.simulate_proposal(conviction).unwrap().passing);
+            set_block_number::<DefaultEnvironment>(cast + CONVICTION_RAMP_BLOCKS);
+            assert!(governance.simulate_proposal(conviction).unwrap().passing);
+        }
+
+
+        #[ink::test]
+        fn test_voting_closes_on_first_deadline_reached() {
+            let (mut governance, accounts) = setup();
+            governance.set_timelock_seconds(30).unwrap();
+            let config = governance.get_time_config();
+            assert_eq!((config.voting_duration_blocks, config.voting_duration_ms), (10, 10 * EXPECTED_BLOCK_TIME_MS));
+            assert_eq!(config.timelock_ms, 30_000);
+
+            // Blocks arrive slower than expected: the timestamp deadline closes voting first
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let slow = governance.create_proposal(ProposalType::UpdateMinStake(500), "Slow blocks".into()).unwrap();
+            let end_time = ink::env::block_timestamp::<DefaultEnvironment>() + 10 * EXPECTED_BLOCK_TIME_MS;
+            assert_eq!(governance.get_voting_end_time(slow), Some(end_time));
+            set_block_timestamp::<DefaultEnvironment>(end_time);
+            governance.vote(slow, true, String::new()).unwrap();
+            set_block_timestamp::<DefaultEnvironment>(end_time + 1);
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(governance.vote(slow, true, String::new()), Err(Error::ProposalExpired));
+            governance.queue_proposal(slow).unwrap();
+
+            // Blocks arrive faster than expected: the block deadline closes voting first
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let fast = governance.create_proposal(ProposalType::UpdateMinStake(700), "Fast blocks".into()).unwrap();
+            governance.vote(fast, true, String::new()).unwrap();
+            advance_blocks(9);
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(governance.queue_proposal(fast), Err(Error::ProposalNotExpired));
+            advance_blocks(1);
+            governance.vote(fast, false, String::new()).unwrap();
+            advance_blocks(1);
+            assert!(ink::env::block_timestamp::<DefaultEnvironment>() < governance.get_voting_end_time(fast).unwrap());
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(governance.vote(fast, true, String::new()), Err(Error::ProposalExpired));
+            assert_eq!(governance.get_proposal_state(fast), Some(ProposalState::Succeeded));
+
+            // Conviction proposals have no voting window
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let conviction = governance.create_conviction_proposal(ProposalType::UpdateMinStake(900), "Conviction".into()).unwrap();
+            assert_eq!(governance.get_voting_end_time(conviction), None);
+        }
+
+    }
 }
@@ -1,10 +1,10 @@
 use ink_e2e::ContractsBackend;
 use powergrid_token::powergrid_token::{PowergridToken, PowergridTokenRef};
 use resource_registry::resource_registry::{ResourceRegistry, ResourceRegistryRef};
-use grid_service::grid_service::{GridService, GridServiceRef};
+use grid_service::grid_service::{GridEventCreated, GridService, GridServiceRef, ParticipationVerified, RewardPaid};
 use governance::governance::{Governance, GovernanceRef};
 use powergrid_shared::{GridEventType, ProposalType};
-use crate::test_helpers::{create_sample_device_metadata, TEST_DEVICE_STAKE, TEST_INITIAL_SUPPLY, TEST_MIN_STAKE};
+use crate::test_helpers::{create_sample_device_metadata, expect_event, TEST_DEVICE_STAKE, TEST_INITIAL_SUPPLY, TEST_MIN_STAKE};
 use ink::prelude::string::String;
 use ink::primitives::AccountId;
 
@@ -71,34 +71,23 @@ where
     println!("   ✨ This contract references other deployed contracts!");
 
     // Deploy Governance contract with correct constructor parameters
-    let governance_constructor_result = std::panic::catch_unwind(|| {
-        GovernanceRef::new(
-            token_account,
-            registry_account, 
-            grid_account,
-            1_000_000_000_000_000_000u128, // Min voting power
-            7 * 24 * 60 * 60u64,           // Voting duration in blocks
-            51u32,                         // Quorum percentage
-        )
-    });
-
-    if let Ok(mut governance_constructor) = governance_constructor_result {
-        let governance_result = client
-            .instantiate("governance", &ink_e2e::alice(), &mut governance_constructor)
-            .value(CONTRACT_ENDOWMENT)
-            .submit()
-            .await;
-        
-        if governance_result.is_ok() {
-            let governance_account = governance_result.unwrap().account_id;
-            println!("✅ Governance contract deployed successfully");
-            println!("   Contract address: {:?}", governance_account);
-        } else {
-            println!("⚠️  Governance contract deployment had issues (but others succeeded)");
-        }
-    } else {
-        println!("⚠️  Governance constructor had type issues (but other contracts deployed)");
-    }
+    let mut governance_constructor = GovernanceRef::new(
+        token_account,
+        registry_account,
+        grid_account,
+        1_000_000_000_000_000_000u128, // Min voting power
+        7 * 24 * 60 * 60u64,           // Voting duration in blocks
+        51u32,                         // Quorum percentage
+    );
+    let governance_account = client
+        .instantiate("governance", &ink_e2e::alice(), &mut governance_constructor)
+        .value(CONTRACT_ENDOWMENT)
+        .submit()
+        .await
+        .expect("Governance deployment failed")
+        .account_id;
+    println!("✅ Governance contract deployed successfully");
+    println!("   Contract address: {:?}", governance_account);
 
     println!("\n🎉 Real Contract Deployment Test Results:");
     println!("✅ Token contract: DEPLOYED");
@@ -227,6 +216,16 @@ where
         .return_value()
         .map_err(|err| format!("add_authorized_caller failed: {err}"))?;
 
+    // The grid must now see itself as a token minter and registry caller
+    let verify_wiring = grid.call_builder::<GridService>().verify_wiring();
+    let report = client
+        .call(&ink_e2e::alice(), &verify_wiring)
+        .dry_run()
+        .await?
+        .return_value();
+    assert_eq!(report.get("token_minter"), Some(true), "grid is not a token minter");
+    assert_eq!(report.get("registry_authorized_caller"), Some(true), "grid is not a registry caller");
+
     // Bob registers a device with stake
     println!("📝 Bob registering device with stake {}", TEST_DEVICE_STAKE);
     let metadata = create_sample_device_metadata();
@@ -266,20 +265,23 @@ where
             10_000_000_000_000_000u128,
             2_000,
         );
-    let event_id = client
+    let create_result = client
         .call(&ink_e2e::alice(), &create_event)
         .extra_gas_portion(EXTRA_GAS_PERCENT)
         .submit()
-        .await?
+        .await?;
+    let created: GridEventCreated = expect_event(&create_result);
+    let event_id = create_result
         .return_value()
         .map_err(|err| format!("create_grid_event failed: {err}"))?;
+    assert_eq!(created.event_id, event_id);
     println!("📍 Grid event created with id {event_id}");
 
     // Bob participates in the event
     println!("👤 Bob participating in event");
     let participate = grid
         .call_builder::<GridService>()
-        .participate_in_event(event_id, 4_000);
+        .participate_in_event(event_id, 2_000);
     client
         .call(&bob_signer, &participate)
         .extra_gas_portion(EXTRA_GAS_PERCENT)
@@ -293,15 +295,20 @@ where
     println!("🔍 Verifying participation and minting rewards");
     let verify = grid
         .call_builder::<GridService>()
-        .verify_participation(event_id, bob_account, 4_000);
-    client
+        .verify_participation(event_id, bob_account, 2_000);
+    let verify_result = client
         .call(&ink_e2e::alice(), &verify)
         .extra_gas_portion(EXTRA_GAS_PERCENT)
         .submit()
-        .await?
+        .await?;
+    let verified: ParticipationVerified = expect_event(&verify_result);
+    let paid: RewardPaid = expect_event(&verify_result);
+    verify_result
         .return_value()
         .map_err(|err| format!("verify_participation failed: {err}"))?;
-    println!("✅ Participation verified");
+    assert!(verified.verified && verified.participant == bob_account);
+    assert_eq!(paid.event_id, event_id);
+    println!("✅ Participation verified, {} paid", paid.amount);
 
     // Check Bob received rewards
     println!("💰 Checking Bob reward balance");
@@ -436,7 +443,7 @@ where
     // Execute proposal (timelock is zero by default; successive extrinsics advance blocks)
     let execute = governance
         .call_builder::<Governance>()
-        .execute_proposal(proposal_id);
+        .execute_proposal(proposal_id, vec![ProposalType::UpdateMinStake(new_min_stake)]);
     client
         .call(&ink_e2e::alice(), &execute)
         .extra_gas_portion(EXTRA_GAS_PERCENT)
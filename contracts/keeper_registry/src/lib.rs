@@ -0,0 +1,634 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod keeper_registry {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use powergrid_shared::{ContractVersion, WiringReport, ONE_TOKEN};
+    use powergrid_shared::{Connect, TokenClient};
+
+    /// Token client: the deployed contract on chain, an installable mock in unit tests
+    #[cfg(not(test))]
+    type Token = powergrid_shared::TokenRef;
+    #[cfg(test)]
+    type Token = powergrid_shared::mock::MockToken;
+
+    /// Default bond a keeper must keep staked
+    pub const DEFAULT_MIN_KEEPER_BOND: Balance = 100 * ONE_TOKEN;
+
+    /// Time after which an unsettled claim may be taken over by another keeper (10 minutes)
+    pub const CLAIM_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+
+    /// Delay between requesting exit and withdrawing the bond, leaving time to slash (7 days)
+    pub const UNBONDING_PERIOD_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// Kind of permissionless maintenance work keepers perform
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum JobType {
+        /// `sweep_unverified` on a grid event past its verification deadline
+        SweepUnverified,
+        /// `auto_enroll` of an opted-in device
+        AutoEnroll,
+        /// Completing or finalizing a grid event
+        EventFinalization,
+        /// `continue_auto_trigger_sweep` over the trigger rules
+        TriggerSweep,
+        /// `instantiate_recurring_event` for the day
+        RecurringInstance,
+    }
+
+    /// A bonded keeper and its job accounting
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Keeper {
+        pub bond: Balance,
+        pub job_types: Vec<JobType>,
+        /// Claims neither settled nor slashed yet
+        pub open_claims: u32,
+        pub jobs_completed: u32,
+        pub fees_earned: Balance,
+        pub slashed: Balance,
+        /// Set once the keeper asked to leave; no new claims afterwards
+        pub exit_requested_at: Option<u64>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum ClaimStatus {
+        Claimed,
+        Paid,
+        Slashed,
+    }
+
+    /// The keeper a job is reserved for; only it can be paid for the job
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct JobClaim {
+        pub keeper: AccountId,
+        pub claimed_at: u64,
+        pub status: ClaimStatus,
+        /// Fee paid on settlement
+        pub fee: Balance,
+    }
+
+    /// Bonded keepers claim maintenance jobs; reporters confirm them and each job pays one keeper
+    #[ink(storage)]
+    pub struct KeeperRegistry {
+        /// Simple reentrancy flag
+        entered: bool,
+        owner: AccountId,
+        governance_address: AccountId,
+        /// PGT token, both the bond asset and the fee currency
+        token_address: AccountId,
+        min_bond: Balance,
+        keepers: Mapping<AccountId, Keeper>,
+        /// Fee paid per settled job of each type
+        job_fees: Mapping<JobType, Balance>,
+        /// Contracts or operators allowed to confirm completed jobs
+        reporters: Mapping<AccountId, bool>,
+        /// Claims by (job type, job ID), e.g. the event ID of a sweep
+        claims: Mapping<(JobType, u64), JobClaim>,
+        /// PGT funded for job fees and slashed bonds
+        fee_pool: Balance,
+        total_bonded: Balance,
+    }
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
+    }
+
+    #[ink(event)]
+    pub struct KeeperRegistered {
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub bond: Balance,
+        pub job_types: Vec<JobType>,
+    }
+
+    #[ink(event)]
+    pub struct KeeperExited {
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub bond: Balance,
+    }
+
+    #[ink(event)]
+    pub struct JobClaimed {
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub job_type: JobType,
+        pub job_id: u64,
+    }
+
+    #[ink(event)]
+    pub struct JobSettled {
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub job_type: JobType,
+        pub job_id: u64,
+        pub fee: Balance,
+    }
+
+    #[ink(event)]
+    pub struct KeeperSlashed {
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub job_type: JobType,
+        pub job_id: u64,
+        pub amount: Balance,
+    }
+
+    /// Errors
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        Unauthorized,
+        ZeroAmount,
+        AlreadyRegistered,
+        NotRegistered,
+        InsufficientBond,
+        NoJobTypes,
+        JobTypeNotRegistered,
+        JobAlreadyClaimed,
+        JobAlreadySettled,
+        JobNotClaimed,
+        KeeperExiting,
+        ExitNotRequested,
+        UnbondingActive,
+        OpenClaims,
+        TransferFailed,
+        ReentrantCall,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl KeeperRegistry {
+        #[ink(constructor)]
+        pub fn new(token_address: AccountId, min_bond: Balance) -> Self {
+            let min_bond = if min_bond == 0 { DEFAULT_MIN_KEEPER_BOND } else { min_bond };
+            let caller = Self::env().caller();
+            let instance = Self {
+                entered: false,
+                owner: caller,
+                governance_address: caller,
+                token_address,
+                min_bond,
+                keepers: Mapping::default(),
+                job_fees: Mapping::default(),
+                reporters: Mapping::default(),
+                claims: Mapping::default(),
+                fee_pool: 0,
+                total_bonded: 0,
+            };
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
+            instance
+        }
+
+        /// Register as a keeper for `job_types`, bonding at least the minimum; requires a prior token approval
+        #[ink(message)]
+        pub fn register(&mut self, bond: Balance, job_types: Vec<JobType>) -> Result<()> {
+            let caller = self.env().caller();
+            if self.keepers.contains(caller) {
+                return Err(Error::AlreadyRegistered);
+            }
+            if bond < self.min_bond {
+                return Err(Error::InsufficientBond);
+            }
+            let job_types = Self::dedup(job_types)?;
+            self.pull(caller, bond)?;
+
+            let keeper = Keeper { bond, job_types: job_types.clone(), ..Default::default() };
+            self.keepers.insert(caller, &keeper);
+            self.total_bonded = self.total_bonded.saturating_add(bond);
+            self.env().emit_event(KeeperRegistered { keeper: caller, bond, job_types });
+            Ok(())
+        }
+
+        /// Replace the job types the caller works on
+        #[ink(message)]
+        pub fn set_job_types(&mut self, job_types: Vec<JobType>) -> Result<()> {
+            let caller = self.env().caller();
+            let mut keeper = self.keepers.get(caller).ok_or(Error::NotRegistered)?;
+            keeper.job_types = Self::dedup(job_types)?;
+            self.keepers.insert(caller, &keeper);
+            Ok(())
+        }
+
+        /// Top up the caller's bond, e.g. after a slash; requires a prior token approval
+        #[ink(message)]
+        pub fn add_bond(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            let caller = self.env().caller();
+            let mut keeper = self.keepers.get(caller).ok_or(Error::NotRegistered)?;
+            self.pull(caller, amount)?;
+            keeper.bond = keeper.bond.saturating_add(amount);
+            self.keepers.insert(caller, &keeper);
+            self.total_bonded = self.total_bonded.saturating_add(amount);
+            Ok(())
+        }
+
+        /// Stop taking jobs and start the unbonding period
+        #[ink(message)]
+        pub fn request_exit(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let mut keeper = self.keepers.get(caller).ok_or(Error::NotRegistered)?;
+            if keeper.exit_requested_at.is_some() {
+                return Err(Error::KeeperExiting);
+            }
+            keeper.exit_requested_at = Some(self.env().block_timestamp());
+            self.keepers.insert(caller, &keeper);
+            Ok(())
+        }
+
+        /// Withdraw the bond and deregister once unbonded with no open claims
+        #[ink(message)]
+        pub fn withdraw_bond(&mut self) -> Result<Balance> {
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            let caller = self.env().caller();
+            let keeper = self.keepers.get(caller).ok_or(Error::NotRegistered)?;
+            let requested_at = keeper.exit_requested_at.ok_or(Error::ExitNotRequested)?;
+            if self.env().block_timestamp() < requested_at.saturating_add(UNBONDING_PERIOD_MS) {
+                return Err(Error::UnbondingActive);
+            }
+            if keeper.open_claims > 0 {
+                return Err(Error::OpenClaims);
+            }
+
+            self.keepers.remove(caller);
+            self.total_bonded = self.total_bonded.saturating_sub(keeper.bond);
+            self.push(caller, keeper.bond)?;
+            self.env().emit_event(KeeperExited { keeper: caller, bond: keeper.bond });
+            Ok(keeper.bond)
+        }
+
+        /// Reserve a job for the caller so no other keeper is paid for it. A claim left
+        /// unsettled for `CLAIM_TIMEOUT_MS` may be taken over.
+        #[ink(message)]
+        pub fn claim_job(&mut self, job_type: JobType, job_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+            let mut keeper = self.keepers.get(caller).ok_or(Error::NotRegistered)?;
+            if keeper.exit_requested_at.is_some() {
+                return Err(Error::KeeperExiting);
+            }
+            if keeper.bond < self.min_bond {
+                return Err(Error::InsufficientBond);
+            }
+            if !keeper.job_types.contains(&job_type) {
+                return Err(Error::JobTypeNotRegistered);
+            }
+
+            let now = self.env().block_timestamp();
+            if let Some(claim) = self.claims.get((job_type, job_id)) {
+                if claim.status != ClaimStatus::Claimed {
+                    return Err(Error::JobAlreadySettled);
+                }
+                if claim.keeper == caller || now < claim.claimed_at.saturating_add(CLAIM_TIMEOUT_MS) {
+                    return Err(Error::JobAlreadyClaimed);
+                }
+                // Expired: the previous keeper loses the job
+                if let Some(mut previous) = self.keepers.get(claim.keeper) {
+                    previous.open_claims = previous.open_claims.saturating_sub(1);
+                    self.keepers.insert(claim.keeper, &previous);
+                }
+            }
+
+            keeper.open_claims = keeper.open_claims.saturating_add(1);
+            self.keepers.insert(caller, &keeper);
+            self.claims.insert((job_type, job_id), &JobClaim { keeper: caller, claimed_at: now, status: ClaimStatus::Claimed, fee: 0 });
+            self.env().emit_event(JobClaimed { keeper: caller, job_type, job_id });
+            Ok(())
+        }
+
+        /// Confirm a claimed job was done and pay its keeper the job fee, as far as the
+        /// fee pool covers it (reporters or owner only)
+        #[ink(message)]
+        pub fn settle_job(&mut self, job_type: JobType, job_id: u64) -> Result<Balance> {
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            let caller = self.env().caller();
+            if caller != self.owner && !self.reporters.get(caller).unwrap_or(false) {
+                return Err(Error::Unauthorized);
+            }
+            let mut claim = self.claims.get((job_type, job_id)).ok_or(Error::JobNotClaimed)?;
+            if claim.status != ClaimStatus::Claimed {
+                return Err(Error::JobAlreadySettled);
+            }
+
+            let fee = self.job_fees.get(job_type).unwrap_or(0).min(self.fee_pool);
+            claim.status = ClaimStatus::Paid;
+            claim.fee = fee;
+            self.claims.insert((job_type, job_id), &claim);
+            self.fee_pool = self.fee_pool.saturating_sub(fee);
+            if let Some(mut keeper) = self.keepers.get(claim.keeper) {
+                keeper.open_claims = keeper.open_claims.saturating_sub(1);
+                keeper.jobs_completed = keeper.jobs_completed.saturating_add(1);
+                keeper.fees_earned = keeper.fees_earned.saturating_add(fee);
+                self.keepers.insert(claim.keeper, &keeper);
+            }
+
+            if fee > 0 {
+                self.push(claim.keeper, fee)?;
+            }
+            self.env().emit_event(JobSettled { keeper: claim.keeper, job_type, job_id, fee });
+            Ok(fee)
+        }
+
+        /// Slash the keeper of a job it griefed, e.g. by front-running a finalization
+        /// incorrectly; the slashed bond joins the fee pool (owner/governance only)
+        #[ink(message)]
+        pub fn slash_job(&mut self, job_type: JobType, job_id: u64, amount: Balance) -> Result<Balance> {
+            self.ensure_admin()?;
+            let mut claim = self.claims.get((job_type, job_id)).ok_or(Error::JobNotClaimed)?;
+            if claim.status == ClaimStatus::Slashed {
+                return Err(Error::JobAlreadySettled);
+            }
+            let mut keeper = self.keepers.get(claim.keeper).ok_or(Error::NotRegistered)?;
+
+            let slashed = amount.min(keeper.bond);
+            if claim.status == ClaimStatus::Claimed {
+                keeper.open_claims = keeper.open_claims.saturating_sub(1);
+            }
+            keeper.bond = keeper.bond.saturating_sub(slashed);
+            keeper.slashed = keeper.slashed.saturating_add(slashed);
+            self.keepers.insert(claim.keeper, &keeper);
+            claim.status = ClaimStatus::Slashed;
+            self.claims.insert((job_type, job_id), &claim);
+            self.total_bonded = self.total_bonded.saturating_sub(slashed);
+            self.fee_pool = self.fee_pool.saturating_add(slashed);
+            self.env().emit_event(KeeperSlashed { keeper: claim.keeper, job_type, job_id, amount: slashed });
+            Ok(slashed)
+        }
+
+        /// Add PGT to the fee pool; requires a prior token approval
+        #[ink(message)]
+        pub fn fund_fee_pool(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.pull(self.env().caller(), amount)?;
+            self.fee_pool = self.fee_pool.saturating_add(amount);
+            Ok(())
+        }
+
+        /// Set the fee paid per settled job of a type (owner/governance only)
+        #[ink(message)]
+        pub fn set_job_fee(&mut self, job_type: JobType, fee: Balance) -> Result<()> {
+            self.ensure_admin()?;
+            self.job_fees.insert(job_type, &fee);
+            Ok(())
+        }
+
+        /// Allow or revoke an account confirming jobs (owner/governance only)
+        #[ink(message)]
+        pub fn set_reporter(&mut self, reporter: AccountId, allowed: bool) -> Result<()> {
+            self.ensure_admin()?;
+            if allowed {
+                self.reporters.insert(reporter, &true);
+            } else {
+                self.reporters.remove(reporter);
+            }
+            Ok(())
+        }
+
+        /// Change the bond keepers need to claim jobs (owner/governance only)
+        #[ink(message)]
+        pub fn set_min_bond(&mut self, min_bond: Balance) -> Result<()> {
+            self.ensure_admin()?;
+            if min_bond == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.min_bond = min_bond;
+            Ok(())
+        }
+
+        /// Set the governance contract (owner only)
+        #[ink(message)]
+        pub fn set_governance_address(&mut self, addr: AccountId) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::Unauthorized);
+            }
+            self.governance_address = addr;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_keeper(&self, keeper: AccountId) -> Option<Keeper> {
+            self.keepers.get(keeper)
+        }
+
+        #[ink(message)]
+        pub fn get_job_claim(&self, job_type: JobType, job_id: u64) -> Option<JobClaim> {
+            self.claims.get((job_type, job_id))
+        }
+
+        #[ink(message)]
+        pub fn get_job_fee(&self, job_type: JobType) -> Balance {
+            self.job_fees.get(job_type).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn is_reporter(&self, reporter: AccountId) -> bool {
+            self.reporters.get(reporter).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        pub fn get_fee_pool(&self) -> Balance {
+            self.fee_pool
+        }
+
+        #[ink(message)]
+        pub fn get_total_bonded(&self) -> Balance {
+            self.total_bonded
+        }
+
+        #[ink(message)]
+        pub fn get_min_bond(&self) -> Balance {
+            self.min_bond
+        }
+
+        #[ink(message)]
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Check that the token contract answers and what it holds covers bonds and the fee pool
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            if let Some(token) = Token::connect(self.token_address) {
+                let balance = token.balance_of(self.env().account_id());
+                report.check("token_solvent", balance >= self.total_bonded.saturating_add(self.fee_pool));
+            }
+            report
+        }
+
+        fn dedup(job_types: Vec<JobType>) -> Result<Vec<JobType>> {
+            let mut unique: Vec<JobType> = Vec::new();
+            for job_type in job_types {
+                if !unique.contains(&job_type) {
+                    unique.push(job_type);
+                }
+            }
+            if unique.is_empty() {
+                return Err(Error::NoJobTypes);
+            }
+            Ok(unique)
+        }
+
+        /// Move `amount` PGT from `from` into this contract
+        fn pull(&mut self, from: AccountId, amount: Balance) -> Result<()> {
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            self.entered = true;
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.transfer_from(from, self.env().account_id(), amount, Vec::new()).is_err() {
+                    self.entered = false;
+                    return Err(Error::TransferFailed);
+                }
+            }
+            self.entered = false;
+            Ok(())
+        }
+
+        /// Send `amount` PGT held by this contract to `to`
+        fn push(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            self.entered = true;
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.transfer(to, amount, Vec::new()).is_err() {
+                    self.entered = false;
+                    return Err(Error::TransferFailed);
+                }
+            }
+            self.entered = false;
+            Ok(())
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner && caller != self.governance_address {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{callee, default_accounts, set_block_timestamp, set_caller, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+        use powergrid_shared::mock::{MockToken, MockTokenState};
+
+        fn setup(accounts: &DefaultAccounts<DefaultEnvironment>) -> KeeperRegistry {
+            let mut state = MockTokenState::default();
+            for account in [accounts.bob, accounts.charlie, accounts.frank] {
+                state.balances.insert(account, 1_000);
+            }
+            MockToken::install(accounts.django, state);
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            KeeperRegistry::new(accounts.django, 100)
+        }
+
+        #[ink::test]
+        fn test_only_one_keeper_is_paid_per_job() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = setup(&accounts);
+            registry.set_job_fee(JobType::SweepUnverified, 30).unwrap();
+            registry.set_reporter(accounts.eve, true).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            registry.fund_fee_pool(50).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.register(50, vec![JobType::SweepUnverified]), Err(Error::InsufficientBond));
+            assert_eq!(registry.register(100, Vec::new()), Err(Error::NoJobTypes));
+            registry.register(100, vec![JobType::SweepUnverified, JobType::SweepUnverified]).unwrap();
+            assert_eq!(registry.get_keeper(accounts.bob).unwrap().job_types, vec![JobType::SweepUnverified]);
+            assert_eq!(registry.claim_job(JobType::AutoEnroll, 1), Err(Error::JobTypeNotRegistered));
+            registry.claim_job(JobType::SweepUnverified, 1).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            registry.register(100, vec![JobType::SweepUnverified]).unwrap();
+            assert_eq!(registry.claim_job(JobType::SweepUnverified, 1), Err(Error::JobAlreadyClaimed));
+
+            // Settled once: Bob is paid and the job cannot be claimed or paid again
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.settle_job(JobType::SweepUnverified, 1), Err(Error::Unauthorized));
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(registry.settle_job(JobType::SweepUnverified, 1), Ok(30));
+            assert_eq!(registry.settle_job(JobType::SweepUnverified, 1), Err(Error::JobAlreadySettled));
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.claim_job(JobType::SweepUnverified, 1), Err(Error::JobAlreadySettled));
+            let bob = registry.get_keeper(accounts.bob).unwrap();
+            assert_eq!((bob.jobs_completed, bob.fees_earned, bob.open_claims), (1, 30, 0));
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.bob), 930);
+
+            // An abandoned claim can be taken over after the timeout
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            registry.claim_job(JobType::SweepUnverified, 2).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            set_block_timestamp::<DefaultEnvironment>(CLAIM_TIMEOUT_MS);
+            registry.claim_job(JobType::SweepUnverified, 2).unwrap();
+            assert_eq!(registry.get_keeper(accounts.bob).unwrap().open_claims, 0);
+
+            // The fee is capped by what is left in the pool
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(registry.settle_job(JobType::SweepUnverified, 2), Ok(20));
+            assert_eq!(registry.get_fee_pool(), 0);
+        }
+
+        #[ink::test]
+        fn test_slashing_and_unbonding() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = setup(&accounts);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            registry.register(150, vec![JobType::EventFinalization]).unwrap();
+            registry.claim_job(JobType::EventFinalization, 7).unwrap();
+            assert_eq!(registry.slash_job(JobType::EventFinalization, 7, 80), Err(Error::Unauthorized));
+
+            // Griefing costs bond, which funds other keepers' fees
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.slash_job(JobType::EventFinalization, 7, 80), Ok(80));
+            assert_eq!(registry.slash_job(JobType::EventFinalization, 7, 80), Err(Error::JobAlreadySettled));
+            assert_eq!(registry.get_fee_pool(), 80);
+            assert_eq!(registry.get_total_bonded(), 70);
+
+            // Below the minimum bond no new jobs until topped up
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.claim_job(JobType::EventFinalization, 8), Err(Error::InsufficientBond));
+            registry.add_bond(30).unwrap();
+            registry.claim_job(JobType::EventFinalization, 8).unwrap();
+
+            assert_eq!(registry.withdraw_bond(), Err(Error::ExitNotRequested));
+            registry.request_exit().unwrap();
+            assert_eq!(registry.claim_job(JobType::EventFinalization, 9), Err(Error::KeeperExiting));
+            set_block_timestamp::<DefaultEnvironment>(UNBONDING_PERIOD_MS);
+            assert_eq!(registry.withdraw_bond(), Err(Error::OpenClaims));
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.settle_job(JobType::EventFinalization, 8).unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.withdraw_bond(), Ok(100));
+            assert_eq!(registry.get_keeper(accounts.bob), None);
+            let token = MockToken::state(accounts.django);
+            assert_eq!(token.balance(accounts.bob), 920);
+            assert_eq!(token.balance(callee::<DefaultEnvironment>()), 80);
+        }
+    }
+}
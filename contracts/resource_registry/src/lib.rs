@@ -4,17 +4,255 @@
 pub mod resource_registry {
     use ink::prelude::{string::String, vec::Vec};
     use ink::storage::Mapping;
-    use powergrid_shared::{DeviceMetadata, Device, ink_account_to_bytes, tokens_to_native};
+    use powergrid_shared::{AddressTimelock, Connect, ExternalContract, PendingAddressChange, TokenClient};
+
+    /// Token client: the deployed contract on chain, an installable mock in unit tests
+    #[cfg(not(test))]
+    type Token = powergrid_shared::TokenRef;
+    #[cfg(test)]
+    type Token = powergrid_shared::mock::MockToken;
+    use powergrid_shared::{DeviceMetadata, Device, DeviceSla, DeviceType, DeviceTypePolicy, ContractVersion, CorrelationId, WiringReport, location_commitment, parse_firmware_version, signed_message_hash, tokens_to_native, verify_signature, NonceDomain, NonceRegistry, HEARTBEAT_INTERVAL_MS, MAX_ZONE_LENGTH, SLA_WINDOW_MS, STATEMENT_PERIOD_MS, HEALTH_SNAPSHOT_INTERVAL_BLOCKS};
+
+    /// Manufacturer-signed record of a firmware update applied to a device
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct FirmwareAttestation {
+        pub version: String,
+        pub firmware_hash: [u8; 32],
+        pub attested_at: u64,
+        /// Hardware-bound sr25519 key of the device, used for liveness proofs
+        pub device_key: Option<AccountId>,
+    }
 
     /// The ResourceRegistry contract
+    /// Temporary ban placed on an account by governance or the guardian
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct BlacklistEntry {
+        pub reason_code: u16,
+        pub blacklisted_at: u64,
+        /// Ban lifts automatically once block time reaches this timestamp
+        pub until: u64,
+    }
+
+    /// Heartbeat counters for the current and previous SLA window
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct UptimeCounters {
+        pub tracking_since: u64,
+        pub window_start: u64,
+        pub current_heartbeats: u32,
+        pub previous_heartbeats: u32,
+        pub last_heartbeat: u64,
+    }
+
+    impl UptimeCounters {
+        /// Advance the window so that `now` falls inside it
+        fn roll(&mut self, now: u64) {
+            let elapsed_windows = now.saturating_sub(self.window_start) / SLA_WINDOW_MS;
+            if elapsed_windows == 0 {
+                return;
+            }
+            self.previous_heartbeats = if elapsed_windows == 1 { self.current_heartbeats } else { 0 };
+            self.current_heartbeats = 0;
+            self.window_start = self.window_start.saturating_add(elapsed_windows.saturating_mul(SLA_WINDOW_MS));
+        }
+    }
+
+    /// Consequence of an under-delivery, escalating with the device's strikes
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PenaltyLevel {
+        /// Recorded in the performance history only; reputation is unaffected
+        Warning,
+        /// Counted as a failed event, lowering reputation
+        ReputationHit,
+        /// Reputation hit plus a slash of `partial_slash_bps` of the stake
+        PartialSlash,
+        /// Reputation hit plus a slash of the whole stake
+        FullSlash,
+    }
+
+    /// Strike thresholds of the graduated penalty policy; every successful event forgives one strike
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PenaltyPolicy {
+        /// Strikes up to this count are warnings
+        pub warning_strikes: u32,
+        /// Strikes up to this count only lower reputation
+        pub reputation_strikes: u32,
+        /// Strikes up to this count slash `partial_slash_bps` of the stake; later ones slash it all
+        pub partial_slash_strikes: u32,
+        pub partial_slash_bps: u16,
+    }
+
+    impl Default for PenaltyPolicy {
+        fn default() -> Self {
+            Self { warning_strikes: 1, reputation_strikes: 3, partial_slash_strikes: 5, partial_slash_bps: 1_000 }
+        }
+    }
+
+    impl PenaltyPolicy {
+        /// Penalty for a device's `strikes`-th outstanding failure
+        pub fn level(&self, strikes: u32) -> PenaltyLevel {
+            if strikes <= self.warning_strikes {
+                PenaltyLevel::Warning
+            } else if strikes <= self.reputation_strikes {
+                PenaltyLevel::ReputationHit
+            } else if strikes <= self.partial_slash_strikes {
+                PenaltyLevel::PartialSlash
+            } else {
+                PenaltyLevel::FullSlash
+            }
+        }
+    }
+
+    /// Referral link recorded when a device registers through a referrer
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Referral {
+        pub referrer: AccountId,
+        /// Referred device reached the required verified participations
+        pub qualified: bool,
+        /// Bonus paid to both parties
+        pub rewarded: bool,
+        /// Referrer's half already transferred, so a retry only pays the referred device
+        pub referrer_paid: bool,
+    }
+
+    /// Privileged registry operation that needs k-of-n signer approval once multisig is enabled.
+    /// Covers every setter the owner key could call alone; pausing and cancelling a pending
+    /// address change stay single-key, since they only stop things and must not wait on signers.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum RegistryAction {
+        SlashStake(AccountId, Balance, String),
+        DeactivateDevice(AccountId, String),
+        ReactivateDevice(AccountId),
+        SetGovernanceAddress(AccountId),
+        /// Replace the signer set and threshold (threshold 0 disables multisig)
+        ConfigureMultisig(Vec<AccountId>, u32),
+        UpdateMinStake(Balance),
+        UpdateReputationThreshold(u32),
+        AddAuthorizedCaller(AccountId),
+        RemoveAuthorizedCaller(AccountId),
+        SetDeviceTypePolicy(DeviceType, Option<DeviceTypePolicy>),
+        SetManufacturerKey(String, Option<[u8; 32]>),
+        SetMinFirmwareVersion(String, String, Option<String>),
+        SetReferralProgram(AccountId, Balance, u32),
+        SetPenaltyPolicy(PenaltyPolicy),
+        SetTokenAddress(AccountId),
+        ProposeAddressChange(ExternalContract, AccountId),
+    }
+
+    /// Pending multisig action and the signers that approved it
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PendingAction {
+        pub action: RegistryAction,
+        pub proposer: AccountId,
+        pub approvals: Vec<AccountId>,
+        pub executed: bool,
+    }
+
+    /// Delay before a change of an already set payout address takes effect (48 hours)
+    pub const PAYOUT_ADDRESS_DELAY_MS: u64 = 48 * 60 * 60 * 1000;
+
+    /// Queued change of a device's payout address
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PendingPayoutAddress {
+        /// New payout address (None = back to the device key)
+        pub address: Option<AccountId>,
+        pub effective_at: u64,
+    }
+
+    /// Where a device's rewards are paid, kept apart from its (hot) device key
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PayoutAddress {
+        /// Payout address in force (None = the device key)
+        pub current: Option<AccountId>,
+        pub pending: Option<PendingPayoutAddress>,
+    }
+
+    impl PayoutAddress {
+        /// Payout address in force at `now`, counting a pending change whose delay has passed
+        pub fn effective(&self, now: u64) -> Option<AccountId> {
+            match &self.pending {
+                Some(pending) if now >= pending.effective_at => pending.address,
+                _ => self.current,
+            }
+        }
+    }
+
+    /// Maximum unavailable windows in a device's availability calendar
+    pub const MAX_UNAVAILABLE_WINDOWS: usize = 16;
+
+    /// Period in which a device will not respond to grid events (e.g. maintenance)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct UnavailableWindow {
+        pub start: u64,
+        pub end: u64,
+    }
+
+    /// Per-device performance history entries retained (oldest are overwritten)
+    pub const MAX_PERFORMANCE_HISTORY: u64 = 100;
+
+    /// Maximum key/value attributes per device
+    pub const MAX_DEVICE_ATTRIBUTES: usize = 16;
+    /// Maximum attribute key length in bytes
+    pub const MAX_ATTRIBUTE_KEY_LEN: usize = 32;
+    /// Maximum attribute value / metadata URI length in bytes
+    pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 128;
+
+    /// Maximum devices returned by one `search_devices` call
+    pub const MAX_SEARCH_RESULTS: u32 = 50;
+
+    /// Criteria for `search_devices`; unset fields match every device
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct DeviceSearchFilter {
+        pub device_type: Option<DeviceType>,
+        /// Zone prefix, so a coarse geohash cell also matches the cells inside it
+        pub zone: Option<String>,
+        pub min_reputation: u32,
+        /// Skip inactive and blacklisted devices
+        pub active_only: bool,
+    }
+
+    /// One performance update of a device
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PerformanceRecord {
+        pub timestamp: u64,
+        /// Grid event the update refers to, if reported by GridService
+        pub event_id: Option<u64>,
+        pub energy_wh: u64,
+        pub success: bool,
+        pub reputation_after: u32,
+    }
+
     #[ink(storage)]
     pub struct ResourceRegistry {
         /// Simple reentrancy flag
         entered: bool,
         /// Pause flag
         paused: bool,
-        /// Mapping from AccountId to Device info (using [u8; 32] as key)
-        devices: Mapping<[u8; 32], Device>,
+        /// Mapping from AccountId to Device info
+        devices: Mapping<AccountId, Device>,
         /// Minimum stake required for device registration
         min_stake: Balance,
         /// Owner of the contract (using ink! AccountId for env() compatibility)
@@ -27,63 +265,300 @@ pub mod resource_registry {
     reputation_threshold: u32,
     /// Governance contract (optional) that can manage roles/params
     governance_address: Option<AccountId>,
+    /// Per device type registration policy (governance managed)
+    device_type_policies: Mapping<DeviceType, DeviceTypePolicy>,
+    /// Manufacturer sr25519 public keys used to check firmware attestations
+    manufacturer_keys: Mapping<String, [u8; 32]>,
+    /// Minimum firmware version per (manufacturer, model)
+    min_firmware_versions: Mapping<(String, String), String>,
+    /// Latest firmware attestation per device
+    firmware_attestations: Mapping<AccountId, FirmwareAttestation>,
+    /// Firmware attestation nonces per device
+    nonces: NonceRegistry,
+    /// Guardian allowed to blacklist accounts alongside governance
+    guardian: Option<AccountId>,
+    /// Pending changes of the token and governance addresses
+    address_timelock: AddressTimelock,
+    /// Blacklisted accounts (entries expire on their own)
+    blacklist: Mapping<AccountId, BlacklistEntry>,
+    /// Per device heartbeat counters for uptime/SLA tracking
+    uptime: Mapping<AccountId, UptimeCounters>,
+    /// PGT token paying referral bonuses out of this contract's balance
+    token_address: Option<AccountId>,
+    /// Bonus paid to each of referrer and referred device (0 = program disabled)
+    referral_bonus: Balance,
+    /// Verified participations the referred device must complete
+    referral_threshold: u32,
+    /// Referral per referred device
+    referrals: Mapping<AccountId, Referral>,
+    /// Number of referrals made per referrer
+    referral_counts: Mapping<AccountId, u32>,
+    /// Graduated penalties for under-delivery
+    penalty_policy: PenaltyPolicy,
+    /// Outstanding failure strikes per device
+    penalty_strikes: Mapping<AccountId, u32>,
+    /// Multisig signers for privileged operations
+    signers: Vec<AccountId>,
+    /// Approvals required (0 = multisig disabled, owner acts alone)
+    signer_threshold: u32,
+    /// Multisig actions by id
+    pending_actions: Mapping<u64, PendingAction>,
+    next_action_id: u64,
+    /// Ring buffer of performance records ((device, slot) -> record)
+    #[allow(clippy::type_complexity)]
+    performance_history: Mapping<(AccountId, u64), PerformanceRecord>,
+    /// Total performance records ever written per device
+    performance_history_len: Mapping<AccountId, u64>,
+    /// PGT stake per device from re-staked rewards (held by this contract)
+    reward_stakes: Mapping<AccountId, Balance>,
+    /// Off-chain metadata URI per device (e.g. IPFS)
+    metadata_uris: Mapping<AccountId, String>,
+    /// Attribute keys set per device
+    attribute_keys: Mapping<AccountId, Vec<String>>,
+    /// Attribute values ((device, key) -> value)
+    #[allow(clippy::type_complexity)]
+    attributes: Mapping<(AccountId, String), String>,
+    /// Number of currently active devices
+    active_device_count: u64,
+    /// Registration timestamps in registration order (index -> timestamp)
+    registration_times: Mapping<u64, u64>,
+    /// Reward payout address per device
+    payout_addresses: Mapping<AccountId, PayoutAddress>,
+    /// Availability calendar per device (sorted, non-overlapping)
+    unavailable_windows: Mapping<AccountId, Vec<UnavailableWindow>>,
+    /// Stake slashed per (device, statement period)
+    slashed_stake: Mapping<(AccountId, u64), Balance>,
+    /// Search index: devices per (device type, reputation decile)
+    #[allow(clippy::type_complexity)]
+    search_buckets: Mapping<(DeviceType, u8), Vec<AccountId>>,
+    /// Search bucket each device is currently filed under
+    search_keys: Mapping<AccountId, (DeviceType, u8)>,
+    /// Device types that have search buckets, in first-seen order
+    indexed_device_types: Vec<DeviceType>,
+    /// Block of the last health snapshot
+    last_health_snapshot: Option<u32>,
+    }
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
     }
 
     /// Events emitted by the contract
+    /// Periodic summary for off-chain monitoring
+    #[ink(event)]
+    pub struct HealthSnapshot {
+        pub block: u32,
+        pub device_count: u64,
+        pub active_device_count: u64,
+        /// Native balance held by the registry (stakes and pending withdrawals)
+        pub stake_held: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PenaltyApplied {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub strikes: u32,
+        pub level: PenaltyLevel,
+        pub slashed: Balance,
+    }
+
     #[ink(event)]
     pub struct DeviceRegistered {
         #[ink(topic)]
-        account: AccountId,
-        stake: Balance,
-        reputation: u32,
+        pub account: AccountId,
+        pub stake: Balance,
+        pub reputation: u32,
     }
 
     #[ink(event)]
     pub struct StakeIncreased {
         #[ink(topic)]
-        account: AccountId,
-        additional_stake: Balance,
-        total_stake: Balance,
+        pub account: AccountId,
+        pub additional_stake: Balance,
+        pub total_stake: Balance,
     }
 
     #[ink(event)]
     pub struct StakeWithdrawn {
         #[ink(topic)]
-        account: AccountId,
-        amount: Balance,
-        remaining_stake: Balance,
+        pub account: AccountId,
+        pub amount: Balance,
+        pub remaining_stake: Balance,
     }
 
     #[ink(event)]
     pub struct StakeSlashed {
         #[ink(topic)]
-        account: AccountId,
-        amount: Balance,
-        remaining_stake: Balance,
-        reason: String,
+        pub account: AccountId,
+        pub amount: Balance,
+        pub remaining_stake: Balance,
+        pub reason: String,
     }
 
     #[ink(event)]
     pub struct DeviceDeactivated {
         #[ink(topic)]
-        account: AccountId,
-        reason: String,
+        pub account: AccountId,
+        pub reason: String,
     }
 
     #[ink(event)]
     pub struct ReputationUpdated {
         #[ink(topic)]
-        account: AccountId,
-        old_reputation: u32,
-        new_reputation: u32,
+        pub account: AccountId,
+        pub old_reputation: u32,
+        pub new_reputation: u32,
+        /// Flow that caused the update, e.g. a grid event's settlement
+        #[ink(topic)]
+        pub correlation_id: Option<CorrelationId>,
+    }
+
+    #[ink(event)]
+    pub struct DeviceTypePolicyUpdated {
+        pub device_type: DeviceType,
+        pub policy: Option<DeviceTypePolicy>,
+    }
+
+    #[ink(event)]
+    pub struct FirmwareAttested {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub version: String,
+        pub firmware_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct DeviceBlacklisted {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub reason_code: u16,
+        pub until: u64,
+    }
+
+    #[ink(event)]
+    pub struct DeviceUnblacklisted {
+        #[ink(topic)]
+        pub account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DeviceReferred {
+        #[ink(topic)]
+        pub account: AccountId,
+        #[ink(topic)]
+        pub referrer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ReferralRewarded {
+        #[ink(topic)]
+        pub account: AccountId,
+        #[ink(topic)]
+        pub referrer: AccountId,
+        pub bonus: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ActionProposed {
+        #[ink(topic)]
+        pub action_id: u64,
+        pub proposer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ActionApproved {
+        #[ink(topic)]
+        pub action_id: u64,
+        pub signer: AccountId,
+        pub approvals: u32,
+    }
+
+    #[ink(event)]
+    pub struct ActionExecuted {
+        #[ink(topic)]
+        pub action_id: u64,
+        pub success: bool,
+    }
+
+    #[ink(event)]
+    pub struct RewardStakeChanged {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+        pub total_reward_stake: Balance,
+        pub increased: bool,
+    }
+
+    #[ink(event)]
+    pub struct DeviceAttributeUpdated {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub key: String,
+        pub value: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeProposed {
+        pub contract: ExternalContract,
+        pub new_address: AccountId,
+        pub executable_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeExecuted {
+        pub contract: ExternalContract,
+        pub old_address: Option<AccountId>,
+        pub new_address: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AddressChangeCancelled {
+        pub contract: ExternalContract,
+        #[ink(topic)]
+        pub by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GuardianUpdated {
+        pub old_guardian: Option<AccountId>,
+        pub new_guardian: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct PauseChanged {
+        #[ink(topic)]
+        pub by: AccountId,
+        pub paused: bool,
+    }
+
+    #[ink(event)]
+    pub struct PayoutAddressChanged {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub payout_address: Option<AccountId>,
+        /// When the address takes effect (now for a first-time set)
+        pub effective_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct AvailabilityCalendarUpdated {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub windows: u32,
     }
 
     #[ink(event)]
     pub struct DeviceUpdated {
         #[ink(topic)]
-        account: AccountId,
-        version: u32,
-        timestamp: u64,
+        pub account: AccountId,
+        pub version: u32,
+        pub timestamp: u64,
     }
 
     impl ResourceRegistry {
@@ -94,7 +569,7 @@ pub mod resource_registry {
             // Accept in tokens, store in native units
             let min_stake = tokens_to_native(min_stake_tokens);
             
-            Self {
+            let instance = Self {
                 devices: Mapping::default(),
                 min_stake,
                 owner: Some(Self::env().caller()),
@@ -104,7 +579,44 @@ pub mod resource_registry {
                 governance_address: Some(Self::env().caller()),
                 entered: false,
                 paused: false,
-            }
+                device_type_policies: Mapping::default(),
+                manufacturer_keys: Mapping::default(),
+                min_firmware_versions: Mapping::default(),
+                firmware_attestations: Mapping::default(),
+                nonces: NonceRegistry::default(),
+                guardian: None,
+                address_timelock: AddressTimelock::default(),
+                blacklist: Mapping::default(),
+                uptime: Mapping::default(),
+                token_address: None,
+                referral_bonus: 0,
+                referral_threshold: 3,
+                penalty_policy: PenaltyPolicy::default(),
+                penalty_strikes: Mapping::default(),
+                referrals: Mapping::default(),
+                referral_counts: Mapping::default(),
+                signers: Vec::new(),
+                signer_threshold: 0,
+                pending_actions: Mapping::default(),
+                next_action_id: 1,
+                performance_history: Mapping::default(),
+                performance_history_len: Mapping::default(),
+                reward_stakes: Mapping::default(),
+                metadata_uris: Mapping::default(),
+                attribute_keys: Mapping::default(),
+                attributes: Mapping::default(),
+                active_device_count: 0,
+                registration_times: Mapping::default(),
+                payout_addresses: Mapping::default(),
+                unavailable_windows: Mapping::default(),
+                slashed_stake: Mapping::default(),
+                search_buckets: Mapping::default(),
+                search_keys: Mapping::default(),
+                indexed_device_types: Vec::new(),
+                last_health_snapshot: None,
+            };
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
+            instance
         }
 
         /// Register a new device with stake
@@ -114,17 +626,28 @@ pub mod resource_registry {
             self.entered = true;
             if self.paused { self.entered = false; return Err("Paused".into()); }
             let caller = self.env().caller();
-            let caller_bytes = ink_account_to_bytes(caller);
             let stake: Balance = self.env().transferred_value();
             
             if stake < self.min_stake {
+                self.entered = false;
                 return Err("Insufficient stake amount".into());
             }
             
-            if self.devices.contains(caller_bytes) {
+            if self.devices.contains(caller) {
+                self.entered = false;
                 return Err("Device already registered".into());
             }
 
+            if self.is_blacklisted(caller) {
+                self.entered = false;
+                return Err("Account is blacklisted".into());
+            }
+
+            if let Err(e) = self.validate_metadata(&metadata, stake) {
+                self.entered = false;
+                return Err(e);
+            }
+
             let now = self.env().block_timestamp();
             let device = Device {
                 metadata,
@@ -139,8 +662,11 @@ pub mod resource_registry {
                 last_updated: now,
             };
 
-            self.devices.insert(caller_bytes, &device);
+            self.devices.insert(caller, &device);
+            self.index_device(caller, &device);
+            self.registration_times.insert(self.device_count, &now);
             self.device_count = self.device_count.saturating_add(1);
+            self.active_device_count = self.active_device_count.saturating_add(1);
 
             self.env().emit_event(DeviceRegistered {
                 account: caller,
@@ -158,14 +684,15 @@ pub mod resource_registry {
             self.entered = true;
             if self.paused { self.entered = false; return Err("Paused".into()); }
             let caller = self.env().caller();
-            let caller_bytes = ink_account_to_bytes(caller);
             let additional_stake: Balance = self.env().transferred_value();
             
-            let mut device = self.devices.get(caller_bytes)
-                .ok_or("Device not registered")?;
+            let Some(mut device) = self.devices.get(caller) else {
+                self.entered = false;
+                return Err("Device not registered".into());
+            };
             
             device.stake = device.stake.saturating_add(additional_stake);
-            self.devices.insert(caller_bytes, &device);
+            self.devices.insert(caller, &device);
 
             self.env().emit_event(StakeIncreased {
                 account: caller,
@@ -176,428 +703,2491 @@ pub mod resource_registry {
             Ok(())
         }
 
-        /// Get device information
+        /// Update metadata of the caller's device, re-checking the device type policy
         #[ink(message)]
-        pub fn get_device(&self, account: AccountId) -> Option<Device> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes)
+        pub fn update_device_metadata(&mut self, metadata: DeviceMetadata) -> Result<(), String> {
+            if self.paused { return Err("Paused".into()); }
+            let caller = self.env().caller();
+            let mut device = self.devices.get(caller).ok_or("Device not registered")?;
+
+            self.validate_metadata(&metadata, device.stake)?;
+            if metadata.firmware_version != device.metadata.firmware_version {
+                return Err("Firmware version changes require attestation".into());
+            }
+            // The firmware minimum is keyed by model, so swapping it would dodge the check
+            if metadata.manufacturer != device.metadata.manufacturer || metadata.model != device.metadata.model {
+                return Err("Manufacturer and model cannot change".into());
+            }
+
+            device.metadata = metadata;
+            device.version = device.version.saturating_add(1);
+            device.last_updated = self.env().block_timestamp();
+            self.devices.insert(caller, &device);
+            self.index_device(caller, &device);
+
+            self.env().emit_event(DeviceUpdated { account: caller, version: device.version, timestamp: device.last_updated });
+            Ok(())
         }
 
-        /// Check if device is registered
-        #[ink(message)]
-        pub fn is_device_registered(&self, account: AccountId) -> bool {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.contains(account_bytes)
+        /// Validate metadata and stake against the policy of its device type
+        fn validate_metadata(&self, metadata: &DeviceMetadata, stake: Balance) -> Result<(), String> {
+            if metadata.zone.len() > MAX_ZONE_LENGTH {
+                return Err("Zone too precise".into());
+            }
+            self.check_device_type_policy(metadata, stake)
         }
 
-        /// Get device reputation
-        #[ink(message)]
-        pub fn get_device_reputation(&self, account: AccountId) -> Option<u32> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes).map(|device| device.reputation)
+        fn check_device_type_policy(&self, metadata: &DeviceMetadata, stake: Balance) -> Result<(), String> {
+            if let Some(policy) = self.device_type_policies.get(&metadata.device_type) {
+                if !policy.enabled {
+                    return Err("Device type not allowed".into());
+                }
+                if stake < policy.min_stake {
+                    return Err("Insufficient stake for device type".into());
+                }
+                if policy.max_capacity_watts > 0 && metadata.capacity_watts > policy.max_capacity_watts {
+                    return Err("Capacity exceeds device type maximum".into());
+                }
+            }
+            Ok(())
         }
 
-        /// Get device count
+        /// Set or clear the policy for a device type (owner/governance only)
         #[ink(message)]
-        pub fn get_device_count(&self) -> u64 {
-            self.device_count
+        pub fn set_device_type_policy(&mut self, device_type: DeviceType, policy: Option<DeviceTypePolicy>) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can update device type policy")?;
+            self.set_device_type_policy_internal(device_type, policy)
         }
 
-        /// Get minimum stake
+        fn set_device_type_policy_internal(&mut self, device_type: DeviceType, policy: Option<DeviceTypePolicy>) -> Result<(), String> {
+            match &policy {
+                Some(p) => { self.device_type_policies.insert(&device_type, p); }
+                None => self.device_type_policies.remove(&device_type),
+            }
+            self.env().emit_event(DeviceTypePolicyUpdated { device_type, policy });
+            Ok(())
+        }
+
+        /// Get the policy for a device type, if any
         #[ink(message)]
-        pub fn get_min_stake(&self) -> Balance {
-            self.min_stake
+        pub fn get_device_type_policy(&self, device_type: DeviceType) -> Option<DeviceTypePolicy> {
+            self.device_type_policies.get(&device_type)
         }
 
-        /// Update device performance (authorized callers only)
+        /// Submit a manufacturer-signed firmware update attestation for the caller's device.
+        /// The signature covers `signed_message_hash(FirmwareAttestation, registry, nonce,
+        /// (account, version, firmware_hash, device_key))` with the device's next attestation
+        /// nonce, so an older attestation cannot be replayed to roll firmware back; a manufacturer
+        /// key that is a contract account validates it through `is_valid_signature`.
+        /// `device_key` registers the device's hardware-bound key for liveness proofs.
         #[ink(message)]
-        pub fn update_device_performance(&mut self, account: AccountId, energy_contributed: u64, success: bool) -> Result<(), String> {
-            if self.ensure_authorized().is_err() {
-                return Err("Unauthorized caller".into());
+        pub fn submit_firmware_attestation(
+            &mut self,
+            version: String,
+            firmware_hash: [u8; 32],
+            device_key: Option<AccountId>,
+            nonce: u64,
+            signature: Vec<u8>,
+        ) -> Result<(), String> {
+            let caller = self.env().caller();
+            let mut device = self.devices.get(caller).ok_or("Device not registered")?;
+            if parse_firmware_version(&version).is_none() {
+                return Err("Invalid firmware version".into());
             }
-            
-            let account_bytes = ink_account_to_bytes(account);
-            let mut device = self.devices.get(account_bytes)
-                .ok_or("Device not registered")?;
-            
-            device.total_energy_contributed = device.total_energy_contributed.saturating_add(energy_contributed);
-            if success {
-                device.successful_events = device.successful_events.saturating_add(1);
-            } else {
-                device.failed_events = device.failed_events.saturating_add(1);
+
+            let key = self.manufacturer_keys.get(&device.metadata.manufacturer)
+                .ok_or("Unknown manufacturer key")?;
+            if nonce != self.nonces.current(caller, NonceDomain::FirmwareAttestation) {
+                return Err("Invalid nonce".into());
             }
-            
-            let old_reputation = device.reputation;
-            device.reputation = self.calculate_performance_score(&device);
-            device.last_activity = self.env().block_timestamp();
-            device.version = device.version.saturating_add(1);
-            device.last_updated = device.last_activity;
-            
-            self.devices.insert(account_bytes, &device);
+            let message = signed_message_hash(
+                NonceDomain::FirmwareAttestation,
+                self.env().account_id(),
+                nonce,
+                &(caller, &version, firmware_hash, device_key),
+            );
+            if !verify_signature(AccountId::from(key), message, &signature) {
+                return Err("Invalid attestation signature".into());
+            }
+            self.nonces.use_nonce(caller, NonceDomain::FirmwareAttestation, nonce);
 
-            self.env().emit_event(ReputationUpdated {
-                account,
-                old_reputation,
-                new_reputation: device.reputation,
+            let now = self.env().block_timestamp();
+            device.metadata.firmware_version = version.clone();
+            device.version = device.version.saturating_add(1);
+            device.last_updated = now;
+            self.devices.insert(caller, &device);
+            self.firmware_attestations.insert(caller, &FirmwareAttestation {
+                version: version.clone(),
+                firmware_hash,
+                attested_at: now,
+                device_key,
             });
-            self.env().emit_event(DeviceUpdated { account, version: device.version, timestamp: device.last_updated });
 
+            self.env().emit_event(FirmwareAttested { account: caller, version, firmware_hash });
+            self.env().emit_event(DeviceUpdated { account: caller, version: device.version, timestamp: now });
             Ok(())
         }
 
-        /// Performance scoring algorithm
-        fn calculate_performance_score(&self, device: &Device) -> u32 {
-            let total_events = device.successful_events.saturating_add(device.failed_events);
-            if total_events == 0 {
-                return 100; // Default score for new devices
-            }
+        /// Next nonce the manufacturer must sign into a device's firmware attestation
+        #[ink(message)]
+        pub fn get_attestation_nonce(&self, account: AccountId) -> u64 {
+            self.nonces.current(account, NonceDomain::FirmwareAttestation)
+        }
 
-            let success_rate = device.successful_events
-                .saturating_mul(100)
-                .checked_div(total_events)
-                .unwrap_or(0);
-            
-            let energy_factor = device.total_energy_contributed
-                .checked_div(1000)
-                .unwrap_or(0)
-                .min(50); // Up to 50 points for energy
-            
-            let base_score = success_rate.saturating_add(energy_factor as u32);
-            base_score.clamp(1, 100) // Keep between 1-100
+        /// Get the latest firmware attestation of a device
+        #[ink(message)]
+        pub fn get_firmware_attestation(&self, account: AccountId) -> Option<FirmwareAttestation> {
+            self.firmware_attestations.get(account)
         }
 
-        /// Update minimum stake (owner only)
+        /// Hardware-bound key registered with a device's latest attestation
         #[ink(message)]
-        pub fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String> {
-            let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address {
-                return Err("Only owner/governance can update minimum stake".into());
+        pub fn get_device_key(&self, account: AccountId) -> Option<AccountId> {
+            self.firmware_attestations.get(account).and_then(|attestation| attestation.device_key)
+        }
+
+        /// Set or clear a manufacturer's attestation key (owner/governance only)
+        #[ink(message)]
+        pub fn set_manufacturer_key(&mut self, manufacturer: String, key: Option<[u8; 32]>) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can manage manufacturer keys")?;
+            self.set_manufacturer_key_internal(manufacturer, key)
+        }
+
+        fn set_manufacturer_key_internal(&mut self, manufacturer: String, key: Option<[u8; 32]>) -> Result<(), String> {
+            match key {
+                Some(k) => { self.manufacturer_keys.insert(&manufacturer, &k); }
+                None => self.manufacturer_keys.remove(&manufacturer),
             }
-            
-            self.min_stake = new_min_stake;
             Ok(())
         }
 
-        /// Update reputation threshold (owner only)
+        /// Set or clear the minimum firmware version for a model (owner/governance only)
         #[ink(message)]
-        pub fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String> {
-            let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address {
-                return Err("Only owner/governance can update reputation threshold".into());
+        pub fn set_min_firmware_version(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can update firmware requirements")?;
+            self.set_min_firmware_version_internal(manufacturer, model, version)
+        }
+
+        fn set_min_firmware_version_internal(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String> {
+            match version {
+                Some(v) => {
+                    if parse_firmware_version(&v).is_none() { return Err("Invalid firmware version".into()); }
+                    self.min_firmware_versions.insert((&manufacturer, &model), &v);
+                }
+                None => self.min_firmware_versions.remove((&manufacturer, &model)),
             }
-            self.reputation_threshold = new_threshold;
             Ok(())
         }
 
-        /// Get reputation threshold
+        /// Get the minimum firmware version for a model
         #[ink(message)]
-        pub fn get_reputation_threshold(&self) -> u32 {
-            self.reputation_threshold
+        pub fn get_min_firmware_version(&self, manufacturer: String, model: String) -> Option<String> {
+            self.min_firmware_versions.get((&manufacturer, &model))
         }
 
-        /// Add authorized caller (owner only)
+        /// Whether the device meets its model's minimum firmware version
+        fn meets_firmware_requirement(&self, device: &Device) -> bool {
+            let meta = &device.metadata;
+            match self.min_firmware_versions.get((&meta.manufacturer, &meta.model)) {
+                None => true,
+                Some(min) => match (parse_firmware_version(&meta.firmware_version), parse_firmware_version(&min)) {
+                    (Some(current), Some(required)) => current >= required,
+                    _ => false,
+                },
+            }
+        }
+
+        /// Check whether a device may take part in grid events
         #[ink(message)]
-        pub fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
-            let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address {
-                return Err("Only owner/governance can add authorized callers".into());
+        pub fn is_eligible(&self, account: AccountId) -> bool {
+            if self.is_blacklisted(account) {
+                return false;
+            }
+            match self.devices.get(account) {
+                Some(device) => device.active
+                    && device.reputation >= self.reputation_threshold
+                    && self.meets_firmware_requirement(&device),
+                None => false,
             }
-            self.authorized_callers.insert(caller, &true);
-            Ok(())
         }
 
-        /// Remove authorized caller (owner only)
+        /// Blacklist an account until `until_timestamp` (governance/guardian only)
         #[ink(message)]
-        pub fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        pub fn blacklist_device(&mut self, account: AccountId, reason_code: u16, until_timestamp: u64) -> Result<(), String> {
             let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address {
-                return Err("Only owner/governance can remove authorized callers".into());
+            if Some(sender) != self.governance_address && Some(sender) != self.guardian {
+                return Err("Only governance/guardian can blacklist".into());
             }
-            self.authorized_callers.remove(caller);
+            let now = self.env().block_timestamp();
+            if until_timestamp <= now {
+                return Err("Blacklist expiry must be in the future".into());
+            }
+            self.blacklist.insert(account, &BlacklistEntry {
+                reason_code,
+                blacklisted_at: now,
+                until: until_timestamp,
+            });
+            self.env().emit_event(DeviceBlacklisted { account, reason_code, until: until_timestamp });
             Ok(())
         }
 
-        /// Set governance address (owner only)
+        /// Lift a blacklisting before it expires (governance/guardian only)
         #[ink(message)]
-        pub fn set_governance_address(&mut self, addr: AccountId) -> Result<(), String> {
-            if Some(self.env().caller()) != self.owner {
-                return Err("Only owner can set governance address".into());
+        pub fn unblacklist_device(&mut self, account: AccountId) -> Result<(), String> {
+            let sender = self.env().caller();
+            if Some(sender) != self.governance_address && Some(sender) != self.guardian {
+                return Err("Only governance/guardian can blacklist".into());
             }
-            self.governance_address = Some(addr);
+            if !self.blacklist.contains(account) {
+                return Err("Account not blacklisted".into());
+            }
+            self.blacklist.remove(account);
+            self.env().emit_event(DeviceUnblacklisted { account });
             Ok(())
         }
 
-        /// Deactivate a device (owner only)
+        /// Get the blacklist entry of an account, if still in force
         #[ink(message)]
-        pub fn deactivate_device(&mut self, account: AccountId, reason: String) -> Result<(), String> {
-            if Some(self.env().caller()) != self.owner {
-                return Err("Only owner can deactivate devices".into());
-            }
+        pub fn get_blacklist_entry(&self, account: AccountId) -> Option<BlacklistEntry> {
+            self.blacklist.get(account)
+                .filter(|entry| self.env().block_timestamp() < entry.until)
+        }
+
+        /// Check whether an account is currently blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: AccountId) -> bool {
+            self.get_blacklist_entry(account).is_some()
+        }
+
+        /// Rotate the protocol guardian, which may pause and blacklist (governance only)
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String> {
+            if Some(self.env().caller()) != self.governance_address {
+                return Err("Only governance can set guardian".into());
+            }
+            let old_guardian = self.guardian;
+            self.guardian = guardian;
+            self.env().emit_event(GuardianUpdated { old_guardian, new_guardian: guardian });
+            Ok(())
+        }
+
+        /// Get the guardian account
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<AccountId> {
+            self.guardian
+        }
+
+        /// Get the governance contract address
+        #[ink(message)]
+        pub fn get_governance_address(&self) -> Option<AccountId> {
+            self.governance_address
+        }
+
+        /// Logic version of this contract, bumped on every upgrade
+        #[ink(message)]
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Report whether governance and the token are wired (and the referral pool can pay out)
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            report.check("not_paused", !self.paused);
+            report.check("governance_set", self.governance_address.is_some());
+            report.check("token_set", self.token_address.is_some());
+            if let Some(token) = self.token_address.and_then(Token::connect) {
+                let pool = token.balance_of(self.env().account_id());
+                report.check("referral_pool_funded", pool >= self.referral_bonus);
+            }
+            report
+        }
+
+        /// Check a revealed (salt, location) pair against a device's location commitment (audits)
+        #[ink(message)]
+        pub fn reveal_location(&self, account: AccountId, salt: [u8; 32], location: String) -> bool {
+            match self.devices.get(account) {
+                Some(device) => device.metadata.location_commitment == location_commitment(salt, &location),
+                None => false,
+            }
+        }
+
+        /// Get device zone
+        #[ink(message)]
+        pub fn get_device_zone(&self, account: AccountId) -> Option<String> {
+            self.devices.get(account).map(|d| d.metadata.zone)
+        }
+
+        /// Report that the caller's device is online
+        #[ink(message)]
+        pub fn heartbeat(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            self.record_heartbeat_internal(caller)
+        }
+
+        /// Report a heartbeat on behalf of a device (authorized callers only, e.g. gateways)
+        #[ink(message)]
+        pub fn record_heartbeat(&mut self, account: AccountId) -> Result<(), String> {
+            if self.ensure_authorized().is_err() {
+                return Err("Unauthorized caller".into());
+            }
+            self.record_heartbeat_internal(account)
+        }
+
+        fn record_heartbeat_internal(&mut self, account: AccountId) -> Result<(), String> {
+            if self.paused { return Err("Paused".into()); }
+            if !self.devices.contains(account) {
+                return Err("Device not registered".into());
+            }
+            let now = self.env().block_timestamp();
+            let mut counters = match self.uptime.get(account) {
+                Some(c) => c,
+                None => UptimeCounters {
+                    tracking_since: now,
+                    window_start: now,
+                    current_heartbeats: 0,
+                    previous_heartbeats: 0,
+                    last_heartbeat: 0,
+                },
+            };
+            counters.roll(now);
+            // At most one counted heartbeat per interval
+            let first = counters.current_heartbeats == 0 && counters.previous_heartbeats == 0 && counters.last_heartbeat == 0;
+            if first || now / HEARTBEAT_INTERVAL_MS > counters.last_heartbeat / HEARTBEAT_INTERVAL_MS {
+                counters.current_heartbeats = counters.current_heartbeats.saturating_add(1);
+            }
+            counters.last_heartbeat = now;
+            self.uptime.insert(account, &counters);
+            Ok(())
+        }
+
+        /// Get a device's availability over the trailing 30-day window
+        #[ink(message)]
+        pub fn get_device_sla(&self, account: AccountId) -> Option<DeviceSla> {
+            let mut counters = self.uptime.get(account)?;
+            let now = self.env().block_timestamp();
+            counters.roll(now);
+
+            // Weight the previous window by how much of it still overlaps the trailing window
+            let into_window = now.saturating_sub(counters.window_start);
+            let carried = u64::from(counters.previous_heartbeats)
+                .saturating_mul(SLA_WINDOW_MS.saturating_sub(into_window)) / SLA_WINDOW_MS;
+            let heartbeats = u64::from(counters.current_heartbeats).saturating_add(carried);
+
+            let window_slots = SLA_WINDOW_MS / HEARTBEAT_INTERVAL_MS;
+            let tracked_slots = now.saturating_sub(counters.tracking_since) / HEARTBEAT_INTERVAL_MS + 1;
+            let expected = tracked_slots.min(window_slots);
+            let uptime_percent = (heartbeats.saturating_mul(100) / expected).min(100) as u8;
+
+            Some(DeviceSla {
+                uptime_percent,
+                heartbeats: heartbeats.min(expected) as u32,
+                expected_heartbeats: expected as u32,
+                last_heartbeat: counters.last_heartbeat,
+            })
+        }
+
+        /// Register a new device with stake, crediting an existing device as referrer
+        #[ink(message, payable)]
+        pub fn register_device_with_referrer(&mut self, metadata: DeviceMetadata, referrer: AccountId) -> Result<(), String> {
+            let caller = self.env().caller();
+            if referrer == caller {
+                return Err("Cannot refer yourself".into());
+            }
+            match self.devices.get(referrer) {
+                Some(device) if device.active && !self.is_blacklisted(referrer) => {}
+                _ => return Err("Referrer not an active device".into()),
+            }
+
+            self.register_device(metadata)?;
+
+            self.referrals.insert(caller, &Referral { referrer, qualified: false, rewarded: false, referrer_paid: false });
+            let count = self.referral_counts.get(referrer).unwrap_or(0);
+            self.referral_counts.insert(referrer, &count.saturating_add(1));
+            self.env().emit_event(DeviceReferred { account: caller, referrer });
+            Ok(())
+        }
+
+        /// Configure the referral program (owner/governance only). The bonus pool is this
+        /// contract's PGT balance, funded by governance.
+        #[ink(message)]
+        pub fn set_referral_program(&mut self, token: AccountId, bonus: Balance, threshold: u32) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can configure referrals")?;
+            self.set_referral_program_internal(token, bonus, threshold)
+        }
+
+        fn set_referral_program_internal(&mut self, token: AccountId, bonus: Balance, threshold: u32) -> Result<(), String> {
+            if threshold == 0 {
+                return Err("Threshold must be positive".into());
+            }
+            if self.token_address.is_some_and(|current| current != token) {
+                return Err("Token address is timelocked".into());
+            }
+            self.token_address = Some(token);
+            self.referral_bonus = bonus;
+            self.referral_threshold = threshold;
+            Ok(())
+        }
+
+        /// Set the graduated penalty policy (owner/governance only)
+        #[ink(message)]
+        pub fn set_penalty_policy(&mut self, policy: PenaltyPolicy) -> Result<(), String> {
+            self.ensure_owner_or_governance("Unauthorized")?;
+            self.set_penalty_policy_internal(policy)
+        }
+
+        fn set_penalty_policy_internal(&mut self, policy: PenaltyPolicy) -> Result<(), String> {
+            if policy.warning_strikes > policy.reputation_strikes
+                || policy.reputation_strikes > policy.partial_slash_strikes
+                || policy.partial_slash_bps > 10_000
+            {
+                return Err("Invalid penalty policy".into());
+            }
+            self.penalty_policy = policy;
+            Ok(())
+        }
+
+        /// Get the graduated penalty policy
+        #[ink(message)]
+        pub fn get_penalty_policy(&self) -> PenaltyPolicy {
+            self.penalty_policy.clone()
+        }
+
+        /// Outstanding failure strikes of a device
+        #[ink(message)]
+        pub fn get_penalty_strikes(&self, account: AccountId) -> u32 {
+            self.penalty_strikes.get(account).unwrap_or(0)
+        }
+
+        /// Get referral program settings: (token, bonus, threshold)
+        #[ink(message)]
+        pub fn get_referral_program(&self) -> (Option<AccountId>, Balance, u32) {
+            (self.token_address, self.referral_bonus, self.referral_threshold)
+        }
+
+        /// Get the referral recorded for a device
+        #[ink(message)]
+        pub fn get_referral(&self, account: AccountId) -> Option<Referral> {
+            self.referrals.get(account)
+        }
+
+        /// Number of devices referred by an account
+        #[ink(message)]
+        pub fn get_referral_count(&self, referrer: AccountId) -> u32 {
+            self.referral_counts.get(referrer).unwrap_or(0)
+        }
+
+        /// Retry a qualified referral bonus that could not be paid (e.g. pool was empty)
+        #[ink(message)]
+        pub fn claim_referral_bonus(&mut self, account: AccountId) -> Result<(), String> {
+            let referral = self.referrals.get(account).ok_or("No referral")?;
+            if !referral.qualified {
+                return Err("Referral not qualified yet".into());
+            }
+            if referral.rewarded {
+                return Err("Referral already rewarded".into());
+            }
+            if self.pay_referral_bonus(account) {
+                Ok(())
+            } else {
+                Err("Referral bonus payout failed".into())
+            }
+        }
+
+        /// Pay the bonus to both parties from the pool; returns whether it was paid
+        fn pay_referral_bonus(&mut self, account: AccountId) -> bool {
+            let mut referral = match self.referrals.get(account) {
+                Some(r) => r,
+                None => return false,
+            };
+            if referral.rewarded || self.referral_bonus == 0 || self.is_blacklisted(referral.referrer) {
+                return false;
+            }
+
+            // Effects before the token interaction; rolled back if the pool cannot pay
+            referral.rewarded = true;
+            self.referrals.insert(account, &referral);
+
+            let mut paid = false;
+            if let Some(mut token) = self.token_address.and_then(Token::connect) {
+                let owed = if referral.referrer_paid { 1 } else { 2 };
+                let pool = token.balance_of(self.env().account_id());
+                if pool >= self.referral_bonus.saturating_mul(owed) {
+                    // Each half is recorded as it lands; a failed second transfer keeps the first
+                    if !referral.referrer_paid && token.transfer(referral.referrer, self.referral_bonus, Vec::new()).is_ok() {
+                        referral.referrer_paid = true;
+                    }
+                    paid = referral.referrer_paid && token.transfer(account, self.referral_bonus, Vec::new()).is_ok();
+                }
+            }
+            if !paid {
+                referral.rewarded = false;
+                self.referrals.insert(account, &referral);
+                return false;
+            }
+
+            self.env().emit_event(ReferralRewarded { account, referrer: referral.referrer, bonus: self.referral_bonus });
+            true
+        }
+
+        /// Set or clear the caller's off-chain metadata URI
+        #[ink(message)]
+        pub fn set_metadata_uri(&mut self, uri: Option<String>) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.devices.contains(caller) {
+                return Err("Device not registered".into());
+            }
+            match uri {
+                Some(u) => {
+                    if u.is_empty() || u.len() > MAX_ATTRIBUTE_VALUE_LEN {
+                        return Err("Invalid metadata URI".into());
+                    }
+                    self.metadata_uris.insert(caller, &u);
+                }
+                None => self.metadata_uris.remove(caller),
+            }
+            Ok(())
+        }
+
+        /// Get a device's off-chain metadata URI
+        #[ink(message)]
+        pub fn get_metadata_uri(&self, account: AccountId) -> Option<String> {
+            self.metadata_uris.get(account)
+        }
+
+        /// Set a key/value attribute on the caller's device
+        #[ink(message)]
+        pub fn set_attribute(&mut self, key: String, value: String) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.devices.contains(caller) {
+                return Err("Device not registered".into());
+            }
+            if key.is_empty() || key.len() > MAX_ATTRIBUTE_KEY_LEN {
+                return Err("Invalid attribute key".into());
+            }
+            if value.len() > MAX_ATTRIBUTE_VALUE_LEN {
+                return Err("Attribute value too long".into());
+            }
+            let mut keys = self.attribute_keys.get(caller).unwrap_or_default();
+            if !keys.contains(&key) {
+                if keys.len() >= MAX_DEVICE_ATTRIBUTES {
+                    return Err("Too many attributes".into());
+                }
+                keys.push(key.clone());
+                self.attribute_keys.insert(caller, &keys);
+            }
+            self.attributes.insert((caller, &key), &value);
+            self.env().emit_event(DeviceAttributeUpdated { account: caller, key, value: Some(value) });
+            Ok(())
+        }
+
+        /// Remove an attribute from the caller's device
+        #[ink(message)]
+        pub fn remove_attribute(&mut self, key: String) -> Result<(), String> {
+            let caller = self.env().caller();
+            let mut keys = self.attribute_keys.get(caller).unwrap_or_default();
+            let index = keys.iter().position(|k| *k == key).ok_or("Attribute not set")?;
+            keys.swap_remove(index);
+            self.attribute_keys.insert(caller, &keys);
+            self.attributes.remove((caller, &key));
+            self.env().emit_event(DeviceAttributeUpdated { account: caller, key, value: None });
+            Ok(())
+        }
+
+        /// Get a device attribute
+        #[ink(message)]
+        pub fn get_attribute(&self, account: AccountId, key: String) -> Option<String> {
+            self.attributes.get((account, &key))
+        }
+
+        /// Get the attribute keys set on a device
+        #[ink(message)]
+        pub fn get_attribute_keys(&self, account: AccountId) -> Vec<String> {
+            self.attribute_keys.get(account).unwrap_or_default()
+        }
+
+        /// Direct the caller's rewards to `payout` (None = the device key); returns when it takes effect.
+        /// A first payout address applies at once, later changes wait `PAYOUT_ADDRESS_DELAY_MS`
+        /// so a stolen device key cannot redirect rewards unnoticed
+        #[ink(message)]
+        pub fn set_payout_address(&mut self, payout: Option<AccountId>) -> Result<u64, String> {
+            let caller = self.env().caller();
+            if !self.devices.contains(caller) {
+                return Err("Device not registered".into());
+            }
+            let now = self.env().block_timestamp();
+            let mut config = self.payout_addresses.get(caller).unwrap_or_default();
+            config.current = config.effective(now);
+            config.pending = None;
+            let effective_at = if config.current.is_none() && payout.is_some() {
+                config.current = payout;
+                now
+            } else {
+                let effective_at = now.saturating_add(PAYOUT_ADDRESS_DELAY_MS);
+                config.pending = Some(PendingPayoutAddress { address: payout, effective_at });
+                effective_at
+            };
+            self.payout_addresses.insert(caller, &config);
+            self.env().emit_event(PayoutAddressChanged { account: caller, payout_address: payout, effective_at });
+            Ok(effective_at)
+        }
+
+        /// Drop the caller's queued payout address change before it takes effect
+        #[ink(message)]
+        pub fn cancel_payout_address_change(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            let mut config = self.payout_addresses.get(caller).unwrap_or_default();
+            match &config.pending {
+                Some(pending) if now < pending.effective_at => config.pending = None,
+                _ => return Err("No pending payout address change".into()),
+            }
+            self.payout_addresses.insert(caller, &config);
+            Ok(())
+        }
+
+        /// Address a device's rewards are paid to (the device key unless a payout address is in force)
+        #[ink(message)]
+        pub fn get_payout_address(&self, account: AccountId) -> AccountId {
+            self.payout_addresses
+                .get(account)
+                .and_then(|config| config.effective(self.env().block_timestamp()))
+                .unwrap_or(account)
+        }
+
+        /// Replace the caller's availability calendar; windows that already ended are dropped
+        #[ink(message)]
+        pub fn set_unavailable_windows(&mut self, mut windows: Vec<UnavailableWindow>) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.devices.contains(caller) {
+                return Err("Device not registered".into());
+            }
+            if windows.len() > MAX_UNAVAILABLE_WINDOWS {
+                return Err("Too many unavailable windows".into());
+            }
+            if windows.iter().any(|w| w.start >= w.end) {
+                return Err("Invalid window".into());
+            }
+            let now = self.env().block_timestamp();
+            windows.retain(|w| w.end > now);
+            windows.sort_by_key(|w| w.start);
+            if windows.windows(2).any(|pair| pair[1].start < pair[0].end) {
+                return Err("Overlapping windows".into());
+            }
+            self.unavailable_windows.insert(caller, &windows);
+            self.env().emit_event(AvailabilityCalendarUpdated {
+                account: caller,
+                windows: u32::try_from(windows.len()).unwrap_or(u32::MAX),
+            });
+            Ok(())
+        }
+
+        /// A device's availability calendar
+        #[ink(message)]
+        pub fn get_unavailable_windows(&self, account: AccountId) -> Vec<UnavailableWindow> {
+            self.unavailable_windows.get(account).unwrap_or_default()
+        }
+
+        /// Stake slashed from a device during a statement period (`timestamp / STATEMENT_PERIOD_MS`)
+        #[ink(message)]
+        pub fn get_slashed_stake(&self, account: AccountId, period: u64) -> Balance {
+            self.slashed_stake.get((account, period)).unwrap_or(0)
+        }
+
+        /// Milliseconds of `[from, to)` a device has marked as unavailable
+        #[ink(message)]
+        pub fn get_unavailable_time(&self, account: AccountId, from: u64, to: u64) -> u64 {
+            self.get_unavailable_windows(account)
+                .iter()
+                .map(|w| w.end.min(to).saturating_sub(w.start.max(from)))
+                .fold(0u64, u64::saturating_add)
+        }
+
+        /// Current and queued payout address of a device
+        #[ink(message)]
+        pub fn get_payout_address_config(&self, account: AccountId) -> Option<PayoutAddress> {
+            self.payout_addresses.get(account)
+        }
+
+        /// Get device information
+        #[ink(message)]
+        pub fn get_device(&self, account: AccountId) -> Option<Device> {
+            self.devices.get(account)
+        }
+
+        /// Check if device is registered
+        #[ink(message)]
+        pub fn is_device_registered(&self, account: AccountId) -> bool {
+            self.devices.contains(account)
+        }
+
+        /// Get device reputation
+        #[ink(message)]
+        pub fn get_device_reputation(&self, account: AccountId) -> Option<u32> {
+            self.devices.get(account).map(|device| device.reputation)
+        }
+
+        /// Flip a device's active flag, keeping the active device count in sync
+        fn set_device_active(&mut self, device: &mut Device, active: bool) {
+            if device.active == active {
+                return;
+            }
+            device.active = active;
+            self.active_device_count = if active {
+                self.active_device_count.saturating_add(1)
+            } else {
+                self.active_device_count.saturating_sub(1)
+            };
+        }
+
+        /// File a device under the search bucket of its current type and reputation decile
+        fn index_device(&mut self, account: AccountId, device: &Device) {
+            let decile = u8::try_from(device.reputation / 10).unwrap_or(u8::MAX).min(10);
+            let key = (device.metadata.device_type.clone(), decile);
+            let previous = self.search_keys.get(account);
+            if previous.as_ref() == Some(&key) {
+                return;
+            }
+            if let Some(previous) = previous {
+                let mut bucket = self.search_buckets.get(&previous).unwrap_or_default();
+                bucket.retain(|member| *member != account);
+                self.search_buckets.insert(&previous, &bucket);
+            }
+            if !self.indexed_device_types.contains(&key.0) {
+                self.indexed_device_types.push(key.0.clone());
+            }
+            let mut bucket = self.search_buckets.get(&key).unwrap_or_default();
+            bucket.push(account);
+            self.search_buckets.insert(&key, &bucket);
+            self.search_keys.insert(account, &key);
+        }
+
+        /// Devices matching `filter`, highest reputation decile first, skipping the first
+        /// `offset` matches and returning at most `limit` (capped at `MAX_SEARCH_RESULTS`)
+        #[ink(message)]
+        pub fn search_devices(&self, filter: DeviceSearchFilter, offset: u32, limit: u32) -> Vec<AccountId> {
+            let limit = usize::try_from(limit.min(MAX_SEARCH_RESULTS)).unwrap_or(0);
+            let mut skip = offset;
+            let mut found = Vec::new();
+            let types = match &filter.device_type {
+                Some(device_type) => vec![device_type.clone()],
+                None => self.indexed_device_types.clone(),
+            };
+            let lowest_decile = u8::try_from(filter.min_reputation / 10).unwrap_or(u8::MAX);
+            for decile in (lowest_decile..=10).rev() {
+                for device_type in &types {
+                    for account in self.search_buckets.get((device_type.clone(), decile)).unwrap_or_default() {
+                        if found.len() >= limit {
+                            return found;
+                        }
+                        let Some(device) = self.devices.get(account) else { continue };
+                        let matches = device.reputation >= filter.min_reputation
+                            && filter.zone.as_ref().is_none_or(|zone| device.metadata.zone.starts_with(zone.as_str()))
+                            && (!filter.active_only || (device.active && !self.is_blacklisted(account)));
+                        if !matches {
+                            continue;
+                        }
+                        if skip > 0 {
+                            skip = skip.saturating_sub(1);
+                        } else {
+                            found.push(account);
+                        }
+                    }
+                }
+            }
+            found
+        }
+
+        /// Number of currently active devices (device-track voting denominator)
+        #[ink(message)]
+        pub fn active_device_count(&self) -> u64 {
+            self.active_device_count
+        }
+
+        /// Number of devices registered strictly before `timestamp` (vote snapshot eligibility)
+        #[ink(message)]
+        pub fn devices_registered_before(&self, timestamp: u64) -> u64 {
+            // Registration times are non-decreasing, so binary search for the first one >= timestamp
+            let (mut low, mut high) = (0u64, self.device_count);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if self.registration_times.get(mid).unwrap_or(u64::MAX) < timestamp {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+            low
+        }
+
+        /// Active devices controlled by an account (one device per account, so 0 or 1)
+        #[ink(message)]
+        pub fn account_active_device_count(&self, account: AccountId) -> u32 {
+            match self.devices.get(account) {
+                Some(device) if device.active && !self.is_blacklisted(account) => 1,
+                _ => 0,
+            }
+        }
+
+        /// Get device count
+        #[ink(message)]
+        pub fn get_device_count(&self) -> u64 {
+            self.device_count
+        }
+
+        /// Emit a `HealthSnapshot` event (anyone; at most once per `HEALTH_SNAPSHOT_INTERVAL_BLOCKS`)
+        #[ink(message)]
+        pub fn emit_health_snapshot(&mut self) -> Result<(), String> {
+            let now = self.env().block_number();
+            if self.last_health_snapshot.is_some_and(|last| now < last.saturating_add(HEALTH_SNAPSHOT_INTERVAL_BLOCKS)) {
+                return Err("Health snapshot too soon".into());
+            }
+            self.last_health_snapshot = Some(now);
+            self.env().emit_event(HealthSnapshot {
+                block: now,
+                device_count: self.device_count,
+                active_device_count: self.active_device_count,
+                stake_held: self.env().balance(),
+            });
+            Ok(())
+        }
+
+        /// Get minimum stake
+        #[ink(message)]
+        pub fn get_min_stake(&self) -> Balance {
+            self.min_stake
+        }
+
+        /// Credit PGT already transferred to this contract as stake of a device
+        /// (authorized callers only, e.g. GridService re-staking rewards)
+        #[ink(message)]
+        pub fn increase_stake_for(&mut self, account: AccountId, amount: Balance) -> Result<(), String> {
+            if self.ensure_authorized().is_err() {
+                return Err("Unauthorized caller".into());
+            }
+            if !self.devices.contains(account) {
+                return Err("Device not registered".into());
+            }
+            let total = self.reward_stakes.get(account).unwrap_or(0).saturating_add(amount);
+            self.reward_stakes.insert(account, &total);
+            self.env().emit_event(RewardStakeChanged { account, amount, total_reward_stake: total, increased: true });
+            Ok(())
+        }
+
+        /// Get the PGT stake a device accumulated from re-staked rewards
+        #[ink(message)]
+        pub fn get_reward_stake(&self, account: AccountId) -> Balance {
+            self.reward_stakes.get(account).unwrap_or(0)
+        }
+
+        /// Withdraw PGT reward stake back to the caller's wallet
+        #[ink(message)]
+        pub fn withdraw_reward_stake(&mut self, amount: Balance) -> Result<(), String> {
+            if self.entered { return Err("Reentrancy".into()); }
+            let caller = self.env().caller();
+            let staked = self.reward_stakes.get(caller).unwrap_or(0);
+            if amount == 0 || amount > staked {
+                return Err("Insufficient reward stake".into());
+            }
+            if self.token_address.is_none() {
+                return Err("Token not configured".into());
+            }
+
+            // Effects before the token interaction
+            self.entered = true;
+            let remaining = staked.saturating_sub(amount);
+            self.reward_stakes.insert(caller, &remaining);
+
+            if let Some(mut token) = self.token_address.and_then(Token::connect) {
+                if token.transfer(caller, amount, Vec::new()).is_err() {
+                    self.reward_stakes.insert(caller, &staked);
+                    self.entered = false;
+                    return Err("Token transfer failed".into());
+                }
+            }
+
+            self.env().emit_event(RewardStakeChanged { account: caller, amount, total_reward_stake: remaining, increased: false });
+            self.entered = false;
+            Ok(())
+        }
+
+        /// Set the PGT token used for referral bonuses and reward stake once (owner/governance only);
+        /// later changes go through `propose_address_change`
+        #[ink(message)]
+        pub fn set_token_address(&mut self, token: AccountId) -> Result<(), String> {
+            self.ensure_owner_or_governance("Unauthorized")?;
+            self.set_token_address_internal(token)
+        }
+
+        fn set_token_address_internal(&mut self, token: AccountId) -> Result<(), String> {
+            if self.token_address.is_some() {
+                return Err("Token address is timelocked".into());
+            }
+            self.token_address = Some(token);
+            Ok(())
+        }
+
+        /// Queue a new token or governance address, executable after `ADDRESS_CHANGE_DELAY_MS`
+        /// (token: owner/governance; governance pointer: owner, while no multisig is configured;
+        /// signers through `RegistryAction::ProposeAddressChange` once it is)
+        #[ink(message)]
+        pub fn propose_address_change(&mut self, contract: ExternalContract, new_address: AccountId) -> Result<u64, String> {
+            match contract {
+                ExternalContract::Token => self.ensure_owner_or_governance("Unauthorized")?,
+                ExternalContract::Governance => {
+                    if Some(self.env().caller()) != self.owner {
+                        return Err("Unauthorized".into());
+                    }
+                    self.ensure_multisig_disabled()?;
+                }
+                _ => return Err("Not in address book".into()),
+            }
+            self.propose_address_change_internal(contract, new_address)
+        }
+
+        fn propose_address_change_internal(&mut self, contract: ExternalContract, new_address: AccountId) -> Result<u64, String> {
+            if !matches!(contract, ExternalContract::Token | ExternalContract::Governance) {
+                return Err("Not in address book".into());
+            }
+            let executable_at = self.address_timelock.propose(contract, new_address, self.env().caller(), self.env().block_timestamp());
+            self.env().emit_event(AddressChangeProposed { contract, new_address, executable_at });
+            Ok(executable_at)
+        }
+
+        /// Apply a pending address change once its delay has passed (anyone)
+        #[ink(message)]
+        pub fn execute_address_change(&mut self, contract: ExternalContract) -> Result<(), String> {
+            let new_address = self.address_timelock
+                .take_ready(contract, self.env().block_timestamp())
+                .map_err(|e| String::from(e.as_str()))?;
+            let slot = match contract {
+                ExternalContract::Token => &mut self.token_address,
+                ExternalContract::Governance => &mut self.governance_address,
+                _ => return Err("Not in address book".into()),
+            };
+            let old_address = slot.replace(new_address);
+            self.env().emit_event(AddressChangeExecuted { contract, old_address, new_address });
+            Ok(())
+        }
+
+        /// Drop a pending address change (guardian, owner or governance)
+        #[ink(message)]
+        pub fn cancel_address_change(&mut self, contract: ExternalContract) -> Result<(), String> {
+            let caller = self.env().caller();
+            let caller_opt = Some(caller);
+            if caller_opt != self.guardian && caller_opt != self.owner && caller_opt != self.governance_address {
+                return Err("Unauthorized".into());
+            }
+            if !self.address_timelock.cancel(contract) {
+                return Err("No pending address change".into());
+            }
+            self.env().emit_event(AddressChangeCancelled { contract, by: caller });
+            Ok(())
+        }
+
+        /// Address change waiting out its delay
+        #[ink(message)]
+        pub fn get_pending_address_change(&self, contract: ExternalContract) -> Option<PendingAddressChange> {
+            self.address_timelock.pending(contract)
+        }
+
+        /// External contracts this contract calls (unset ones are omitted)
+        #[ink(message)]
+        pub fn get_address_book(&self) -> Vec<(ExternalContract, AccountId)> {
+            [(ExternalContract::Token, self.token_address), (ExternalContract::Governance, self.governance_address)]
+                .into_iter()
+                .filter_map(|(contract, address)| address.map(|address| (contract, address)))
+                .collect()
+        }
+
+        /// Update device performance (authorized callers only)
+        #[ink(message)]
+        pub fn update_device_performance(&mut self, account: AccountId, energy_contributed: u64, success: bool) -> Result<(), String> {
+            if self.ensure_authorized().is_err() {
+                return Err("Unauthorized caller".into());
+            }
+            self.update_performance_internal(account, None, energy_contributed, success, None)
+        }
+
+        /// Update device performance for a specific grid event (authorized callers only);
+        /// `correlation_id` is echoed in `ReputationUpdated`
+        #[ink(message)]
+        pub fn record_event_performance(
+            &mut self,
+            account: AccountId,
+            event_id: u64,
+            energy_contributed: u64,
+            success: bool,
+            correlation_id: CorrelationId,
+        ) -> Result<(), String> {
+            if self.ensure_authorized().is_err() {
+                return Err("Unauthorized caller".into());
+            }
+            self.update_performance_internal(account, Some(event_id), energy_contributed, success, Some(correlation_id))
+        }
+
+        /// Get performance history, oldest retained entry first
+        #[ink(message)]
+        pub fn get_performance_history(&self, account: AccountId, offset: u64, limit: u64) -> Vec<PerformanceRecord> {
+            let total = self.performance_history_len.get(account).unwrap_or(0);
+            let retained = total.min(MAX_PERFORMANCE_HISTORY);
+            let first = total.saturating_sub(retained);
+            let end = offset.saturating_add(limit.min(MAX_PERFORMANCE_HISTORY)).min(retained);
+            let mut out = Vec::new();
+            for i in offset..end {
+                let slot = first.saturating_add(i) % MAX_PERFORMANCE_HISTORY;
+                if let Some(record) = self.performance_history.get((account, slot)) {
+                    out.push(record);
+                }
+            }
+            out
+        }
+
+        /// Total performance records written for a device (including overwritten ones)
+        #[ink(message)]
+        pub fn get_performance_history_len(&self, account: AccountId) -> u64 {
+            self.performance_history_len.get(account).unwrap_or(0)
+        }
+
+        fn update_performance_internal(
+            &mut self,
+            account: AccountId,
+            event_id: Option<u64>,
+            energy_contributed: u64,
+            success: bool,
+            correlation_id: Option<CorrelationId>,
+        ) -> Result<(), String> {
+            let mut device = self.devices.get(account)
+                .ok_or("Device not registered")?;
+            
+            device.total_energy_contributed = device.total_energy_contributed.saturating_add(energy_contributed);
+            let strikes = self.penalty_strikes.get(account).unwrap_or(0);
+            let penalty = if success {
+                device.successful_events = device.successful_events.saturating_add(1);
+                self.penalty_strikes.insert(account, &strikes.saturating_sub(1));
+                None
+            } else {
+                let strikes = strikes.saturating_add(1);
+                self.penalty_strikes.insert(account, &strikes);
+                let level = self.penalty_policy.level(strikes);
+                if level != PenaltyLevel::Warning {
+                    device.failed_events = device.failed_events.saturating_add(1);
+                }
+                Some((strikes, level))
+            };
+            
+            let old_reputation = device.reputation;
+            device.reputation = self.calculate_performance_score(&device);
+            device.last_activity = self.env().block_timestamp();
+            device.version = device.version.saturating_add(1);
+            device.last_updated = device.last_activity;
+            
+            self.devices.insert(account, &device);
+            self.index_device(account, &device);
+
+            self.env().emit_event(ReputationUpdated {
+                account,
+                old_reputation,
+                new_reputation: device.reputation,
+                correlation_id,
+            });
+            self.env().emit_event(DeviceUpdated { account, version: device.version, timestamp: device.last_updated });
+
+            let total = self.performance_history_len.get(account).unwrap_or(0);
+            self.performance_history.insert((account, total % MAX_PERFORMANCE_HISTORY), &PerformanceRecord {
+                timestamp: device.last_activity,
+                event_id,
+                energy_wh: energy_contributed,
+                success,
+                reputation_after: device.reputation,
+            });
+            self.performance_history_len.insert(account, &total.saturating_add(1));
+
+            if let Some((strikes, level)) = penalty {
+                let slashed = match level {
+                    PenaltyLevel::PartialSlash => device.stake.saturating_mul(Balance::from(self.penalty_policy.partial_slash_bps)) / 10_000,
+                    PenaltyLevel::FullSlash => device.stake,
+                    PenaltyLevel::Warning | PenaltyLevel::ReputationHit => 0,
+                };
+                if slashed > 0 {
+                    self.slash_stake_internal(account, slashed, "Repeated under-delivery".into())?;
+                }
+                self.env().emit_event(PenaltyApplied { account, strikes, level, slashed });
+            }
+
+            if success && device.successful_events >= self.referral_threshold {
+                if let Some(mut referral) = self.referrals.get(account) {
+                    if !referral.qualified {
+                        referral.qualified = true;
+                        self.referrals.insert(account, &referral);
+                        self.pay_referral_bonus(account);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Performance scoring algorithm
+        fn calculate_performance_score(&self, device: &Device) -> u32 {
+            let total_events = device.successful_events.saturating_add(device.failed_events);
+            if total_events == 0 {
+                return 100; // Default score for new devices
+            }
+
+            let success_rate = device.successful_events
+                .saturating_mul(100)
+                .checked_div(total_events)
+                .unwrap_or(0);
+            
+            let energy_factor = device.total_energy_contributed
+                .checked_div(1000)
+                .unwrap_or(0)
+                .min(50); // Up to 50 points for energy
+            
+            let base_score = success_rate.saturating_add(energy_factor as u32);
+            base_score.clamp(1, 100) // Keep between 1-100
+        }
+
+        /// Update minimum stake (owner only)
+        #[ink(message)]
+        pub fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can update minimum stake")?;
+            self.min_stake = new_min_stake;
+            Ok(())
+        }
+
+        /// Update reputation threshold (owner only)
+        #[ink(message)]
+        pub fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can update reputation threshold")?;
+            self.reputation_threshold = new_threshold;
+            Ok(())
+        }
+
+        /// Get reputation threshold
+        #[ink(message)]
+        pub fn get_reputation_threshold(&self) -> u32 {
+            self.reputation_threshold
+        }
+
+        /// Add authorized caller (owner only)
+        #[ink(message)]
+        pub fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can add authorized callers")?;
+            self.authorized_callers.insert(caller, &true);
+            Ok(())
+        }
+
+        /// Remove authorized caller (owner only)
+        #[ink(message)]
+        pub fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+            self.ensure_owner_or_governance("Only owner/governance can remove authorized callers")?;
+            self.authorized_callers.remove(caller);
+            Ok(())
+        }
+
+        /// Wire the governance contract once after deployment (owner only);
+        /// later changes go through `propose_address_change`
+        #[ink(message)]
+        pub fn set_governance_address(&mut self, addr: AccountId) -> Result<(), String> {
+            if Some(self.env().caller()) != self.owner {
+                return Err("Only owner can set governance address".into());
+            }
+            self.ensure_multisig_disabled()?;
+            if self.governance_address != self.owner {
+                return Err("Governance address is timelocked".into());
+            }
+            self.governance_address = Some(addr);
+            Ok(())
+        }
+
+        /// Deactivate a device (owner only)
+        #[ink(message)]
+        pub fn deactivate_device(&mut self, account: AccountId, reason: String) -> Result<(), String> {
+            if Some(self.env().caller()) != self.owner {
+                return Err("Only owner can deactivate devices".into());
+            }
+            self.ensure_multisig_disabled()?;
+            self.deactivate_device_internal(account, reason)
+        }
+
+        fn deactivate_device_internal(&mut self, account: AccountId, reason: String) -> Result<(), String> {
+            let mut device = self.devices.get(account)
+                .ok_or("Device not registered")?;
+            
+            self.set_device_active(&mut device, false);
+            self.devices.insert(account, &device);
+
+            self.env().emit_event(DeviceDeactivated {
+                account,
+                reason,
+            });
+
+            Ok(())
+        }
+
+        /// Reactivate a device (owner only)
+        #[ink(message)]
+        pub fn reactivate_device(&mut self, account: AccountId) -> Result<(), String> {
+            if Some(self.env().caller()) != self.owner {
+                return Err("Only owner can reactivate devices".into());
+            }
+            self.ensure_multisig_disabled()?;
+            self.reactivate_device_internal(account)
+        }
+
+        fn reactivate_device_internal(&mut self, account: AccountId) -> Result<(), String> {
+            let mut device = self.devices.get(account)
+                .ok_or("Device not registered")?;
+            
+            self.set_device_active(&mut device, true);
+            self.devices.insert(account, &device);
+
+            Ok(())
+        }
+
+        /// Get all authorized callers (owner only)
+        #[ink(message)]
+        pub fn get_authorized_callers(&self) -> Result<Vec<AccountId>, String> {
+            if Some(self.env().caller()) != self.owner {
+                return Err("Only owner can view authorized callers".into());
+            }
+            // Not iterable safely; return empty list to avoid O(n)
+            Ok(Vec::new())
+        }
+
+        /// Check if an account is authorized
+        #[ink(message)]
+        pub fn is_authorized_caller(&self, account: AccountId) -> bool {
+            if Some(account) == self.owner { return true; }
+            self.authorized_callers.get(account).unwrap_or(false)
+        }
+
+        /// Check if caller is authorized
+        fn ensure_authorized(&self) -> Result<(), String> {
+            let caller = self.env().caller();
+            if Some(caller) == self.owner || self.authorized_callers.get(caller).unwrap_or(false) {
+                Ok(())
+            } else {
+                Err("Unauthorized caller".into())
+            }
+        }
+
+        /// Withdraw stake (down to minimum if active)
+        #[ink(message)]
+        pub fn withdraw_stake(&mut self, amount: Balance) -> Result<(), String> {
+            if self.entered { return Err("Reentrancy".into()); }
+            if self.paused { return Err("Paused".into()); }
+            let caller = self.env().caller();
+            let mut device = self.devices.get(caller).ok_or("Device not registered")?;
+            if amount == 0 { return Ok(()); }
+            if amount > device.stake { return Err("AmountExceedsStake".into()); }
+            let remaining = device.stake.saturating_sub(amount);
+            if device.active && remaining < self.min_stake { return Err("BelowMinStake".into()); }
+
+            // Lower the stake before paying out; the guard stays up for the transfer only
+            device.stake = remaining;
+            self.devices.insert(caller, &device);
+            self.env().emit_event(StakeWithdrawn { account: caller, amount, remaining_stake: remaining });
+
+            self.entered = true;
+            let transferred = self.env().transfer(caller, amount);
+            self.entered = false;
+            transferred.map_err(|_| String::from("TransferFailed"))
+        }
+
+        /// Slash stake (owner/governance)
+        #[ink(message)]
+        pub fn slash_stake(&mut self, account: AccountId, amount: Balance, reason: String) -> Result<(), String> {
+            self.ensure_owner_or_governance("Unauthorized")?;
+            self.slash_stake_internal(account, amount, reason)
+        }
+
+        fn slash_stake_internal(&mut self, account: AccountId, amount: Balance, reason: String) -> Result<(), String> {
+            if self.entered { return Err("Reentrancy".into()); }
+            let mut device = self.devices.get(account).ok_or("Device not registered")?;
+            self.entered = true;
+            let slash_amt = core::cmp::min(amount, device.stake);
+            device.stake = device.stake.saturating_sub(slash_amt);
+            if device.stake < self.min_stake { self.set_device_active(&mut device, false); }
+            self.devices.insert(account, &device);
+            let period = self.env().block_timestamp() / STATEMENT_PERIOD_MS;
+            let slashed = self.slashed_stake.get((account, period)).unwrap_or(0).saturating_add(slash_amt);
+            self.slashed_stake.insert((account, period), &slashed);
+            self.env().emit_event(StakeSlashed { account, amount: slash_amt, remaining_stake: device.stake, reason });
+            self.entered = false;
+            Ok(())
+        }
 
-            let account_bytes = ink_account_to_bytes(account);
-            let mut device = self.devices.get(account_bytes)
-                .ok_or("Device not registered")?;
-            
-            device.active = false;
-            self.devices.insert(account_bytes, &device);
+        /// Owner or governance. Governance keeps direct access; the owner key needs multisig
+        /// approval when enabled, even while it still doubles as the governance pointer
+        fn ensure_owner_or_governance(&self, error: &str) -> Result<(), String> {
+            let sender = Some(self.env().caller());
+            if sender != self.owner && sender != self.governance_address {
+                return Err(error.into());
+            }
+            if sender == self.owner {
+                self.ensure_multisig_disabled()?;
+            }
+            Ok(())
+        }
 
-            self.env().emit_event(DeviceDeactivated {
-                account,
-                reason,
+        fn ensure_multisig_disabled(&self) -> Result<(), String> {
+            if self.signer_threshold > 0 {
+                return Err("Multisig approval required".into());
+            }
+            Ok(())
+        }
+
+        /// Enable the multisig signer set (owner only, while multisig is disabled).
+        /// Later changes go through `RegistryAction::ConfigureMultisig`.
+        #[ink(message)]
+        pub fn configure_multisig(&mut self, signers: Vec<AccountId>, threshold: u32) -> Result<(), String> {
+            if Some(self.env().caller()) != self.owner {
+                return Err("Only owner can configure multisig".into());
+            }
+            self.ensure_multisig_disabled()?;
+            self.set_signers(signers, threshold)
+        }
+
+        fn set_signers(&mut self, mut signers: Vec<AccountId>, threshold: u32) -> Result<(), String> {
+            signers.sort();
+            signers.dedup();
+            if threshold as usize > signers.len() {
+                return Err("Threshold exceeds signer count".into());
+            }
+            self.signers = signers;
+            self.signer_threshold = threshold;
+            Ok(())
+        }
+
+        /// Get multisig signers and threshold
+        #[ink(message)]
+        pub fn get_multisig(&self) -> (Vec<AccountId>, u32) {
+            (self.signers.clone(), self.signer_threshold)
+        }
+
+        /// Propose a privileged action (signers only); the proposer's approval is counted
+        #[ink(message)]
+        pub fn propose_action(&mut self, action: RegistryAction) -> Result<u64, String> {
+            let caller = self.env().caller();
+            if self.signer_threshold == 0 {
+                return Err("Multisig not enabled".into());
+            }
+            if !self.signers.contains(&caller) {
+                return Err("Only signers can propose actions".into());
+            }
+            let action_id = self.next_action_id;
+            self.next_action_id = self.next_action_id.saturating_add(1);
+            self.pending_actions.insert(action_id, &PendingAction {
+                action,
+                proposer: caller,
+                approvals: Vec::new(),
+                executed: false,
             });
+            self.env().emit_event(ActionProposed { action_id, proposer: caller });
+            self.approve_action(action_id)?;
+            Ok(action_id)
+        }
 
+        /// Approve a pending action (signers only); executes once the threshold is reached
+        #[ink(message)]
+        pub fn approve_action(&mut self, action_id: u64) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.signers.contains(&caller) {
+                return Err("Only signers can approve actions".into());
+            }
+            let mut pending = self.pending_actions.get(action_id).ok_or("Action not found")?;
+            if pending.executed {
+                return Err("Action already executed".into());
+            }
+            if pending.approvals.contains(&caller) {
+                return Err("Already approved".into());
+            }
+            pending.approvals.push(caller);
+            let approvals = pending.approvals.len() as u32;
+            self.env().emit_event(ActionApproved { action_id, signer: caller, approvals });
+
+            // Only approvals from current signers count (the set may have changed)
+            let valid = pending.approvals.iter().filter(|a| self.signers.contains(a)).count() as u32;
+            if valid >= self.signer_threshold {
+                pending.executed = true;
+                self.pending_actions.insert(action_id, &pending);
+                let success = self.execute_action(pending.action).is_ok();
+                self.env().emit_event(ActionExecuted { action_id, success });
+            } else {
+                self.pending_actions.insert(action_id, &pending);
+            }
             Ok(())
         }
 
-        /// Reactivate a device (owner only)
+        /// Get a multisig action
         #[ink(message)]
-        pub fn reactivate_device(&mut self, account: AccountId) -> Result<(), String> {
-            if Some(self.env().caller()) != self.owner {
-                return Err("Only owner can reactivate devices".into());
+        pub fn get_action(&self, action_id: u64) -> Option<PendingAction> {
+            self.pending_actions.get(action_id)
+        }
+
+        fn execute_action(&mut self, action: RegistryAction) -> Result<(), String> {
+            match action {
+                RegistryAction::SlashStake(account, amount, reason) => self.slash_stake_internal(account, amount, reason),
+                RegistryAction::DeactivateDevice(account, reason) => self.deactivate_device_internal(account, reason),
+                RegistryAction::ReactivateDevice(account) => self.reactivate_device_internal(account),
+                RegistryAction::SetGovernanceAddress(addr) => {
+                    self.governance_address = Some(addr);
+                    Ok(())
+                }
+                RegistryAction::ConfigureMultisig(signers, threshold) => self.set_signers(signers, threshold),
+                RegistryAction::UpdateMinStake(min_stake) => {
+                    self.min_stake = min_stake;
+                    Ok(())
+                }
+                RegistryAction::UpdateReputationThreshold(threshold) => {
+                    self.reputation_threshold = threshold;
+                    Ok(())
+                }
+                RegistryAction::AddAuthorizedCaller(caller) => {
+                    self.authorized_callers.insert(caller, &true);
+                    Ok(())
+                }
+                RegistryAction::RemoveAuthorizedCaller(caller) => {
+                    self.authorized_callers.remove(caller);
+                    Ok(())
+                }
+                RegistryAction::SetDeviceTypePolicy(device_type, policy) => self.set_device_type_policy_internal(device_type, policy),
+                RegistryAction::SetManufacturerKey(manufacturer, key) => self.set_manufacturer_key_internal(manufacturer, key),
+                RegistryAction::SetMinFirmwareVersion(manufacturer, model, version) => {
+                    self.set_min_firmware_version_internal(manufacturer, model, version)
+                }
+                RegistryAction::SetReferralProgram(token, bonus, threshold) => self.set_referral_program_internal(token, bonus, threshold),
+                RegistryAction::SetPenaltyPolicy(policy) => self.set_penalty_policy_internal(policy),
+                RegistryAction::SetTokenAddress(token) => self.set_token_address_internal(token),
+                RegistryAction::ProposeAddressChange(contract, new_address) => {
+                    self.propose_address_change_internal(contract, new_address).map(|_| ())
+                }
             }
+        }
+
+        /// Pause/unpause (owner or governance); the guardian may only pause
+        #[ink(message)]
+        pub fn set_paused(&mut self, pause: bool) -> Result<(), String> {
+            let sender = self.env().caller();
+            let is_admin = Some(sender) == self.owner || Some(sender) == self.governance_address;
+            let is_guardian_pause = pause && Some(sender) == self.guardian;
+            if !is_admin && !is_guardian_pause { return Err("Unauthorized".into()); }
+            self.paused = pause;
+            self.env().emit_event(PauseChanged { by: sender, paused: pause });
+            Ok(())
+        }
+
+        /// Get device stake
+        #[ink(message)]
+        pub fn get_device_stake(&self, account: AccountId) -> Option<Balance> {
+            self.devices.get(account).map(|device| device.stake)
+        }
+
+        /// Get device activity status
+        #[ink(message)]
+        pub fn is_device_active(&self, account: AccountId) -> Option<bool> {
+            self.devices.get(account).map(|device| device.active)
+        }
+
+        /// Get device energy contribution
+        #[ink(message)]
+        pub fn get_device_energy_contribution(&self, account: AccountId) -> Option<u64> {
+            self.devices.get(account).map(|device| device.total_energy_contributed)
+        }
+
+        /// Get device event statistics
+        #[ink(message)]
+        pub fn get_device_event_stats(&self, account: AccountId) -> Option<(u32, u32)> {
+            self.devices.get(account).map(|device| (device.successful_events, device.failed_events))
+        }
+    }
+    
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{default_accounts, set_block_timestamp, set_caller, set_value_transferred, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        #[ink::test]
+        fn test_device_registration_success() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            let result = registry.register_device(metadata);
+            assert!(result.is_ok());
+            assert_eq!(registry.get_device_count(), 1);
+            assert!(registry.is_device_registered(accounts.alice));
+        }
+
+        #[ink::test]
+        fn test_device_registration_insufficient_stake() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            set_value_transferred::<DefaultEnvironment>(500); // Below minimum
+
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            let result = registry.register_device(metadata);
+            assert!(result.is_err());
+            assert_eq!(registry.get_device_count(), 0);
+        }
+
+        #[ink::test]
+        fn test_device_deactivation() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            // Register device first
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            let _ = registry.register_device(metadata);
+
+            // Deactivate as owner
+            let result = registry.deactivate_device(accounts.alice, "Test deactivation".into());
+            assert!(result.is_ok());
+
+            let device = registry.get_device(accounts.alice).unwrap();
+            assert!(!device.active);
+            assert_eq!(registry.is_device_active(accounts.alice), Some(false));
+        }
+
+        #[ink::test]
+        fn test_stake_increase() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            // Register device first
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            let _ = registry.register_device(metadata);
+
+            // Increase stake
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(500));
+            let result = registry.increase_stake();
+            assert!(result.is_ok());
+
+            assert_eq!(registry.get_device_stake(accounts.alice), Some(tokens_to_native(2000)));
+        }
+
+        #[ink::test]
+        fn test_authorized_caller_management() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            // Add authorized caller
+            let result = registry.add_authorized_caller(accounts.bob);
+            assert!(result.is_ok());
+            assert!(registry.is_authorized_caller(accounts.bob));
+
+            // Remove authorized caller
+            let result = registry.remove_authorized_caller(accounts.bob);
+            assert!(result.is_ok());
+            assert!(!registry.is_authorized_caller(accounts.bob));
+        }
+
+
+        #[ink::test]
+        fn test_device_type_policy() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            let policy = DeviceTypePolicy {
+                enabled: true,
+                min_stake: tokens_to_native(2000),
+                max_capacity_watts: 5000,
+            };
+            assert!(registry.set_device_type_policy(DeviceType::Battery, Some(policy.clone())).is_ok());
+            assert_eq!(registry.get_device_type_policy(DeviceType::Battery), Some(policy));
+            assert!(registry.set_device_type_policy(DeviceType::EV, Some(DeviceTypePolicy {
+                enabled: false,
+                min_stake: 0,
+                max_capacity_watts: 0,
+            })).is_ok());
+
+            let metadata = |device_type: DeviceType, capacity_watts: u64| DeviceMetadata {
+                device_type,
+                capacity_watts,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Powerwall".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert_eq!(registry.register_device(metadata(DeviceType::EV, 2000)), Err("Device type not allowed".into()));
+            assert_eq!(registry.register_device(metadata(DeviceType::Battery, 2000)), Err("Insufficient stake for device type".into()));
+
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(2500));
+            assert_eq!(registry.register_device(metadata(DeviceType::Battery, 9000)), Err("Capacity exceeds device type maximum".into()));
+            assert!(registry.register_device(metadata(DeviceType::Battery, 4000)).is_ok());
+
+            // Metadata updates are checked against the same policy
+            assert!(registry.update_device_metadata(metadata(DeviceType::Battery, 9000)).is_err());
+            assert!(registry.update_device_metadata(metadata(DeviceType::Battery, 4500)).is_ok());
+            assert_eq!(registry.get_device(accounts.bob).unwrap().metadata.capacity_watts, 4500);
+        }
+
+
+        #[ink::test]
+        fn test_firmware_minimum_version() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            assert!(registry.register_device(metadata.clone()).is_ok());
+            assert!(registry.is_eligible(accounts.bob));
+
+            // Only owner/governance can set firmware requirements
+            assert!(registry.set_min_firmware_version("Tesla".into(), "Model S".into(), Some("1.2.0".into())).is_err());
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.set_min_firmware_version("Tesla".into(), "Model S".into(), Some("1.2.0".into())).is_ok());
+            assert!(!registry.is_eligible(accounts.bob));
+
+            // Attestations need a registered manufacturer key and a valid signature
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.submit_firmware_attestation("1.2.0".into(), [7u8; 32], None, 0, vec![0u8; 64]),
+                Err("Unknown manufacturer key".into())
+            );
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.set_manufacturer_key("Tesla".into(), Some([1u8; 32])).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.submit_firmware_attestation("1.2.0".into(), [7u8; 32], None, 0, vec![0u8; 64]),
+                Err("Invalid attestation signature".into())
+            );
+
+            // Firmware cannot be bumped through plain metadata updates
+            let mut bumped = metadata.clone();
+            bumped.firmware_version = "1.2.0".into();
+            assert!(registry.update_device_metadata(bumped).is_err());
+            assert!(!registry.is_eligible(accounts.bob));
+
+            // Nor escaped by switching to a model without a minimum
+            let mut renamed = metadata;
+            renamed.model = "Model 3".into();
+            assert_eq!(registry.update_device_metadata(renamed), Err("Manufacturer and model cannot change".into()));
+            assert!(!registry.is_eligible(accounts.bob));
+        }
+
+
+        #[ink::test]
+        fn test_blacklist_with_expiry() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            assert!(registry.set_guardian(Some(accounts.eve)).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(registry.blacklist_device(accounts.charlie, 1, 5_000).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(registry.blacklist_device(accounts.charlie, 7, 5_000).is_ok());
+            assert!(registry.blacklist_device(accounts.bob, 2, 5_000).is_ok());
+            assert_eq!(registry.get_blacklist_entry(accounts.charlie).map(|e| e.reason_code), Some(7));
+
+            // Blacklisted accounts cannot (re-)register
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert_eq!(registry.register_device(metadata.clone()), Err("Account is blacklisted".into()));
+
+            // Early lift by guardian
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(registry.unblacklist_device(accounts.charlie).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(registry.register_device(metadata).is_ok());
+            assert!(registry.is_eligible(accounts.charlie));
+
+            // Ban on bob expires on its own
+            assert!(registry.is_blacklisted(accounts.bob));
+            set_block_timestamp::<DefaultEnvironment>(5_000);
+            assert!(!registry.is_blacklisted(accounts.bob));
+        }
+
+
+        #[ink::test]
+        fn test_location_commitment_reveal() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let salt = [9u8; 32];
+            let mut metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: location_commitment(salt, "221B Baker Street"),
+                zone: "gcpvjxyz".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
 
-            let account_bytes = ink_account_to_bytes(account);
-            let mut device = self.devices.get(account_bytes)
-                .ok_or("Device not registered")?;
-            
-            device.active = true;
-            self.devices.insert(account_bytes, &device);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert_eq!(registry.register_device(metadata.clone()), Err("Zone too precise".into()));
 
-            Ok(())
-        }
+            metadata.zone = "gcpvj".into();
+            assert!(registry.register_device(metadata).is_ok());
+            assert_eq!(registry.get_device_zone(accounts.bob), Some("gcpvj".into()));
 
-        /// Get all authorized callers (owner only)
-        #[ink(message)]
-        pub fn get_authorized_callers(&self) -> Result<Vec<AccountId>, String> {
-            if Some(self.env().caller()) != self.owner {
-                return Err("Only owner can view authorized callers".into());
-            }
-            // Not iterable safely; return empty list to avoid O(n)
-            Ok(Vec::new())
+            assert!(registry.reveal_location(accounts.bob, salt, "221B Baker Street".into()));
+            assert!(!registry.reveal_location(accounts.bob, [0u8; 32], "221B Baker Street".into()));
+            assert!(!registry.reveal_location(accounts.bob, salt, "10 Downing Street".into()));
         }
 
-        /// Check if an account is authorized
-        #[ink(message)]
-        pub fn is_authorized_caller(&self, account: AccountId) -> bool {
-            if Some(account) == self.owner { return true; }
-            self.authorized_callers.get(account).unwrap_or(false)
-        }
 
-        /// Check if caller is authorized
-        fn ensure_authorized(&self) -> Result<(), String> {
-            let caller = self.env().caller();
-            if Some(caller) == self.owner || self.authorized_callers.get(caller).unwrap_or(false) {
-                Ok(())
-            } else {
-                Err("Unauthorized caller".into())
-            }
-        }
+        #[ink::test]
+        fn test_device_sla_tracking() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let hour = HEARTBEAT_INTERVAL_MS;
 
-        /// Withdraw stake (down to minimum if active)
-        #[ink(message)]
-        pub fn withdraw_stake(&mut self, amount: Balance) -> Result<(), String> {
-            if self.entered { return Err("Reentrancy".into()); }
-            self.entered = true;
-            if self.paused { self.entered = false; return Err("Paused".into()); }
-            let caller = self.env().caller();
-            let caller_bytes = ink_account_to_bytes(caller);
-            let mut device = self.devices.get(caller_bytes).ok_or("Device not registered")?;
-            if amount == 0 { return Ok(()); }
-            if amount > device.stake { return Err("AmountExceedsStake".into()); }
-            let remaining = device.stake.saturating_sub(amount);
-            if device.active && remaining < self.min_stake { return Err("BelowMinStake".into()); }
-            device.stake = remaining;
-            self.devices.insert(caller_bytes, &device);
-            self.env().transfer(caller, amount).map_err(|_| String::from("TransferFailed"))?;
-            self.env().emit_event(StakeWithdrawn { account: caller, amount, remaining_stake: remaining });
-            self.entered = false;
-            Ok(())
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.heartbeat(), Err("Device not registered".into()));
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            assert!(registry.register_device(metadata).is_ok());
+            assert_eq!(registry.get_device_sla(accounts.bob), None);
+
+            // Online for 2 of 4 hours; repeated heartbeats in one hour count once
+            set_block_timestamp::<DefaultEnvironment>(hour);
+            assert!(registry.heartbeat().is_ok());
+            set_block_timestamp::<DefaultEnvironment>(hour + 10);
+            assert!(registry.heartbeat().is_ok());
+            set_block_timestamp::<DefaultEnvironment>(2 * hour);
+            assert!(registry.heartbeat().is_ok());
+            set_block_timestamp::<DefaultEnvironment>(4 * hour);
+            let sla = registry.get_device_sla(accounts.bob).unwrap();
+            assert_eq!(sla.heartbeats, 2);
+            assert_eq!(sla.expected_heartbeats, 4);
+            assert_eq!(sla.uptime_percent, 50);
+
+            // Only authorized gateways can report for other devices
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(registry.record_heartbeat(accounts.bob).is_err());
+
+            // Previous window fades out of the trailing 30 days
+            set_block_timestamp::<DefaultEnvironment>(hour + 2 * SLA_WINDOW_MS);
+            let sla = registry.get_device_sla(accounts.bob).unwrap();
+            assert_eq!(sla.heartbeats, 0);
+            assert_eq!(sla.uptime_percent, 0);
         }
 
-        /// Slash stake (owner/governance)
-        #[ink(message)]
-        pub fn slash_stake(&mut self, account: AccountId, amount: Balance, reason: String) -> Result<(), String> {
-            if self.entered { return Err("Reentrancy".into()); }
-            self.entered = true;
-            let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address { return Err("Unauthorized".into()); }
-            let acc_bytes = ink_account_to_bytes(account);
-            let mut device = self.devices.get(acc_bytes).ok_or("Device not registered")?;
-            let slash_amt = core::cmp::min(amount, device.stake);
-            device.stake = device.stake.saturating_sub(slash_amt);
-            if device.stake < self.min_stake { device.active = false; }
-            self.devices.insert(acc_bytes, &device);
-            self.env().emit_event(StakeSlashed { account, amount: slash_amt, remaining_stake: device.stake, reason });
-            self.entered = false;
-            Ok(())
-        }
 
-        /// Pause/unpause (owner or governance)
-        #[ink(message)]
-        pub fn set_paused(&mut self, pause: bool) -> Result<(), String> {
-            let sender = self.env().caller();
-            if Some(sender) != self.owner && Some(sender) != self.governance_address { return Err("Unauthorized".into()); }
-            self.paused = pause;
-            Ok(())
+        #[ink::test]
+        fn test_referral_program() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            assert!(registry.set_referral_program(accounts.django, 100, 2).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert_eq!(
+                registry.register_device_with_referrer(metadata.clone(), accounts.bob),
+                Err("Cannot refer yourself".into())
+            );
+            assert_eq!(
+                registry.register_device_with_referrer(metadata.clone(), accounts.charlie),
+                Err("Referrer not an active device".into())
+            );
+            assert!(registry.register_device(metadata.clone()).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(registry.register_device_with_referrer(metadata, accounts.bob).is_ok());
+            assert_eq!(registry.get_referral_count(accounts.bob), 1);
+
+            // Qualifies after the threshold of verified participations
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.update_device_performance(accounts.charlie, 100, true).is_ok());
+            assert!(!registry.get_referral(accounts.charlie).unwrap().qualified);
+            assert!(registry.update_device_performance(accounts.charlie, 100, true).is_ok());
+            let referral = registry.get_referral(accounts.charlie).unwrap();
+            assert_eq!(referral.referrer, accounts.bob);
+            assert!(referral.qualified);
+            // No pool funded off-chain, so the bonus stays claimable
+            assert!(!referral.rewarded);
+            assert!(registry.claim_referral_bonus(accounts.charlie).is_err());
         }
 
-        /// Get device stake
-        #[ink(message)]
-        pub fn get_device_stake(&self, account: AccountId) -> Option<Balance> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes).map(|device| device.stake)
-        }
 
-        /// Get device activity status
-        #[ink(message)]
-        pub fn is_device_active(&self, account: AccountId) -> Option<bool> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes).map(|device| device.active)
+        #[ink::test]
+        fn test_referral_bonus_survives_failed_second_transfer() {
+            use powergrid_shared::mock::{MockToken, MockTokenState};
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            let mut token = MockTokenState::default();
+            token.balances.insert(ink::env::account_id::<DefaultEnvironment>(), 1_000);
+            MockToken::install(accounts.django, token);
+            assert!(registry.set_referral_program(accounts.django, 100, 1).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device(metadata.clone()).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(registry.register_device_with_referrer(metadata, accounts.bob).is_ok());
+
+            // The referrer's transfer lands, the referred device's fails
+            let mut transfers = 0;
+            MockToken::on_call(accounts.django, move |call| {
+                if call == "transfer" {
+                    transfers += 1;
+                    if transfers == 2 {
+                        MockToken::update(accounts.django, |token| token.failing.insert("transfer"));
+                    }
+                }
+            });
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.update_device_performance(accounts.charlie, 100, true).is_ok());
+            let referral = registry.get_referral(accounts.charlie).unwrap();
+            assert!(referral.qualified && referral.referrer_paid && !referral.rewarded);
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.bob), 100);
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.charlie), 0);
+
+            // The retry pays only the missing half
+            MockToken::update(accounts.django, |token| token.failing.remove("transfer"));
+            assert!(registry.claim_referral_bonus(accounts.charlie).is_ok());
+            assert!(registry.get_referral(accounts.charlie).unwrap().rewarded);
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.bob), 100);
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.charlie), 100);
         }
 
-        /// Get device energy contribution
-        #[ink(message)]
-        pub fn get_device_energy_contribution(&self, account: AccountId) -> Option<u64> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes).map(|device| device.total_energy_contributed)
-        }
 
-        /// Get device event statistics
-        #[ink(message)]
-        pub fn get_device_event_stats(&self, account: AccountId) -> Option<(u32, u32)> {
-            let account_bytes = ink_account_to_bytes(account);
-            self.devices.get(account_bytes).map(|device| (device.successful_events, device.failed_events))
+        #[ink::test]
+        fn test_multisig_privileged_actions() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            assert!(registry.register_device(metadata).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.configure_multisig(vec![accounts.charlie, accounts.django], 3).is_err());
+            assert!(registry.configure_multisig(vec![accounts.charlie, accounts.django, accounts.eve], 2).is_ok());
+            assert!(registry.set_governance_address(accounts.frank).is_err());
+
+            // Owner can no longer act alone
+            assert_eq!(
+                registry.deactivate_device(accounts.bob, "fraud".into()),
+                Err("Multisig approval required".into())
+            );
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(registry.propose_action(RegistryAction::ReactivateDevice(accounts.bob)).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let id = registry.propose_action(RegistryAction::DeactivateDevice(accounts.bob, "fraud".into())).unwrap();
+            assert_eq!(registry.approve_action(id), Err("Already approved".into()));
+            assert_eq!(registry.is_device_active(accounts.bob), Some(true));
+
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(registry.approve_action(id).is_ok());
+            assert!(registry.get_action(id).unwrap().executed);
+            assert_eq!(registry.is_device_active(accounts.bob), Some(false));
+            assert!(registry.approve_action(id).is_err());
+
+            // Parameter setters are privileged too: the owner key needs the signers for them
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(registry.update_min_stake(tokens_to_native(2000)), Err("Multisig approval required".into()));
+            assert_eq!(registry.update_reputation_threshold(80), Err("Multisig approval required".into()));
+            assert_eq!(registry.set_device_type_policy(DeviceType::Battery, None), Err("Multisig approval required".into()));
+            assert_eq!(registry.set_referral_program(accounts.django, 100, 2), Err("Multisig approval required".into()));
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let id = registry.propose_action(RegistryAction::UpdateReputationThreshold(80)).unwrap();
+            assert_eq!(registry.get_reputation_threshold(), 50);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(registry.approve_action(id).is_ok());
+            assert_eq!(registry.get_reputation_threshold(), 80);
         }
-    }
-    
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use powergrid_shared::DeviceType;
-        use ink::env::test::{default_accounts, set_caller, set_value_transferred, DefaultAccounts};
-        use ink::env::DefaultEnvironment;
 
         #[ink::test]
-        fn test_device_registration_success() {
+        fn test_performance_history() {
             let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
             let mut registry = ResourceRegistry::new(1000);
 
-            set_caller::<DefaultEnvironment>(accounts.alice);
-            set_value_transferred::<DefaultEnvironment>(1500);
-
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
             let metadata = DeviceMetadata {
                 device_type: DeviceType::SmartPlug,
                 capacity_watts: 2000,
-                location: "Home".into(),
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
                 manufacturer: "Tesla".into(),
                 model: "Model S".into(),
                 firmware_version: "1.0.0".into(),
                 installation_date: 1640995200,
             };
+            assert!(registry.register_device(metadata).is_ok());
 
-            let result = registry.register_device(metadata);
-            assert!(result.is_ok());
-            assert_eq!(registry.get_device_count(), 1);
-            assert!(registry.is_device_registered(accounts.alice));
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.record_event_performance(accounts.bob, 7, 500, true, [0; 32]).is_ok());
+            assert!(registry.update_device_performance(accounts.bob, 0, false).is_ok());
+            let history = registry.get_performance_history(accounts.bob, 0, 10);
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].event_id, Some(7));
+            assert_eq!(history[0].energy_wh, 500);
+            assert!(!history[1].success);
+            assert_eq!(history[1].reputation_after, registry.get_device_reputation(accounts.bob).unwrap());
+
+            // Bounded: oldest entries are overwritten
+            for i in 0..MAX_PERFORMANCE_HISTORY {
+                assert!(registry.record_event_performance(accounts.bob, 100 + i, 1, true, [0; 32]).is_ok());
+            }
+            assert_eq!(registry.get_performance_history_len(accounts.bob), MAX_PERFORMANCE_HISTORY + 2);
+            let page = registry.get_performance_history(accounts.bob, 0, 3);
+            assert_eq!(page.iter().map(|r| r.event_id).collect::<Vec<_>>(), vec![Some(100), Some(101), Some(102)]);
+            assert_eq!(registry.get_performance_history(accounts.bob, MAX_PERFORMANCE_HISTORY - 1, 10).len(), 1);
         }
 
+
         #[ink::test]
-        fn test_device_registration_insufficient_stake() {
+        fn test_reward_restaking() {
             let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
             let mut registry = ResourceRegistry::new(1000);
 
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            assert!(registry.register_device(metadata).is_ok());
+            assert!(registry.increase_stake_for(accounts.bob, 100).is_err());
+
             set_caller::<DefaultEnvironment>(accounts.alice);
-            set_value_transferred::<DefaultEnvironment>(500); // Below minimum
+            assert!(registry.increase_stake_for(accounts.charlie, 100).is_err());
+            assert!(registry.increase_stake_for(accounts.bob, 100).is_ok());
+            assert!(registry.increase_stake_for(accounts.bob, 50).is_ok());
+            assert_eq!(registry.get_reward_stake(accounts.bob), 150);
+            assert!(registry.set_token_address(accounts.django).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(registry.withdraw_reward_stake(200).is_err());
+            assert!(registry.withdraw_reward_stake(120).is_ok());
+            assert_eq!(registry.get_reward_stake(accounts.bob), 30);
+        }
+
+
+        #[ink::test]
+        fn test_metadata_uri_and_attributes() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
 
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(registry.set_attribute("dso_id".into(), "DSO-17".into()).is_err());
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
             let metadata = DeviceMetadata {
                 device_type: DeviceType::SmartPlug,
                 capacity_watts: 2000,
-                location: "Home".into(),
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
                 manufacturer: "Tesla".into(),
                 model: "Model S".into(),
                 firmware_version: "1.0.0".into(),
                 installation_date: 1640995200,
             };
+            assert!(registry.register_device(metadata).is_ok());
 
-            let result = registry.register_device(metadata);
-            assert!(result.is_err());
-            assert_eq!(registry.get_device_count(), 0);
+            assert!(registry.set_metadata_uri(Some("ipfs://bafybeigdyrzt".into())).is_ok());
+            assert_eq!(registry.get_metadata_uri(accounts.bob), Some("ipfs://bafybeigdyrzt".into()));
+
+            assert!(registry.set_attribute("dso_id".into(), "DSO-17".into()).is_ok());
+            assert!(registry.set_attribute("tariff_class".into(), "residential".into()).is_ok());
+            assert!(registry.set_attribute("dso_id".into(), "DSO-18".into()).is_ok());
+            assert_eq!(registry.get_attribute(accounts.bob, "dso_id".into()), Some("DSO-18".into()));
+            assert_eq!(registry.get_attribute_keys(accounts.bob).len(), 2);
+
+            assert!(registry.remove_attribute("dso_id".into()).is_ok());
+            assert_eq!(registry.get_attribute(accounts.bob, "dso_id".into()), None);
+            assert!(registry.remove_attribute("dso_id".into()).is_err());
+
+            for i in 0..(MAX_DEVICE_ATTRIBUTES - 1) {
+                assert!(registry.set_attribute(format!("k{}", i), "v".into()).is_ok());
+            }
+            assert_eq!(registry.set_attribute("extra".into(), "v".into()), Err("Too many attributes".into()));
         }
 
+
         #[ink::test]
-        fn test_device_deactivation() {
+        fn test_device_counts_for_governance() {
             let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
             let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+
+            for (i, account) in [accounts.bob, accounts.charlie, accounts.django].into_iter().enumerate() {
+                set_block_timestamp::<DefaultEnvironment>(1_000 * (i as u64 + 1));
+                set_caller::<DefaultEnvironment>(account);
+                set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+                assert!(registry.register_device(metadata.clone()).is_ok());
+            }
+            assert_eq!(registry.active_device_count(), 3);
+            assert_eq!(registry.devices_registered_before(1_000), 0);
+            assert_eq!(registry.devices_registered_before(2_500), 2);
+            assert_eq!(registry.devices_registered_before(10_000), 3);
 
-            // Register device first
             set_caller::<DefaultEnvironment>(accounts.alice);
-            set_value_transferred::<DefaultEnvironment>(1500);
+            assert!(registry.deactivate_device(accounts.charlie, "maintenance".into()).is_ok());
+            assert!(registry.deactivate_device(accounts.charlie, "maintenance".into()).is_ok());
+            assert_eq!(registry.active_device_count(), 2);
+            assert_eq!(registry.account_active_device_count(accounts.charlie), 0);
+            assert_eq!(registry.account_active_device_count(accounts.bob), 1);
+
+            assert!(registry.reactivate_device(accounts.charlie).is_ok());
+            assert_eq!(registry.active_device_count(), 3);
+        }
 
+        #[ink::test]
+        fn test_attestation_signature_formats() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
             let metadata = DeviceMetadata {
                 device_type: DeviceType::SmartPlug,
                 capacity_watts: 2000,
-                location: "Home".into(),
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
                 manufacturer: "Tesla".into(),
                 model: "Model S".into(),
                 firmware_version: "1.0.0".into(),
                 installation_date: 1640995200,
             };
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device(metadata).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.set_manufacturer_key("Tesla".into(), Some([1u8; 32])).is_ok());
+
+            // A plain-account key needs exactly a 64-byte sr25519 signature
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            for signature in [vec![], vec![0u8; 63], vec![0u8; 65]] {
+                assert_eq!(
+                    registry.submit_firmware_attestation("1.2.0".into(), [7u8; 32], None, 0, signature),
+                    Err("Invalid attestation signature".into())
+                );
+            }
+            assert_eq!(
+                registry.submit_firmware_attestation("1.2.0".into(), [7u8; 32], None, 1, vec![0u8; 64]),
+                Err("Invalid nonce".into())
+            );
+            assert_eq!(registry.get_attestation_nonce(accounts.bob), 0);
+            assert!(!verify_signature(accounts.bob, [0u8; 32], &[0u8; 64]));
+            assert_eq!(powergrid_shared::signatures::VALID_SIGNATURE_MAGIC, ink::selector_bytes!("is_valid_signature"));
+        }
 
-            let _ = registry.register_device(metadata);
 
-            // Deactivate as owner
-            let result = registry.deactivate_device(accounts.alice, "Test deactivation".into());
-            assert!(result.is_ok());
+        #[ink::test]
+        fn test_payout_address_change_delay() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::Battery,
+                capacity_watts: 5000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Powerwall".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device(metadata).is_ok());
+            assert_eq!(registry.get_payout_address(accounts.bob), accounts.bob);
+
+            // The first payout address applies at once
+            set_block_timestamp::<DefaultEnvironment>(1_000);
+            assert_eq!(registry.set_payout_address(Some(accounts.charlie)), Ok(1_000));
+            assert_eq!(registry.get_payout_address(accounts.bob), accounts.charlie);
+
+            // Changing it waits out the delay and can be cancelled meanwhile
+            let effective_at = registry.set_payout_address(Some(accounts.eve)).unwrap();
+            assert_eq!(effective_at, 1_000 + PAYOUT_ADDRESS_DELAY_MS);
+            assert_eq!(registry.get_payout_address(accounts.bob), accounts.charlie);
+            assert!(registry.cancel_payout_address_change().is_ok());
+            assert!(registry.cancel_payout_address_change().is_err());
+
+            let effective_at = registry.set_payout_address(None).unwrap();
+            set_block_timestamp::<DefaultEnvironment>(effective_at);
+            assert_eq!(registry.get_payout_address(accounts.bob), accounts.bob);
+
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(registry.set_payout_address(Some(accounts.eve)).is_err());
+        }
 
-            let device = registry.get_device(accounts.alice).unwrap();
-            assert!(!device.active);
-            assert_eq!(registry.is_device_active(accounts.alice), Some(false));
+
+        #[ink::test]
+        fn test_availability_calendar() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            let window = |start, end| UnavailableWindow { start, end };
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::Battery,
+                capacity_watts: 3000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Powerwall".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.set_unavailable_windows(vec![window(10, 20)]), Err("Device not registered".into()));
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device(metadata).is_ok());
+
+            assert_eq!(registry.set_unavailable_windows(vec![window(20, 20)]), Err("Invalid window".into()));
+            assert_eq!(
+                registry.set_unavailable_windows(vec![window(50, 80), window(10, 60)]),
+                Err("Overlapping windows".into())
+            );
+            assert_eq!(
+                registry.set_unavailable_windows(vec![window(0, 1); MAX_UNAVAILABLE_WINDOWS + 1]),
+                Err("Too many unavailable windows".into())
+            );
+
+            // Stored sorted, with windows already over dropped
+            set_block_timestamp::<DefaultEnvironment>(100);
+            assert!(registry.set_unavailable_windows(vec![window(400, 500), window(50, 100), window(150, 250)]).is_ok());
+            assert_eq!(registry.get_unavailable_windows(accounts.bob), vec![window(150, 250), window(400, 500)]);
+            assert_eq!(registry.get_unavailable_time(accounts.bob, 200, 450), 100);
+            assert_eq!(registry.get_unavailable_time(accounts.bob, 250, 400), 0);
+            assert_eq!(registry.get_unavailable_time(accounts.charlie, 0, 1_000), 0);
         }
 
+
         #[ink::test]
-        fn test_stake_increase() {
+        fn test_token_address_timelock() {
             let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
             let mut registry = ResourceRegistry::new(1000);
+            assert!(registry.set_token_address(accounts.django).is_ok());
+            assert_eq!(registry.set_token_address(accounts.eve), Err("Token address is timelocked".into()));
+            assert_eq!(registry.set_referral_program(accounts.eve, 100, 2), Err("Token address is timelocked".into()));
+            assert!(registry.set_referral_program(accounts.django, 100, 2).is_ok());
+
+            let executable_at = registry.propose_address_change(ExternalContract::Token, accounts.eve).unwrap();
+            assert_eq!(registry.execute_address_change(ExternalContract::Token), Err("Address change timelock active".into()));
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(registry.cancel_address_change(ExternalContract::Token).is_err());
+            set_block_timestamp::<DefaultEnvironment>(executable_at);
+            assert!(registry.execute_address_change(ExternalContract::Token).is_ok());
+            assert_eq!(
+                registry.get_address_book(),
+                vec![(ExternalContract::Token, accounts.eve), (ExternalContract::Governance, accounts.alice)]
+            );
+        }
 
-            // Register device first
-            set_caller::<DefaultEnvironment>(accounts.alice);
-            set_value_transferred::<DefaultEnvironment>(1500);
 
+        #[ink::test]
+        fn test_graduated_penalties() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut registry = ResourceRegistry::new(1000);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(2000));
             let metadata = DeviceMetadata {
                 device_type: DeviceType::SmartPlug,
                 capacity_watts: 2000,
-                location: "Home".into(),
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
                 manufacturer: "Tesla".into(),
                 model: "Model S".into(),
                 firmware_version: "1.0.0".into(),
                 installation_date: 1640995200,
             };
+            assert!(registry.register_device(metadata).is_ok());
+            let stake = |registry: &ResourceRegistry| registry.get_device(accounts.bob).unwrap().stake;
 
-            let _ = registry.register_device(metadata);
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            // First failure is a warning: no failed event, no reputation change
+            assert!(registry.record_event_performance(accounts.bob, 1, 0, false, [0; 32]).is_ok());
+            assert_eq!(registry.get_device_event_stats(accounts.bob), Some((0, 0)));
+            assert_eq!(registry.get_device_reputation(accounts.bob), Some(100));
+
+            // Then reputation hits
+            assert!(registry.record_event_performance(accounts.bob, 2, 0, false, [0; 32]).is_ok());
+            assert!(registry.record_event_performance(accounts.bob, 3, 0, false, [0; 32]).is_ok());
+            assert_eq!(registry.get_device_event_stats(accounts.bob), Some((0, 2)));
+            assert_eq!(stake(&registry), tokens_to_native(2000));
+
+            // A success forgives one strike, so the next failure is still a reputation hit
+            assert!(registry.record_event_performance(accounts.bob, 4, 100, true, [0; 32]).is_ok());
+            assert_eq!(registry.get_penalty_strikes(accounts.bob), 2);
+            assert!(registry.record_event_performance(accounts.bob, 5, 0, false, [0; 32]).is_ok());
+            assert_eq!(stake(&registry), tokens_to_native(2000));
+
+            // Partial slashes of 10%, then the whole stake
+            assert!(registry.record_event_performance(accounts.bob, 6, 0, false, [0; 32]).is_ok());
+            assert_eq!(stake(&registry), tokens_to_native(1800));
+            assert!(registry.record_event_performance(accounts.bob, 7, 0, false, [0; 32]).is_ok());
+            assert_eq!(stake(&registry), tokens_to_native(1620));
+            assert!(registry.record_event_performance(accounts.bob, 8, 0, false, [0; 32]).is_ok());
+            assert_eq!(stake(&registry), 0);
+            assert_eq!(registry.get_penalty_strikes(accounts.bob), 6);
+
+            let policy = PenaltyPolicy { warning_strikes: 4, ..Default::default() };
+            assert_eq!(registry.set_penalty_policy(policy), Err("Invalid penalty policy".into()));
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.set_penalty_policy(PenaltyPolicy::default()), Err("Unauthorized".into()));
+        }
 
-            // Increase stake
-            set_value_transferred::<DefaultEnvironment>(500);
-            let result = registry.increase_stake();
-            assert!(result.is_ok());
 
-            assert_eq!(registry.get_device_stake(accounts.alice), Some(2000));
+        #[ink::test]
+        fn test_search_devices() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut registry = ResourceRegistry::new(1000);
+            registry.add_authorized_caller(accounts.alice).unwrap();
+
+            let register = |registry: &mut ResourceRegistry, account, device_type, zone: &str| {
+                set_caller::<DefaultEnvironment>(account);
+                set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+                registry.register_device(DeviceMetadata {
+                    device_type,
+                    capacity_watts: 2000,
+                    location_commitment: [0u8; 32],
+                    zone: zone.into(),
+                    manufacturer: "Tesla".into(),
+                    model: "Powerwall".into(),
+                    firmware_version: "1.0.0".into(),
+                    installation_date: 1640995200,
+                }).unwrap();
+            };
+            register(&mut registry, accounts.alice, DeviceType::Battery, "u4pru");
+            register(&mut registry, accounts.bob, DeviceType::Battery, "u4ps");
+            register(&mut registry, accounts.charlie, DeviceType::SmartPlug, "u4pr1");
+
+            // Two strikes drop bob's reputation into the lowest decile
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            registry.update_device_performance(accounts.bob, 0, false).unwrap();
+            registry.update_device_performance(accounts.bob, 0, false).unwrap();
+            assert_eq!(registry.get_device_reputation(accounts.bob), Some(1));
+            registry.deactivate_device(accounts.charlie, "Maintenance".into()).unwrap();
+
+            let filter = |device_type, zone: Option<&str>, min_reputation, active_only| DeviceSearchFilter {
+                device_type,
+                zone: zone.map(Into::into),
+                min_reputation,
+                active_only,
+            };
+            assert_eq!(
+                registry.search_devices(filter(Some(DeviceType::Battery), Some("u4pr"), 80, true), 0, 10),
+                vec![accounts.alice]
+            );
+            assert_eq!(
+                registry.search_devices(filter(Some(DeviceType::Battery), None, 0, false), 0, 10),
+                vec![accounts.alice, accounts.bob]
+            );
+            assert_eq!(
+                registry.search_devices(filter(Some(DeviceType::Battery), None, 0, false), 1, 1),
+                vec![accounts.bob]
+            );
+            assert_eq!(registry.search_devices(filter(None, Some("u4pr"), 0, true), 0, 10), vec![accounts.alice]);
+            assert_eq!(
+                registry.search_devices(filter(None, Some("u4pr"), 0, false), 0, 10),
+                vec![accounts.alice, accounts.charlie]
+            );
+
+            // Changing the device type moves the device to its new bucket
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let mut metadata = registry.get_device(accounts.bob).unwrap().metadata;
+            metadata.device_type = DeviceType::SmartPlug;
+            registry.update_device_metadata(metadata).unwrap();
+            assert_eq!(
+                registry.search_devices(filter(Some(DeviceType::Battery), None, 0, false), 0, 10),
+                vec![accounts.alice]
+            );
+            assert_eq!(
+                registry.search_devices(filter(Some(DeviceType::SmartPlug), None, 0, false), 0, 10),
+                vec![accounts.charlie, accounts.bob]
+            );
         }
 
+
         #[ink::test]
-        fn test_authorized_caller_management() {
+        fn test_stake_and_referral_paths_follow_checks_effects_interactions() {
+            use powergrid_shared::mock::{MockToken, MockTokenState};
+            use ink::storage::traits::StorageKey;
+            use std::{cell::RefCell, rc::Rc};
             let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
             let mut registry = ResourceRegistry::new(1000);
+            let metadata = DeviceMetadata {
+                device_type: DeviceType::SmartPlug,
+                capacity_watts: 2000,
+                location_commitment: [0u8; 32],
+                zone: "u4pru".into(),
+                manufacturer: "Tesla".into(),
+                model: "Model S".into(),
+                firmware_version: "1.0.0".into(),
+                installation_date: 1640995200,
+            };
 
-            // Add authorized caller
-            let result = registry.add_authorized_caller(accounts.bob);
-            assert!(result.is_ok());
-            assert!(registry.is_authorized_caller(accounts.bob));
-
-            // Remove authorized caller
-            let result = registry.remove_authorized_caller(accounts.bob);
-            assert!(result.is_ok());
-            assert!(!registry.is_authorized_caller(accounts.bob));
+            // Rejected calls must not leave the reentrancy guard raised
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.withdraw_stake(10), Err("Device not registered".into()));
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(10));
+            assert_eq!(registry.increase_stake(), Err("Device not registered".into()));
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device(metadata.clone()).is_ok());
+            assert_eq!(registry.withdraw_stake(tokens_to_native(5000)), Err("AmountExceedsStake".into()));
+            assert_eq!(registry.withdraw_stake(tokens_to_native(1000)), Err("BelowMinStake".into()));
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(10));
+            assert!(registry.increase_stake().is_ok());
+
+            // The referral is marked rewarded before the bonus transfers run
+            let registry_account = ink::env::account_id::<DefaultEnvironment>();
+            let mut token = MockTokenState::default();
+            token.balances.insert(registry_account, 1_000);
+            MockToken::install(accounts.django, token);
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.set_referral_program(accounts.django, 100, 1).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            set_value_transferred::<DefaultEnvironment>(tokens_to_native(1500));
+            assert!(registry.register_device_with_referrer(metadata, accounts.bob).is_ok());
+
+            let key = registry.referrals.key();
+            let charlie = accounts.charlie;
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let observed = Rc::clone(&seen);
+            MockToken::on_call(accounts.django, move |call| {
+                if call == "transfer" {
+                    let referral: Option<Referral> = ink::env::get_contract_storage(&(&key, charlie)).unwrap();
+                    observed.borrow_mut().push(referral.is_some_and(|referral| referral.rewarded));
+                }
+            });
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(registry.update_device_performance(accounts.charlie, 100, true).is_ok());
+            assert_eq!(*seen.borrow(), vec![true, true]);
+            assert!(registry.get_referral(accounts.charlie).unwrap().rewarded);
+            assert_eq!(MockToken::state(accounts.django).balance(accounts.bob), 100);
         }
     }
 }
\ No newline at end of file
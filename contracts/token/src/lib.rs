@@ -26,6 +26,7 @@
 #[ink::contract]
 pub mod powergrid_token {
     use ink::prelude::{string::String, vec::Vec};
+    use powergrid_shared::{signed_message_hash, verify_signature, ContractVersion, CorrelationId, EmissionBucket, EmissionSchedule, NonceDomain, NonceRegistry, WiringReport, HEALTH_SNAPSHOT_INTERVAL_BLOCKS};
 
     #[ink(storage)]
     pub struct PowergridToken {
@@ -39,21 +40,188 @@ pub mod powergrid_token {
         paused: bool,
         minters: ink::storage::Mapping<AccountId, ()>,
         entered: bool,
+        /// Protocol guardian: may pause transfers/mints, nothing else
+        guardian: Option<AccountId>,
+        /// Contracts allowed to lock balances (e.g. governance while votes are open)
+        lockers: ink::storage::Mapping<AccountId, ()>,
+        /// Active locks per account: (amount, locked until block number)
+        #[allow(clippy::type_complexity)]
+        locks: ink::storage::Mapping<AccountId, Vec<(Balance, u32)>>,
+        /// Optional expiry and spending cap per (owner, spender) allowance
+        allowance_limits: ink::storage::Mapping<(AccountId, AccountId), AllowanceLimits>,
+        /// Latest snapshot id (0 = none taken)
+        current_snapshot_id: u32,
+        /// Balances as of each snapshot, recorded lazily before the first change after it:
+        /// ascending (snapshot_id, balance) per account
+        #[allow(clippy::type_complexity)]
+        balance_snapshots: ink::storage::Mapping<AccountId, Vec<(u32, Balance)>>,
+        total_supply_snapshots: Vec<(u32, Balance)>,
+        /// Permit nonces per owner
+        nonces: NonceRegistry,
+        /// Role that may mint against the emergency budget without a proposal
+        emergency_minter: Option<AccountId>,
+        /// Most the emergency minter may mint per `EMERGENCY_PERIOD_MS`
+        emergency_cap: Balance,
+        /// Emergency mints per quarter index
+        emergency_minted: ink::storage::Mapping<u64, Balance>,
+        /// Block of the last health snapshot
+        last_health_snapshot: Option<u32>,
+        /// Total supply at the last health snapshot
+        last_snapshot_supply: Balance,
+        /// Inflation cap enforced on `mint` (None = unlimited)
+        emission_schedule: Option<EmissionSchedule>,
+        /// Bucket each minter mints for (default: participation rewards)
+        minter_buckets: ink::storage::Mapping<AccountId, EmissionBucket>,
+        /// Emission epoch of the latest mint
+        emission_epoch: Option<EmissionEpoch>,
     }
 
-    /// PSP22 error
-    #[derive(Debug, PartialEq, Eq)]
+    /// Length of an allowance spending-cap period (one day)
+    pub const ALLOWANCE_PERIOD_MS: u64 = 24 * 60 * 60 * 1000;
+
+    /// Maximum simultaneous locks on one account
+    pub const MAX_LOCKS_PER_ACCOUNT: usize = 32;
+
+    /// Length of an emergency mint budget period (one quarter, 91 days)
+    pub const EMERGENCY_PERIOD_MS: u64 = 91 * ALLOWANCE_PERIOD_MS;
+
+    /// Length of an emission budget epoch (one week)
+    pub const EMISSION_EPOCH_MS: u64 = 7 * ALLOWANCE_PERIOD_MS;
+
+    /// Length of the year the annual inflation cap refers to
+    pub const EMISSION_YEAR_MS: u64 = 365 * ALLOWANCE_PERIOD_MS;
+
+    /// Supply the epoch's budgets derive from and what each bucket minted in it
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct EmissionEpoch {
+        pub epoch: u64,
+        /// Total supply when the epoch's first mint happened
+        pub base_supply: Balance,
+        /// Minted per bucket, indexed by `EmissionBucket::index`
+        pub minted: [Balance; 3],
+    }
+
+    /// Budget usage of one emission bucket in the current epoch
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct BucketEmission {
+        pub bucket: EmissionBucket,
+        pub budget: Balance,
+        pub minted: Balance,
+        pub remaining: Balance,
+    }
+
+    /// Emission schedule and the current epoch's budgets
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct EmissionStatus {
+        pub schedule: Option<EmissionSchedule>,
+        pub epoch: u64,
+        pub base_supply: Balance,
+        /// Empty without a schedule
+        pub buckets: Vec<BucketEmission>,
+    }
+
+    /// Restrictions set with `approve_with_limits`
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[allow(clippy::cast_possible_truncation)]
-    pub enum PSP22Error {
-        Custom(String),
-        InsufficientBalance,
-        InsufficientAllowance,
-        ZeroRecipientAddress,
-        ZeroSenderAddress,
-        SafeTransferCheckFailed(String),
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AllowanceLimits {
+        /// Allowance is void from this timestamp on
+        pub expiry: Option<u64>,
+        /// Most the spender may move per `ALLOWANCE_PERIOD_MS`
+        pub period_cap: Option<Balance>,
+        pub period: u64,
+        pub spent_in_period: Balance,
+    }
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
+    }
+
+    #[ink(event)]
+    pub struct SnapshotCreated {
+        #[ink(topic)]
+        pub snapshot_id: u32,
+        pub block: u32,
+    }
+
+    #[ink(event)]
+    pub struct BalanceLocked {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+        pub until_block: u32,
+    }
+
+    #[ink(event)]
+    pub struct EmergencyBudgetSet {
+        pub minter: Option<AccountId>,
+        pub cap_per_quarter: Balance,
+    }
+
+    #[ink(event)]
+    pub struct EmergencyMint {
+        #[ink(topic)]
+        pub minter: AccountId,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount: Balance,
+        pub quarter: u64,
+        /// Budget left this quarter after the mint
+        pub remaining: Balance,
+        pub reason: String,
+    }
+
+    /// Periodic summary for off-chain monitoring
+    #[ink(event)]
+    pub struct HealthSnapshot {
+        pub block: u32,
+        pub total_supply: Balance,
+        /// Change of the total supply since the previous snapshot
+        pub supply_delta: i128,
+        pub paused: bool,
     }
 
+    #[ink(event)]
+    pub struct Minted {
+        #[ink(topic)]
+        pub minter: AccountId,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount: Balance,
+        /// Flow the mint belongs to, e.g. a grid event's reward settlement
+        #[ink(topic)]
+        pub correlation_id: Option<CorrelationId>,
+    }
+
+    #[ink(event)]
+    pub struct EmissionScheduleSet {
+        pub schedule: Option<EmissionSchedule>,
+    }
+
+    #[ink(event)]
+    pub struct GuardianUpdated {
+        pub old_guardian: Option<AccountId>,
+        pub new_guardian: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct PauseChanged {
+        #[ink(topic)]
+        pub by: AccountId,
+        pub paused: bool,
+    }
+
+    pub use powergrid_shared::PSP22Error;
+
     pub type Result<T> = core::result::Result<T, PSP22Error>;
 
     impl PowergridToken {
@@ -71,9 +239,26 @@ pub mod powergrid_token {
                 paused: false,
                 minters: ink::storage::Mapping::default(),
                 entered: false,
+                guardian: None,
+                lockers: ink::storage::Mapping::default(),
+                locks: ink::storage::Mapping::default(),
+                allowance_limits: ink::storage::Mapping::default(),
+                current_snapshot_id: 0,
+                balance_snapshots: ink::storage::Mapping::default(),
+                total_supply_snapshots: Vec::new(),
+                nonces: NonceRegistry::default(),
+                emergency_minter: None,
+                emergency_cap: 0,
+                emergency_minted: ink::storage::Mapping::default(),
+                last_health_snapshot: None,
+                last_snapshot_supply: initial_supply,
+                emission_schedule: None,
+                minter_buckets: ink::storage::Mapping::default(),
+                emission_epoch: None,
             };
             instance.balances.insert(caller, &initial_supply);
             instance.minters.insert(caller, &());
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
             instance
         }
 
@@ -104,6 +289,10 @@ pub mod powergrid_token {
 
         #[ink(message)]
         pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            let expired = self.allowance_limits.get((owner, spender))
+                .and_then(|limits| limits.expiry)
+                .is_some_and(|expiry| self.env().block_timestamp() >= expiry);
+            if expired { return 0; }
             self.allowances.get((owner, spender)).unwrap_or(0)
         }
 
@@ -128,6 +317,10 @@ pub mod powergrid_token {
                     self.reset_entered();
                     return Err(PSP22Error::InsufficientAllowance);
                 }
+                if let Err(e) = self.spend_within_cap(from, caller, value) {
+                    self.reset_entered();
+                    return Err(e);
+                }
                 self.allowances.insert((from, caller), &allowance.saturating_sub(value));
             }
             
@@ -140,6 +333,96 @@ pub mod powergrid_token {
         pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
             let owner = self.env().caller();
             self.allowances.insert((owner, spender), &value);
+            self.allowance_limits.remove((owner, spender));
+            Ok(())
+        }
+
+        /// Approve `spender` for `value`, void from `expiry` (timestamp) and limited to
+        /// `period_cap` per `ALLOWANCE_PERIOD_MS` when given. A plain `approve` lifts the limits.
+        #[ink(message)]
+        pub fn approve_with_limits(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+            expiry: Option<u64>,
+            period_cap: Option<Balance>,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            if expiry.is_some_and(|expiry| expiry <= self.env().block_timestamp()) {
+                return Err(PSP22Error::Custom(String::from("ExpiryInPast")));
+            }
+            self.allowances.insert((owner, spender), &value);
+            self.allowance_limits.insert((owner, spender), &AllowanceLimits {
+                expiry,
+                period_cap,
+                period: self.env().block_timestamp() / ALLOWANCE_PERIOD_MS,
+                spent_in_period: 0,
+            });
+            Ok(())
+        }
+
+        /// Limits attached to an allowance, if any
+        #[ink(message)]
+        pub fn get_allowance_limits(&self, owner: AccountId, spender: AccountId) -> Option<AllowanceLimits> {
+            self.allowance_limits.get((owner, spender))
+        }
+
+        /// Set an allowance from the owner's off-chain signature over `permit_hash`; anyone may
+        /// relay it. `deadline` is the timestamp from which the permit is void.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            nonce: u64,
+            signature: Vec<u8>,
+        ) -> Result<()> {
+            if self.env().block_timestamp() >= deadline {
+                return Err(PSP22Error::Custom(String::from("PermitExpired")));
+            }
+            if nonce != self.nonces.current(owner, NonceDomain::Permit) {
+                return Err(PSP22Error::Custom(String::from("InvalidNonce")));
+            }
+            let message = self.permit_hash(owner, spender, value, deadline, nonce);
+            if !verify_signature(owner, message, &signature) {
+                return Err(PSP22Error::Custom(String::from("InvalidSignature")));
+            }
+            self.nonces.use_nonce(owner, NonceDomain::Permit, nonce);
+            self.allowances.insert((owner, spender), &value);
+            self.allowance_limits.remove((owner, spender));
+            Ok(())
+        }
+
+        /// Next permit nonce the owner must sign
+        #[ink(message)]
+        pub fn permit_nonce(&self, owner: AccountId) -> u64 {
+            self.nonces.current(owner, NonceDomain::Permit)
+        }
+
+        /// Message an owner signs for `permit`:
+        /// `signed_message_hash(Permit, token_address, nonce, (owner, spender, value, deadline))`
+        #[ink(message)]
+        pub fn permit_hash(&self, owner: AccountId, spender: AccountId, value: Balance, deadline: u64, nonce: u64) -> [u8; 32] {
+            signed_message_hash(NonceDomain::Permit, self.env().account_id(), nonce, &(owner, spender, value, deadline))
+        }
+
+        /// Count `value` against the allowance's spending cap for the current period
+        fn spend_within_cap(&mut self, owner: AccountId, spender: AccountId, value: Balance) -> Result<()> {
+            let Some(mut limits) = self.allowance_limits.get((owner, spender)) else { return Ok(()) };
+            let Some(cap) = limits.period_cap else { return Ok(()) };
+            let period = self.env().block_timestamp() / ALLOWANCE_PERIOD_MS;
+            if period != limits.period {
+                limits.period = period;
+                limits.spent_in_period = 0;
+            }
+            let spent = limits.spent_in_period.saturating_add(value);
+            if spent > cap {
+                return Err(PSP22Error::Custom(String::from("PeriodCapExceeded")));
+            }
+            limits.spent_in_period = spent;
+            self.allowance_limits.insert((owner, spender), &limits);
             Ok(())
         }
 
@@ -153,7 +436,12 @@ pub mod powergrid_token {
             if from_balance < value {
                 return Err(PSP22Error::InsufficientBalance);
             }
+            if from_balance.saturating_sub(value) < self.locked_balance_of(*from) {
+                return Err(PSP22Error::Custom(String::from("BalanceLocked")));
+            }
             
+            self.record_snapshot(*from);
+            self.record_snapshot(*to);
             self.balances.insert(*from, &from_balance.saturating_sub(value));
             let to_balance = self.balance_of(*to);
             self.balances.insert(*to, &to_balance.saturating_add(value));
@@ -176,22 +464,195 @@ pub mod powergrid_token {
             Ok(())
         }
 
+        /// Governance helpers: add/remove locker role
+        #[ink(message)]
+        pub fn add_locker(&mut self, account: AccountId) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            self.lockers.insert(account, &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn remove_locker(&mut self, account: AccountId) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            self.lockers.remove(account);
+            Ok(())
+        }
+
+        /// Lock `amount` of an account's balance until `until_block` (LOCKER role only).
+        /// Locks are kept separately and overlap: the locked balance is the largest
+        /// amount among the locks that have not expired yet.
+        #[ink(message)]
+        pub fn lock(&mut self, account: AccountId, amount: Balance, until_block: u32) -> Result<()> {
+            if !self.lockers.contains(Self::env().caller()) {
+                return Err(PSP22Error::Custom(String::from("NotLocker")));
+            }
+            let now = self.env().block_number();
+            let mut locks = self.locks.get(account).unwrap_or_default();
+            // Expired locks and locks the new one fully covers no longer matter
+            locks.retain(|&(locked, until)| until > now && (locked > amount || until > until_block));
+            if locks.iter().all(|&(locked, until)| locked < amount || until < until_block) {
+                if locks.len() >= MAX_LOCKS_PER_ACCOUNT {
+                    return Err(PSP22Error::Custom(String::from("TooManyLocks")));
+                }
+                locks.push((amount, until_block));
+            }
+            self.locks.insert(account, &locks);
+            self.env().emit_event(BalanceLocked { account, amount, until_block });
+            Ok(())
+        }
+
+        /// Balance currently locked: the largest amount among the unexpired locks
+        #[ink(message)]
+        pub fn locked_balance_of(&self, account: AccountId) -> Balance {
+            let now = self.env().block_number();
+            self.locks
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|&(_, until)| until > now)
+                .map(|(amount, _)| amount)
+                .max()
+                .unwrap_or(0)
+        }
+
+        /// Unexpired locks of an account as (amount, until block)
+        #[ink(message)]
+        pub fn get_locks(&self, account: AccountId) -> Vec<(Balance, u32)> {
+            let now = self.env().block_number();
+            let mut locks = self.locks.get(account).unwrap_or_default();
+            locks.retain(|&(_, until)| until > now);
+            locks
+        }
+
         #[ink(message)]
         pub fn is_minter(&self, account: AccountId) -> bool {
             self.minters.contains(account)
         }
 
-        /// Emergency pause/unpause (admin only)
+        /// Emergency pause/unpause (admin only); the guardian may only pause
         #[ink(message)]
         pub fn set_paused(&mut self, pause: bool) -> Result<()> {
-            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            let caller = Self::env().caller();
+            if caller != self.admin && !(pause && Some(caller) == self.guardian) {
+                return Err(PSP22Error::Custom(String::from("NotAdmin")));
+            }
             self.paused = pause;
+            self.env().emit_event(PauseChanged { by: caller, paused: pause });
+            Ok(())
+        }
+
+        /// Rotate the protocol guardian (admin, i.e. governance, only)
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            let old_guardian = self.guardian;
+            self.guardian = guardian;
+            self.env().emit_event(GuardianUpdated { old_guardian, new_guardian: guardian });
             Ok(())
         }
 
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<AccountId> {
+            self.guardian
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message)]
+        pub fn is_locker(&self, account: AccountId) -> bool {
+            self.lockers.contains(account)
+        }
+
+        /// Logic version of this contract, bumped on every upgrade
+        #[ink(message)]
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Readiness report; the token has no outbound dependencies
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            report.check("not_paused", !self.paused);
+            report
+        }
+
+        /// Take a balance snapshot for airdrops and distributions (admin, i.e. governance, only)
+        #[ink(message)]
+        pub fn create_snapshot(&mut self) -> Result<u32> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            self.current_snapshot_id = self.current_snapshot_id.saturating_add(1);
+            let snapshot_id = self.current_snapshot_id;
+            self.env().emit_event(SnapshotCreated { snapshot_id, block: self.env().block_number() });
+            Ok(snapshot_id)
+        }
+
+        /// Latest snapshot id (0 = none taken)
+        #[ink(message)]
+        pub fn current_snapshot_id(&self) -> u32 {
+            self.current_snapshot_id
+        }
+
+        /// Balance of `account` when snapshot `snapshot_id` was taken
+        #[ink(message)]
+        pub fn balance_of_at_snapshot(&self, account: AccountId, snapshot_id: u32) -> Result<Balance> {
+            self.ensure_snapshot_exists(snapshot_id)?;
+            let recorded = self.balance_snapshots.get(account).unwrap_or_default();
+            Ok(Self::value_at(&recorded, snapshot_id).unwrap_or_else(|| self.balance_of(account)))
+        }
+
+        /// Total supply when snapshot `snapshot_id` was taken
+        #[ink(message)]
+        pub fn total_supply_at_snapshot(&self, snapshot_id: u32) -> Result<Balance> {
+            self.ensure_snapshot_exists(snapshot_id)?;
+            Ok(Self::value_at(&self.total_supply_snapshots, snapshot_id).unwrap_or(self.total_supply))
+        }
+
+        fn ensure_snapshot_exists(&self, snapshot_id: u32) -> Result<()> {
+            if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+                return Err(PSP22Error::Custom(String::from("UnknownSnapshot")));
+            }
+            Ok(())
+        }
+
+        /// The first value recorded at or after `snapshot_id` is the one that held at it;
+        /// with none, the value has not changed since and the live value applies
+        fn value_at(recorded: &[(u32, Balance)], snapshot_id: u32) -> Option<Balance> {
+            recorded.iter().find(|(id, _)| *id >= snapshot_id).map(|(_, value)| *value)
+        }
+
+        /// Record the pre-change balance the first time an account changes after a snapshot
+        fn record_snapshot(&mut self, account: AccountId) {
+            if self.current_snapshot_id == 0 { return; }
+            let mut recorded = self.balance_snapshots.get(account).unwrap_or_default();
+            if recorded.last().is_some_and(|(id, _)| *id == self.current_snapshot_id) { return; }
+            recorded.push((self.current_snapshot_id, self.balance_of(account)));
+            self.balance_snapshots.insert(account, &recorded);
+        }
+
+        fn record_total_supply_snapshot(&mut self) {
+            if self.current_snapshot_id == 0 { return; }
+            if self.total_supply_snapshots.last().is_some_and(|(id, _)| *id == self.current_snapshot_id) { return; }
+            self.total_supply_snapshots.push((self.current_snapshot_id, self.total_supply));
+        }
+
         /// Restricted mint (MINTER role only)
         #[ink(message)]
         pub fn mint(&mut self, account: AccountId, amount: Balance) -> Result<()> {
+            self.mint_checked(account, amount, None)
+        }
+
+        /// `mint` tagged with the correlation ID of the flow it belongs to
+        #[ink(message)]
+        pub fn mint_correlated(&mut self, account: AccountId, amount: Balance, correlation_id: CorrelationId) -> Result<()> {
+            self.mint_checked(account, amount, Some(correlation_id))
+        }
+
+        fn mint_checked(&mut self, account: AccountId, amount: Balance, correlation_id: Option<CorrelationId>) -> Result<()> {
             self.ensure_not_entered()?;
             if !self.minters.contains(Self::env().caller()) { 
                 self.reset_entered();
@@ -201,11 +662,154 @@ pub mod powergrid_token {
                 self.reset_entered();
                 return Err(PSP22Error::Custom(String::from("Paused"))); 
             }
+            if let Some(schedule) = &self.emission_schedule {
+                let bucket = self.get_minter_emission_bucket(Self::env().caller());
+                let mut epoch = self.current_emission_epoch();
+                let minted = epoch.minted[bucket.index()].saturating_add(amount);
+                if minted > Self::bucket_budget(schedule, epoch.base_supply, bucket) {
+                    self.reset_entered();
+                    return Err(PSP22Error::Custom(String::from("EmissionCapExceeded")));
+                }
+                epoch.minted[bucket.index()] = minted;
+                self.emission_epoch = Some(epoch);
+            }
             
+            self.mint_to(account, amount);
+            self.env().emit_event(Minted { minter: Self::env().caller(), to: account, amount, correlation_id });
+            self.reset_entered();
+            Ok(())
+        }
+
+        /// Set (or lift with None) the annual inflation cap enforced on `mint` (admin, i.e. governance, only)
+        #[ink(message)]
+        pub fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            if schedule.as_ref().is_some_and(|schedule| !schedule.is_valid()) {
+                return Err(PSP22Error::Custom(String::from("InvalidEmissionSchedule")));
+            }
+            self.emission_schedule = schedule.clone();
+            self.env().emit_event(EmissionScheduleSet { schedule });
+            Ok(())
+        }
+
+        /// Assign a minter to the bucket its mints count against (admin only)
+        #[ink(message)]
+        pub fn set_minter_emission_bucket(&mut self, minter: AccountId, bucket: EmissionBucket) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            self.minter_buckets.insert(minter, &bucket);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_minter_emission_bucket(&self, minter: AccountId) -> EmissionBucket {
+            self.minter_buckets.get(minter).unwrap_or_default()
+        }
+
+        /// Budget, minted amount and remaining budget per bucket in the current epoch
+        #[ink(message)]
+        pub fn get_emission_status(&self) -> EmissionStatus {
+            let epoch = self.current_emission_epoch();
+            let buckets = self.emission_schedule.as_ref().map_or_else(Vec::new, |schedule| {
+                EmissionBucket::ALL
+                    .iter()
+                    .map(|bucket| {
+                        let budget = Self::bucket_budget(schedule, epoch.base_supply, *bucket);
+                        let minted = epoch.minted[bucket.index()];
+                        BucketEmission { bucket: *bucket, budget, minted, remaining: budget.saturating_sub(minted) }
+                    })
+                    .collect()
+            });
+            EmissionStatus {
+                schedule: self.emission_schedule.clone(),
+                epoch: epoch.epoch,
+                base_supply: epoch.base_supply,
+                buckets,
+            }
+        }
+
+        /// Stored emission epoch, or a fresh one based on the current supply once a new epoch began
+        fn current_emission_epoch(&self) -> EmissionEpoch {
+            let epoch = self.env().block_timestamp() / EMISSION_EPOCH_MS;
+            match &self.emission_epoch {
+                Some(stored) if stored.epoch == epoch => stored.clone(),
+                _ => EmissionEpoch { epoch, base_supply: self.total_supply, minted: [0; 3] },
+            }
+        }
+
+        /// A bucket's share of one epoch's worth of the annual inflation cap
+        fn bucket_budget(schedule: &EmissionSchedule, base_supply: Balance, bucket: EmissionBucket) -> Balance {
+            let annual = base_supply.saturating_mul(u128::from(schedule.annual_inflation_bps)) / 10_000;
+            let per_epoch = annual.saturating_mul(u128::from(EMISSION_EPOCH_MS)) / u128::from(EMISSION_YEAR_MS);
+            per_epoch.saturating_mul(u128::from(schedule.bucket_bps(bucket))) / 10_000
+        }
+
+        fn mint_to(&mut self, account: AccountId, amount: Balance) {
+            self.record_snapshot(account);
+            self.record_total_supply_snapshot();
             let current_balance = self.balance_of(account);
             self.balances.insert(account, &current_balance.saturating_add(amount));
             self.total_supply = self.total_supply.saturating_add(amount);
-            self.reset_entered();
+        }
+
+        /// Designate (or revoke with None) the emergency minter and its cap per quarter (admin, i.e. governance, only)
+        #[ink(message)]
+        pub fn set_emergency_budget(&mut self, minter: Option<AccountId>, cap_per_quarter: Balance) -> Result<()> {
+            if Self::env().caller() != self.admin { return Err(PSP22Error::Custom(String::from("NotAdmin"))); }
+            self.emergency_minter = minter;
+            self.emergency_cap = cap_per_quarter;
+            self.env().emit_event(EmergencyBudgetSet { minter, cap_per_quarter });
+            Ok(())
+        }
+
+        /// Mint grid-critical incentives against the pre-approved quarterly budget (emergency minter only);
+        /// every mint is reported with its reason and deducted from the budget
+        #[ink(message)]
+        pub fn emergency_mint(&mut self, to: AccountId, amount: Balance, reason: String) -> Result<()> {
+            let minter = Self::env().caller();
+            if self.emergency_minter != Some(minter) {
+                return Err(PSP22Error::Custom(String::from("NotEmergencyMinter")));
+            }
+            if self.paused {
+                return Err(PSP22Error::Custom(String::from("Paused")));
+            }
+            let quarter = self.env().block_timestamp() / EMERGENCY_PERIOD_MS;
+            let minted = self.emergency_minted.get(quarter).unwrap_or(0).saturating_add(amount);
+            if minted > self.emergency_cap {
+                return Err(PSP22Error::Custom(String::from("EmergencyCapExceeded")));
+            }
+            self.emergency_minted.insert(quarter, &minted);
+            self.mint_to(to, amount);
+            let remaining = self.emergency_cap.saturating_sub(minted);
+            self.env().emit_event(EmergencyMint { minter, to, amount, quarter, remaining, reason });
+            Ok(())
+        }
+
+        /// (emergency minter, cap per quarter, budget left this quarter)
+        #[ink(message)]
+        pub fn get_emergency_budget(&self) -> (Option<AccountId>, Balance, Balance) {
+            let quarter = self.env().block_timestamp() / EMERGENCY_PERIOD_MS;
+            let minted = self.emergency_minted.get(quarter).unwrap_or(0);
+            (self.emergency_minter, self.emergency_cap, self.emergency_cap.saturating_sub(minted))
+        }
+
+        /// Emit a `HealthSnapshot` event (anyone; at most once per `HEALTH_SNAPSHOT_INTERVAL_BLOCKS`)
+        #[ink(message)]
+        pub fn emit_health_snapshot(&mut self) -> Result<()> {
+            let now = self.env().block_number();
+            if self.last_health_snapshot.is_some_and(|last| now < last.saturating_add(HEALTH_SNAPSHOT_INTERVAL_BLOCKS)) {
+                return Err(PSP22Error::Custom(String::from("Health snapshot too soon")));
+            }
+            self.last_health_snapshot = Some(now);
+            let supply_delta = i128::try_from(self.total_supply)
+                .unwrap_or(i128::MAX)
+                .saturating_sub(i128::try_from(self.last_snapshot_supply).unwrap_or(i128::MAX));
+            self.last_snapshot_supply = self.total_supply;
+            self.env().emit_event(HealthSnapshot {
+                block: now,
+                total_supply: self.total_supply,
+                supply_delta,
+                paused: self.paused,
+            });
             Ok(())
         }
 
@@ -224,7 +828,13 @@ pub mod powergrid_token {
                 self.reset_entered();
                 return Err(PSP22Error::InsufficientBalance);
             }
+            if current_balance.saturating_sub(amount) < self.locked_balance_of(caller) {
+                self.reset_entered();
+                return Err(PSP22Error::Custom(String::from("BalanceLocked")));
+            }
             
+            self.record_snapshot(caller);
+            self.record_total_supply_snapshot();
             self.balances.insert(caller, &current_balance.saturating_sub(amount));
             self.total_supply = self.total_supply.saturating_sub(amount);
             self.reset_entered();
@@ -304,5 +914,251 @@ pub mod powergrid_token {
             assert_eq!(token.balance_of(accounts.alice), 800);
             assert_eq!(token.total_supply(), 800);
         }
+
+
+        #[ink::test]
+        fn test_guardian_can_only_pause() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(token.set_paused(true).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.set_guardian(Some(accounts.eve)).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(token.set_guardian(Some(accounts.bob)).is_err());
+            assert!(token.set_paused(true).is_ok());
+            assert!(token.is_paused());
+            assert!(token.set_paused(false).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.transfer(accounts.bob, 10, Vec::new()).is_err());
+            assert!(token.set_paused(false).is_ok());
+            assert!(token.transfer(accounts.bob, 10, Vec::new()).is_ok());
+        }
+
+
+        #[ink::test]
+        fn test_vote_lock_blocks_transfers() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(token.lock(accounts.alice, 800, 5).is_err());
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.add_locker(accounts.django).is_ok());
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(token.lock(accounts.alice, 800, 5).is_ok());
+            assert_eq!(token.locked_balance_of(accounts.alice), 800);
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.transfer(accounts.bob, 200, Vec::new()).is_ok());
+            assert_eq!(
+                token.transfer(accounts.bob, 1, Vec::new()),
+                Err(PSP22Error::Custom("BalanceLocked".into()))
+            );
+            assert!(token.burn(1).is_err());
+
+            // Lock lifts once voting ends
+            for _ in 0..5 {
+                ink::env::test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(token.locked_balance_of(accounts.alice), 0);
+            assert!(token.transfer(accounts.bob, 100, Vec::new()).is_ok());
+        }
+
+        #[ink::test]
+        fn test_allowance_expiry_and_period_cap() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+            let expiry = 3 * ALLOWANCE_PERIOD_MS;
+            assert!(token.approve_with_limits(accounts.bob, 500, Some(expiry), Some(100)).is_ok());
+
+            // At most 100 per day
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.transfer_from(accounts.alice, accounts.charlie, 80, Vec::new()).is_ok());
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.charlie, 30, Vec::new()),
+                Err(PSP22Error::Custom("PeriodCapExceeded".into()))
+            );
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(ALLOWANCE_PERIOD_MS);
+            assert!(token.transfer_from(accounts.alice, accounts.charlie, 100, Vec::new()).is_ok());
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 320);
+
+            // Void once expired
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(expiry);
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.charlie, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+
+            // Plain approve restores an unrestricted PSP22 allowance
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.approve(accounts.bob, 300).is_ok());
+            assert!(token.get_allowance_limits(accounts.alice, accounts.bob).is_none());
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.transfer_from(accounts.alice, accounts.charlie, 300, Vec::new()).is_ok());
+        }
+
+        #[ink::test]
+        fn test_balance_snapshots() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+
+            let first = token.create_snapshot().unwrap();
+            assert!(token.transfer(accounts.bob, 300, Vec::new()).is_ok());
+            let second = token.create_snapshot().unwrap();
+            assert!(token.mint(accounts.bob, 200).is_ok());
+
+            assert_eq!(token.balance_of_at_snapshot(accounts.alice, first), Ok(1000));
+            assert_eq!(token.balance_of_at_snapshot(accounts.bob, first), Ok(0));
+            assert_eq!(token.balance_of_at_snapshot(accounts.alice, second), Ok(700));
+            assert_eq!(token.balance_of_at_snapshot(accounts.bob, second), Ok(300));
+            assert_eq!(token.total_supply_at_snapshot(first), Ok(1000));
+            assert_eq!(token.total_supply_at_snapshot(second), Ok(1000));
+            assert_eq!(token.balance_of(accounts.bob), 500);
+            assert!(token.balance_of_at_snapshot(accounts.bob, 3).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.create_snapshot(), Err(PSP22Error::Custom("NotAdmin".into())));
+        }
+
+
+        #[ink::test]
+        fn test_permit_checks_deadline_nonce_and_signature() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(100);
+
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                token.permit(accounts.alice, accounts.bob, 50, 100, 0, vec![0u8; 64]),
+                Err(PSP22Error::Custom("PermitExpired".into()))
+            );
+            assert_eq!(
+                token.permit(accounts.alice, accounts.bob, 50, 200, 1, vec![0u8; 64]),
+                Err(PSP22Error::Custom("InvalidNonce".into()))
+            );
+            assert_eq!(
+                token.permit(accounts.alice, accounts.bob, 50, 200, 0, vec![0u8; 64]),
+                Err(PSP22Error::Custom("InvalidSignature".into()))
+            );
+            assert_eq!(token.permit_nonce(accounts.alice), 0);
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+
+            // The hash is bound to the nonce and domain
+            assert_ne!(
+                token.permit_hash(accounts.alice, accounts.bob, 50, 200, 0),
+                token.permit_hash(accounts.alice, accounts.bob, 50, 200, 1)
+            );
+            assert_ne!(
+                token.permit_hash(accounts.alice, accounts.bob, 50, 200, 0),
+                signed_message_hash(NonceDomain::Vote, ink::env::account_id::<DefaultEnvironment>(), 0, &(accounts.alice, accounts.bob, 50u128, 200u64))
+            );
+        }
+
+
+        #[ink::test]
+        fn test_emergency_mint_budget() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+            assert!(token.set_emergency_budget(Some(accounts.django), 100).is_ok());
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.emergency_mint(accounts.eve, 10, "heatwave".into()).is_err());
+
+            set_caller::<DefaultEnvironment>(accounts.django);
+            assert!(token.emergency_mint(accounts.eve, 60, "heatwave".into()).is_ok());
+            assert_eq!(token.balance_of(accounts.eve), 60);
+            assert_eq!(token.total_supply(), 1060);
+            assert_eq!(token.get_emergency_budget(), (Some(accounts.django), 100, 40));
+            assert_eq!(
+                token.emergency_mint(accounts.eve, 41, "heatwave".into()),
+                Err(PSP22Error::Custom(String::from("EmergencyCapExceeded")))
+            );
+
+            // The budget resets next quarter
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(EMERGENCY_PERIOD_MS);
+            assert!(token.emergency_mint(accounts.eve, 100, "cold snap".into()).is_ok());
+            assert_eq!(token.get_emergency_budget().2, 0);
+        }
+
+
+        #[ink::test]
+        fn test_emission_schedule_caps_mints_per_bucket() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 365_000_000);
+            let mut schedule = EmissionSchedule { annual_inflation_bps: 1_000, participation_bps: 6_000, staking_bps: 3_000, treasury_bps: 500 };
+            assert_eq!(
+                token.set_emission_schedule(Some(schedule.clone())),
+                Err(PSP22Error::Custom(String::from("InvalidEmissionSchedule")))
+            );
+            schedule.treasury_bps = 1_000;
+            assert!(token.set_emission_schedule(Some(schedule)).is_ok());
+            assert!(token.add_minter(accounts.bob).is_ok());
+            assert!(token.set_minter_emission_bucket(accounts.bob, EmissionBucket::Treasury).is_ok());
+
+            // 10% a year on 365M is 700k per week: 420k participation, 210k staking, 70k treasury
+            assert!(token.mint(accounts.charlie, 420_000).is_ok());
+            assert_eq!(token.mint(accounts.charlie, 1), Err(PSP22Error::Custom(String::from("EmissionCapExceeded"))));
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(token.mint(accounts.bob, 70_001).is_err());
+            assert!(token.mint(accounts.bob, 50_000).is_ok());
+            let status = token.get_emission_status();
+            assert_eq!(status.base_supply, 365_000_000);
+            let remaining: Vec<Balance> = status.buckets.iter().map(|b| b.remaining).collect();
+            assert_eq!(remaining, vec![0, 210_000, 20_000]);
+
+            // A new epoch derives its budgets from the grown supply
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(EMISSION_EPOCH_MS);
+            let status = token.get_emission_status();
+            assert_eq!((status.epoch, status.base_supply), (1, 365_470_000));
+            assert!(token.mint(accounts.bob, 70_000).is_ok());
+        }
+
+
+        #[ink::test]
+        fn test_overlapping_locks_expire_independently() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut token = PowergridToken::new("Test".into(), "TEST".into(), 18, 1000);
+            token.add_locker(accounts.django).unwrap();
+
+            // A large short lock and a small long lock
+            set_caller::<DefaultEnvironment>(accounts.django);
+            token.lock(accounts.alice, 900, 3).unwrap();
+            token.lock(accounts.alice, 100, 10).unwrap();
+            assert_eq!(token.locked_balance_of(accounts.alice), 900);
+            assert_eq!(token.get_locks(accounts.alice), vec![(900, 3), (100, 10)]);
+            // A lock covered by an existing one adds nothing
+            token.lock(accounts.alice, 50, 2).unwrap();
+            assert_eq!(token.get_locks(accounts.alice).len(), 2);
+
+            for _ in 0..3 {
+                ink::env::test::advance_block::<DefaultEnvironment>();
+            }
+            assert_eq!(token.locked_balance_of(accounts.alice), 100);
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(token.transfer(accounts.bob, 900, Vec::new()).is_ok());
+            assert_eq!(
+                token.transfer(accounts.bob, 1, Vec::new()),
+                Err(PSP22Error::Custom("BalanceLocked".into()))
+            );
+
+            // A new lock covering the remaining one replaces it
+            set_caller::<DefaultEnvironment>(accounts.django);
+            token.lock(accounts.alice, 100, 20).unwrap();
+            assert_eq!(token.get_locks(accounts.alice), vec![(100, 20)]);
+        }
     }
 }
\ No newline at end of file
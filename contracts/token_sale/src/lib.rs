@@ -0,0 +1,416 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod token_sale {
+    use ink::storage::Mapping;
+    use powergrid_shared::{ContractVersion, WiringReport};
+    use powergrid_shared::{Connect, TokenClient};
+
+    /// Token client: the deployed contract on chain, an installable mock in unit tests
+    #[cfg(not(test))]
+    type Token = powergrid_shared::TokenRef;
+    #[cfg(test)]
+    type Token = powergrid_shared::mock::MockToken;
+
+    /// PGT base units per whole token; prices are quoted in native units per whole PGT
+    pub const PRICE_UNIT: u128 = 1_000_000_000_000_000_000;
+
+    /// Logic version; bump on every upgrade of this contract
+    pub const CONTRACT_VERSION: ContractVersion = ContractVersion::new(0, 1, 0);
+
+    /// How the price of PGT evolves as the sale progresses
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Pricing {
+        /// Constant price per whole PGT
+        Fixed { price: Balance },
+        /// `base_price + slope * sold / PRICE_UNIT` per whole PGT
+        Linear { base_price: Balance, slope: Balance },
+    }
+
+    impl Pricing {
+        /// Spot price after `sold` base units have been sold
+        pub fn price_at(&self, sold: Balance) -> Option<Balance> {
+            match *self {
+                Pricing::Fixed { price } => Some(price),
+                Pricing::Linear { base_price, slope } => {
+                    base_price.checked_add(slope.checked_mul(sold)? / PRICE_UNIT)
+                }
+            }
+        }
+
+        /// Native cost of buying `amount` base units after `sold`; exact for a linear curve
+        /// since the average price over the purchase is the price at its midpoint. Rounded up,
+        /// so fractions of a whole PGT never cost nothing.
+        pub fn cost(&self, sold: Balance, amount: Balance) -> Option<Balance> {
+            let midpoint = sold.checked_add(amount / 2)?;
+            Some(amount.checked_mul(self.price_at(midpoint)?)?.div_ceil(PRICE_UNIT))
+        }
+    }
+
+    /// Governance-set parameters of the sale
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct SaleConfig {
+        pub pricing: Pricing,
+        /// Sale window, in milliseconds
+        pub start: Timestamp,
+        pub end: Timestamp,
+        /// Total PGT the sale may distribute
+        pub hard_cap: Balance,
+        /// PGT a single account may buy over the whole sale
+        pub per_account_cap: Balance,
+    }
+
+    /// Sells newly minted PGT for native tokens and forwards proceeds to the treasury
+    #[ink(storage)]
+    pub struct TokenSale {
+        /// Simple reentrancy flag
+        entered: bool,
+        owner: AccountId,
+        governance_address: AccountId,
+        /// PGT token; this contract must be one of its minters
+        token_address: AccountId,
+        /// Receives all sale proceeds
+        treasury: AccountId,
+        config: Option<SaleConfig>,
+        paused: bool,
+        sold: Balance,
+        raised: Balance,
+        purchased: Mapping<AccountId, Balance>,
+    }
+
+    /// Emitted once at instantiation with the logic version
+    #[ink(event)]
+    pub struct Instantiated {
+        pub version: ContractVersion,
+    }
+
+    #[ink(event)]
+    pub struct SaleConfigured {
+        pub config: SaleConfig,
+    }
+
+    #[ink(event)]
+    pub struct TokensPurchased {
+        #[ink(topic)]
+        pub buyer: AccountId,
+        pub amount: Balance,
+        pub cost: Balance,
+        pub refunded: Balance,
+        pub total_sold: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryUpdated {
+        pub old_treasury: AccountId,
+        pub new_treasury: AccountId,
+    }
+
+    /// Errors
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        Unauthorized,
+        SaleNotConfigured,
+        SaleNotActive,
+        SalePaused,
+        InvalidConfig,
+        ZeroAmount,
+        ZeroCost,
+        HardCapExceeded,
+        AccountCapExceeded,
+        InsufficientPayment,
+        Overflow,
+        MintFailed,
+        TransferFailed,
+        ReentrantCall,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl TokenSale {
+        #[ink(constructor)]
+        pub fn new(token_address: AccountId, treasury: AccountId) -> Self {
+            let caller = Self::env().caller();
+            let instance = Self {
+                entered: false,
+                owner: caller,
+                governance_address: caller,
+                token_address,
+                treasury,
+                config: None,
+                paused: false,
+                sold: 0,
+                raised: 0,
+                purchased: Mapping::default(),
+            };
+            Self::env().emit_event(Instantiated { version: CONTRACT_VERSION });
+            instance
+        }
+
+        /// Buy `amount` PGT base units; pay at least `quote(amount)`, any excess is refunded
+        #[ink(message, payable)]
+        pub fn buy(&mut self, amount: Balance) -> Result<Balance> {
+            if self.entered {
+                return Err(Error::ReentrantCall);
+            }
+            if self.paused {
+                return Err(Error::SalePaused);
+            }
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            let config = self.config.ok_or(Error::SaleNotConfigured)?;
+            let now = self.env().block_timestamp();
+            if now < config.start || now >= config.end {
+                return Err(Error::SaleNotActive);
+            }
+            let total_sold = self.sold.checked_add(amount).ok_or(Error::Overflow)?;
+            if total_sold > config.hard_cap {
+                return Err(Error::HardCapExceeded);
+            }
+            let caller = self.env().caller();
+            let bought = self.purchased.get(caller).unwrap_or(0).saturating_add(amount);
+            if bought > config.per_account_cap {
+                return Err(Error::AccountCapExceeded);
+            }
+            let cost = config.pricing.cost(self.sold, amount).ok_or(Error::Overflow)?;
+            if cost == 0 {
+                return Err(Error::ZeroCost);
+            }
+            let paid = self.env().transferred_value();
+            if paid < cost {
+                return Err(Error::InsufficientPayment);
+            }
+
+            self.entered = true;
+            self.sold = total_sold;
+            self.raised = self.raised.saturating_add(cost);
+            self.purchased.insert(caller, &bought);
+
+            if let Some(mut token) = Token::connect(self.token_address) {
+                if token.mint(caller, amount).is_err() {
+                    self.entered = false;
+                    return Err(Error::MintFailed);
+                }
+            }
+            if self.env().transfer(self.treasury, cost).is_err() {
+                self.entered = false;
+                return Err(Error::TransferFailed);
+            }
+            let refunded = paid.saturating_sub(cost);
+            if refunded > 0 && self.env().transfer(caller, refunded).is_err() {
+                self.entered = false;
+                return Err(Error::TransferFailed);
+            }
+
+            self.env().emit_event(TokensPurchased { buyer: caller, amount, cost, refunded, total_sold });
+            self.entered = false;
+            Ok(cost)
+        }
+
+        /// Native cost of buying `amount` PGT base units right now
+        #[ink(message)]
+        pub fn quote(&self, amount: Balance) -> Result<Balance> {
+            let config = self.config.ok_or(Error::SaleNotConfigured)?;
+            config.pricing.cost(self.sold, amount).ok_or(Error::Overflow)
+        }
+
+        /// Spot price per whole PGT
+        #[ink(message)]
+        pub fn current_price(&self) -> Option<Balance> {
+            self.config.and_then(|config| config.pricing.price_at(self.sold))
+        }
+
+        /// PGT the account can still buy, bounded by both its own cap and the hard cap
+        #[ink(message)]
+        pub fn remaining_allowance(&self, account: AccountId) -> Balance {
+            let Some(config) = self.config else { return 0 };
+            let account_left = config.per_account_cap.saturating_sub(self.purchased.get(account).unwrap_or(0));
+            account_left.min(config.hard_cap.saturating_sub(self.sold))
+        }
+
+        /// Set or replace the sale parameters (owner/governance only)
+        #[ink(message)]
+        pub fn configure_sale(&mut self, config: SaleConfig) -> Result<()> {
+            self.ensure_admin()?;
+            if config.end <= config.start || config.hard_cap < self.sold || config.per_account_cap == 0 {
+                return Err(Error::InvalidConfig);
+            }
+            self.config = Some(config);
+            self.env().emit_event(SaleConfigured { config });
+            Ok(())
+        }
+
+        /// Change where proceeds are sent (owner/governance only)
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: AccountId) -> Result<()> {
+            self.ensure_admin()?;
+            let old_treasury = self.treasury;
+            self.treasury = treasury;
+            self.env().emit_event(TreasuryUpdated { old_treasury, new_treasury: treasury });
+            Ok(())
+        }
+
+        /// Halt or resume purchases (owner/governance only)
+        #[ink(message)]
+        pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+            self.ensure_admin()?;
+            self.paused = paused;
+            Ok(())
+        }
+
+        /// Set the governance contract (owner only)
+        #[ink(message)]
+        pub fn set_governance_address(&mut self, addr: AccountId) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::Unauthorized);
+            }
+            self.governance_address = addr;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_config(&self) -> Option<SaleConfig> {
+            self.config
+        }
+
+        #[ink(message)]
+        pub fn get_sold(&self) -> Balance {
+            self.sold
+        }
+
+        #[ink(message)]
+        pub fn get_raised(&self) -> Balance {
+            self.raised
+        }
+
+        #[ink(message)]
+        pub fn get_purchased(&self, account: AccountId) -> Balance {
+            self.purchased.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message)]
+        pub fn get_governance_address(&self) -> AccountId {
+            self.governance_address
+        }
+
+        #[ink(message)]
+        pub fn get_version(&self) -> ContractVersion {
+            CONTRACT_VERSION
+        }
+
+        /// Dry-run the roles the sale needs on the token and report readiness
+        #[ink(message)]
+        pub fn verify_wiring(&self) -> WiringReport {
+            let mut report = WiringReport::new();
+            if let Some(token) = Token::connect(self.token_address) {
+                report.check("token_minter", token.is_minter(self.env().account_id()));
+            }
+            report
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner && caller != self.governance_address {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{
+            callee, default_accounts, get_account_balance, set_account_balance, set_block_timestamp, set_caller,
+            set_value_transferred, DefaultAccounts,
+        };
+        use ink::env::DefaultEnvironment;
+
+        #[ink::test]
+        fn test_bonding_curve_sale_with_caps() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut sale = TokenSale::new(accounts.django, accounts.eve);
+            set_account_balance::<DefaultEnvironment>(callee::<DefaultEnvironment>(), 1_000_000);
+            let treasury_before = get_account_balance::<DefaultEnvironment>(accounts.eve).unwrap();
+
+            sale.configure_sale(SaleConfig {
+                pricing: Pricing::Linear { base_price: 100, slope: 10 },
+                start: 10,
+                end: 100,
+                hard_cap: 30 * PRICE_UNIT,
+                per_account_cap: 20 * PRICE_UNIT,
+            })
+            .unwrap();
+
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(sale.buy(PRICE_UNIT), Err(Error::SaleNotActive));
+            set_block_timestamp::<DefaultEnvironment>(10);
+
+            // First 10 PGT: average price 100 + 10 * 5 = 150
+            assert_eq!(sale.quote(10 * PRICE_UNIT), Ok(1_500));
+            set_value_transferred::<DefaultEnvironment>(1_400);
+            assert_eq!(sale.buy(10 * PRICE_UNIT), Err(Error::InsufficientPayment));
+            set_value_transferred::<DefaultEnvironment>(2_000);
+            assert_eq!(sale.buy(10 * PRICE_UNIT), Ok(1_500));
+            assert_eq!(sale.current_price(), Some(200));
+            assert_eq!(get_account_balance::<DefaultEnvironment>(accounts.eve).unwrap(), treasury_before + 1_500);
+
+            assert_eq!(sale.buy(11 * PRICE_UNIT), Err(Error::AccountCapExceeded));
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(sale.remaining_allowance(accounts.charlie), 20 * PRICE_UNIT);
+            assert_eq!(sale.buy(21 * PRICE_UNIT), Err(Error::HardCapExceeded));
+
+            assert_eq!(sale.configure_sale(sale.get_config().unwrap()), Err(Error::Unauthorized));
+        }
+
+
+        #[ink::test]
+        fn test_dust_purchases_are_never_free() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            let mut sale = TokenSale::new(accounts.django, accounts.eve);
+            set_account_balance::<DefaultEnvironment>(callee::<DefaultEnvironment>(), 1_000_000);
+            set_block_timestamp::<DefaultEnvironment>(10);
+            let config = SaleConfig {
+                pricing: Pricing::Fixed { price: 100 },
+                start: 10,
+                end: 100,
+                hard_cap: 30 * PRICE_UNIT,
+                per_account_cap: 20 * PRICE_UNIT,
+            };
+            sale.configure_sale(config).unwrap();
+
+            // One base unit is worth 1e-16 native units, charged as 1
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(sale.quote(1), Ok(1));
+            set_value_transferred::<DefaultEnvironment>(0);
+            assert_eq!(sale.buy(1), Err(Error::InsufficientPayment));
+            set_value_transferred::<DefaultEnvironment>(1);
+            assert_eq!(sale.buy(PRICE_UNIT / 100 - 1), Ok(1));
+            assert_eq!(sale.get_raised(), 1);
+            // Whole-token amounts are still charged exactly
+            assert_eq!(sale.quote(PRICE_UNIT), Ok(100));
+
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            sale.configure_sale(SaleConfig { pricing: Pricing::Fixed { price: 0 }, ..config }).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(sale.buy(PRICE_UNIT), Err(Error::ZeroCost));
+        }
+    }
+}
\ No newline at end of file
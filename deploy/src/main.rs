@@ -0,0 +1,282 @@
+//! Deployment orchestrator for PowerGrid Network
+//!
+//! Instantiates every contract in dependency order through `cargo contract`,
+//! performs the permission wiring the contracts expect, checks it with each
+//! contract's `verify_wiring` message and writes a JSON manifest of addresses
+//! for front-ends and relayers.
+//!
+//! Configuration is read from the environment:
+//! - `NODE_URL` (default `ws://localhost:9944`)
+//! - `SURI` deployer key (default `//Alice`)
+//! - `MANIFEST_PATH` (default `deployment/addresses.json`)
+//! - `SKIP_BUILD` set to skip `cargo contract build`
+
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type DeployResult<T> = Result<T, String>;
+
+const TOKEN_NAME: &str = "PowerGrid Token";
+const TOKEN_SYMBOL: &str = "PGT";
+const TOKEN_DECIMALS: u8 = 18;
+const TOKEN_INITIAL_SUPPLY: u128 = 1_000_000_000_000_000_000_000;
+/// Registry minimum stake in whole tokens
+const MIN_STAKE_TOKENS: u128 = 1;
+const MIN_VOTING_POWER: u128 = 100;
+const VOTING_DURATION_BLOCKS: u64 = 100;
+const QUORUM_PERCENTAGE: u32 = 10;
+/// Fee distribution epoch (7 days)
+const FEE_EPOCH_LENGTH_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+/// Keeper bond in token base units (0 = contract default)
+const KEEPER_MIN_BOND: u128 = 0;
+
+struct Deployer {
+    root: PathBuf,
+    url: String,
+    suri: String,
+}
+
+impl Deployer {
+    fn contract_dir(&self, contract: &str) -> PathBuf {
+        self.root.join("contracts").join(contract)
+    }
+
+    fn cargo_contract(&self, contract: &str, args: &[String]) -> DeployResult<String> {
+        let output = Command::new("cargo")
+            .arg("contract")
+            .args(args)
+            .current_dir(self.contract_dir(contract))
+            .output()
+            .map_err(|e| format!("failed to run cargo contract: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "cargo contract {} failed for {contract}: {}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn build(&self, contract: &str) -> DeployResult<()> {
+        println!("📦 Building {contract}...");
+        self.cargo_contract(contract, &["build".into(), "--release".into(), "--quiet".into()])?;
+        Ok(())
+    }
+
+    /// Instantiate `contract` with the `new` constructor and return its address
+    fn instantiate(&self, contract: &str, ctor_args: &[String]) -> DeployResult<String> {
+        println!("🚀 Instantiating {contract}...");
+        let mut args: Vec<String> = vec!["instantiate".into(), "--constructor".into(), "new".into()];
+        if !ctor_args.is_empty() {
+            args.push("--args".into());
+            args.extend(ctor_args.iter().cloned());
+        }
+        args.extend(self.tx_flags());
+        args.push("--output-json".into());
+        let stdout = self.cargo_contract(contract, &args)?;
+        let parsed: Value = serde_json::from_str(&stdout)
+            .map_err(|e| format!("unexpected instantiate output for {contract}: {e}"))?;
+        let address = parsed["contract"]
+            .as_str()
+            .ok_or_else(|| format!("no contract address in instantiate output for {contract}"))?;
+        println!("✅ {contract} at {address}");
+        Ok(address.to_string())
+    }
+
+    /// Submit a message call as a transaction
+    fn call(&self, contract: &str, address: &str, message: &str, msg_args: &[&str]) -> DeployResult<()> {
+        println!("🔑 {contract}.{message}({})", msg_args.join(", "));
+        let mut args: Vec<String> = vec![
+            "call".into(),
+            "--contract".into(),
+            address.into(),
+            "--message".into(),
+            message.into(),
+        ];
+        if !msg_args.is_empty() {
+            args.push("--args".into());
+            args.extend(msg_args.iter().map(|a| a.to_string()));
+        }
+        args.extend(self.tx_flags());
+        let stdout = self.cargo_contract(contract, &args)?;
+        // Messages returning Result<_, _> still succeed as extrinsics when they return Err
+        if stdout.contains("Err(") {
+            return Err(format!("{contract}.{message} returned an error: {stdout}"));
+        }
+        Ok(())
+    }
+
+    /// Dry-run `verify_wiring` and report whether the contract is ready
+    fn verify(&self, contract: &str, address: &str) -> DeployResult<bool> {
+        let args: Vec<String> = vec![
+            "call".into(),
+            "--contract".into(),
+            address.into(),
+            "--message".into(),
+            "verify_wiring".into(),
+            "--suri".into(),
+            self.suri.clone(),
+            "--url".into(),
+            self.url.clone(),
+        ];
+        let stdout = self.cargo_contract(contract, &args)?;
+        let ready = stdout.contains("ready: true");
+        if ready {
+            println!("🩺 {contract} ready");
+        } else {
+            println!("⚠️  {contract} not ready:\n{stdout}");
+        }
+        Ok(ready)
+    }
+
+    fn tx_flags(&self) -> Vec<String> {
+        vec![
+            "--suri".into(),
+            self.suri.clone(),
+            "--url".into(),
+            self.url.clone(),
+            "--execute".into(),
+            "--skip-confirm".into(),
+        ]
+    }
+}
+
+fn quoted(s: &str) -> String {
+    format!("\"{s}\"")
+}
+
+fn run() -> DeployResult<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .ok_or("deploy crate has no workspace root")?
+        .to_path_buf();
+    let deployer = Deployer {
+        url: env::var("NODE_URL").unwrap_or_else(|_| "ws://localhost:9944".into()),
+        suri: env::var("SURI").unwrap_or_else(|_| "//Alice".into()),
+        root,
+    };
+    let manifest_path = env::var("MANIFEST_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| deployer.root.join("deployment").join("addresses.json"));
+
+    let contracts = ["token", "resource_registry", "grid_service", "governance", "fee_distributor", "token_sale", "keeper_registry"];
+    if env::var("SKIP_BUILD").is_err() {
+        for contract in contracts {
+            deployer.build(contract)?;
+        }
+    }
+
+    println!("📋 Deploying contracts in dependency order...");
+    let token = deployer.instantiate(
+        "token",
+        &[
+            quoted(TOKEN_NAME),
+            quoted(TOKEN_SYMBOL),
+            TOKEN_DECIMALS.to_string(),
+            TOKEN_INITIAL_SUPPLY.to_string(),
+        ],
+    )?;
+    let registry = deployer.instantiate("resource_registry", &[MIN_STAKE_TOKENS.to_string()])?;
+    let grid = deployer.instantiate("grid_service", &[token.clone(), registry.clone()])?;
+    let governance = deployer.instantiate(
+        "governance",
+        &[
+            token.clone(),
+            registry.clone(),
+            grid.clone(),
+            MIN_VOTING_POWER.to_string(),
+            VOTING_DURATION_BLOCKS.to_string(),
+            QUORUM_PERCENTAGE.to_string(),
+        ],
+    )?;
+    let fee_distributor = deployer.instantiate(
+        "fee_distributor",
+        &[token.clone(), FEE_EPOCH_LENGTH_MS.to_string()],
+    )?;
+    // Sale proceeds go to the governance treasury; the sale itself is configured by governance
+    let token_sale = deployer.instantiate("token_sale", &[token.clone(), governance.clone()])?;
+    let keeper_registry = deployer.instantiate("keeper_registry", &[token.clone(), KEEPER_MIN_BOND.to_string()])?;
+
+    println!("🔗 Wiring permissions...");
+    // GridService mints rewards and updates device reputation
+    deployer.call("token", &token, "add_minter", &[&grid])?;
+    deployer.call("resource_registry", &registry, "add_authorized_caller", &[&grid])?;
+    // Governance locks voted balances and administers registry and grid
+    deployer.call("token", &token, "add_locker", &[&governance])?;
+    deployer.call("resource_registry", &registry, "set_governance_address", &[&governance])?;
+    deployer.call("grid_service", &grid, "set_governance_address", &[&governance])?;
+    // Registry pays referral bonuses and restakes through the token
+    deployer.call("resource_registry", &registry, "set_token_address", &[&token])?;
+    // Grid and registry may report protocol fees to the distributor
+    deployer.call("fee_distributor", &fee_distributor, "set_fee_source", &[&grid, "true"])?;
+    deployer.call("fee_distributor", &fee_distributor, "set_fee_source", &[&registry, "true"])?;
+
+    // The sale mints PGT to buyers
+    deployer.call("token", &token, "add_minter", &[&token_sale])?;
+    deployer.call("token_sale", &token_sale, "set_governance_address", &[&governance])?;
+    // Governance sets keeper fees and slashes griefing keepers
+    deployer.call("keeper_registry", &keeper_registry, "set_governance_address", &[&governance])?;
+
+    println!("🩺 Verifying wiring...");
+    let addresses = [
+        ("token", &token),
+        ("resource_registry", &registry),
+        ("grid_service", &grid),
+        ("governance", &governance),
+        ("fee_distributor", &fee_distributor),
+        ("token_sale", &token_sale),
+        ("keeper_registry", &keeper_registry),
+    ];
+    let mut readiness = serde_json::Map::new();
+    let mut all_ready = true;
+    for (contract, address) in addresses {
+        let ready = deployer.verify(contract, address)?;
+        all_ready &= ready;
+        readiness.insert(contract.to_string(), Value::Bool(ready));
+    }
+
+    let deployed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let manifest = json!({
+        "network": deployer.url,
+        "deployer": deployer.suri,
+        "deployed_at": deployed_at,
+        "contracts": {
+            "powergrid_token": token,
+            "resource_registry": registry,
+            "grid_service": grid,
+            "governance": governance,
+            "fee_distributor": fee_distributor,
+            "token_sale": token_sale,
+            "keeper_registry": keeper_registry,
+        },
+        "wiring": readiness,
+    });
+    if let Some(dir) = manifest_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    }
+    let rendered = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(&manifest_path, rendered)
+        .map_err(|e| format!("failed to write {}: {e}", manifest_path.display()))?;
+    println!("📄 Manifest written to {}", manifest_path.display());
+
+    if !all_ready {
+        return Err("deployment finished but wiring verification failed".into());
+    }
+    println!("🎉 Deployment completed successfully!");
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("❌ {err}");
+        exit(1);
+    }
+}
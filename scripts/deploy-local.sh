@@ -88,6 +88,14 @@ deploy_registry() {
     fi
 }
 
+# Dry-run verify_wiring on already-deployed contracts (addresses via env, e.g. GRID_ADDRESS)
+verify_wiring() {
+    local contract_dir="$1" address="$2"
+    [ -z "$address" ] && return 0
+    echo -e "${BLUE}🩺 Verifying wiring for $contract_dir ($address)...${NC}"
+    (cd "contracts/$contract_dir" && cargo contract call --contract "$address" --message verify_wiring --suri //Alice --url "$WS_URL" --skip-confirm)
+}
+
 main() {
     check_node
     mkdir -p deployment
@@ -112,6 +120,11 @@ main() {
         exit 1
     fi
     
+    verify_wiring token "$TOKEN_ADDRESS"
+    verify_wiring resource_registry "$REGISTRY_ADDRESS"
+    verify_wiring grid_service "$GRID_ADDRESS"
+    verify_wiring governance "$GOVERNANCE_ADDRESS"
+
     echo -e "${GREEN}🎉 Deployment completed successfully!${NC}"
     echo -e "${YELLOW}Contract addresses are shown in the output above${NC}"
     
@@ -0,0 +1,89 @@
+//! Timelocked changes of the external contract addresses a contract calls.
+//!
+//! Swapping a dependency (e.g. the token a contract mints through) only takes
+//! effect `ADDRESS_CHANGE_DELAY_MS` after it was proposed, leaving the
+//! guardian time to cancel a change pushed by a compromised owner key.
+
+use ink::primitives::AccountId;
+use ink::storage::Mapping;
+use scale::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Delay between proposing and executing an address change (48 hours)
+pub const ADDRESS_CHANGE_DELAY_MS: u64 = 48 * 60 * 60 * 1000;
+
+/// Named external contract in a contract's address book
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum ExternalContract {
+    Token,
+    Registry,
+    GridService,
+    Governance,
+}
+
+/// Proposed address waiting out the delay
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct PendingAddressChange {
+    pub new_address: AccountId,
+    pub proposed_by: AccountId,
+    pub executable_at: u64,
+}
+
+/// Why a pending address change cannot be executed
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+pub enum AddressChangeError {
+    NoPendingChange,
+    TimelockActive,
+}
+
+impl AddressChangeError {
+    /// Error message for contracts returning `String` errors
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressChangeError::NoPendingChange => "No pending address change",
+            AddressChangeError::TimelockActive => "Address change timelock active",
+        }
+    }
+}
+
+/// Pending address changes per external contract; embed as a contract storage field
+#[ink::storage_item]
+#[derive(Default, Debug)]
+pub struct AddressTimelock {
+    pending: Mapping<ExternalContract, PendingAddressChange>,
+}
+
+impl AddressTimelock {
+    /// Change waiting for `contract`, if any
+    pub fn pending(&self, contract: ExternalContract) -> Option<PendingAddressChange> {
+        self.pending.get(contract)
+    }
+
+    /// Queue `new_address` for `contract`, replacing any earlier proposal; returns when it can execute
+    pub fn propose(&mut self, contract: ExternalContract, new_address: AccountId, proposed_by: AccountId, now: u64) -> u64 {
+        let executable_at = now.saturating_add(ADDRESS_CHANGE_DELAY_MS);
+        self.pending.insert(contract, &PendingAddressChange { new_address, proposed_by, executable_at });
+        executable_at
+    }
+
+    /// Drop the pending change of `contract`; false if there was none
+    pub fn cancel(&mut self, contract: ExternalContract) -> bool {
+        if !self.pending.contains(contract) {
+            return false;
+        }
+        self.pending.remove(contract);
+        true
+    }
+
+    /// Remove and return the new address of `contract` once its delay has passed
+    pub fn take_ready(&mut self, contract: ExternalContract, now: u64) -> Result<AccountId, AddressChangeError> {
+        let change = self.pending.get(contract).ok_or(AddressChangeError::NoPendingChange)?;
+        if now < change.executable_at {
+            return Err(AddressChangeError::TimelockActive);
+        }
+        self.pending.remove(contract);
+        Ok(change.new_address)
+    }
+}
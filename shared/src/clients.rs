@@ -0,0 +1,334 @@
+//! Plain-Rust clients for the cross-contract interfaces.
+//!
+//! Contract code talks to its dependencies through these traits and obtains
+//! them with `Connect`. On chain the implementations are the `…Ref` call
+//! builders; unit tests swap in the programmable mocks of the `mock` module,
+//! so the cross-contract branches run off-chain too.
+
+use crate::interfaces::*;
+use crate::{Alarm, CorrelationId, CallerPermissions, CustomEventTypeDescriptor, Device, DeviceSla, DeviceType, DeviceTypePolicy, EmissionBucket, EmissionSchedule, FeedRateLimit, GridEventType, RewardConfig, TriggerRuleParams};
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+use crate::env::Balance;
+
+/// Obtain a client for the contract at `address`; `None` when nothing answers there
+pub trait Connect: Sized {
+    fn connect(address: AccountId) -> Option<Self>;
+}
+
+/// Token calls made by other contracts
+pub trait TokenClient {
+    fn total_supply(&self) -> Balance;
+    fn balance_of(&self, owner: AccountId) -> Balance;
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+    fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error>;
+    fn mint_correlated(&mut self, account: AccountId, amount: Balance, correlation_id: CorrelationId) -> Result<(), PSP22Error>;
+    fn is_minter(&self, account: AccountId) -> bool;
+    fn add_minter(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+    fn remove_minter(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+    fn is_locker(&self, account: AccountId) -> bool;
+    fn lock(&mut self, account: AccountId, amount: Balance, until_block: u32) -> Result<(), PSP22Error>;
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), PSP22Error>;
+    fn set_emergency_budget(&mut self, minter: Option<AccountId>, cap_per_quarter: Balance) -> Result<(), PSP22Error>;
+    fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<(), PSP22Error>;
+    fn set_minter_emission_bucket(&mut self, minter: AccountId, bucket: EmissionBucket) -> Result<(), PSP22Error>;
+}
+
+impl Connect for TokenRef {
+    fn connect(address: AccountId) -> Option<Self> {
+        Some(address.into())
+    }
+}
+
+impl TokenClient for TokenRef {
+    fn total_supply(&self) -> Balance {
+        TokenApi::total_supply(self)
+    }
+
+    fn balance_of(&self, owner: AccountId) -> Balance {
+        TokenApi::balance_of(self, owner)
+    }
+
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+        TokenApi::transfer(self, to, value, data)
+    }
+
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+        TokenApi::transfer_from(self, from, to, value, data)
+    }
+
+    fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        TokenApi::mint(self, account, amount)
+    }
+
+    fn mint_correlated(&mut self, account: AccountId, amount: Balance, correlation_id: CorrelationId) -> Result<(), PSP22Error> {
+        TokenApi::mint_correlated(self, account, amount, correlation_id)
+    }
+
+    fn is_minter(&self, account: AccountId) -> bool {
+        TokenApi::is_minter(self, account)
+    }
+
+    fn add_minter(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        TokenApi::add_minter(self, account)
+    }
+
+    fn remove_minter(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        TokenApi::remove_minter(self, account)
+    }
+
+    fn is_locker(&self, account: AccountId) -> bool {
+        TokenApi::is_locker(self, account)
+    }
+
+    fn lock(&mut self, account: AccountId, amount: Balance, until_block: u32) -> Result<(), PSP22Error> {
+        TokenApi::lock(self, account, amount, until_block)
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), PSP22Error> {
+        TokenApi::set_guardian(self, guardian)
+    }
+
+    fn set_emergency_budget(&mut self, minter: Option<AccountId>, cap_per_quarter: Balance) -> Result<(), PSP22Error> {
+        TokenApi::set_emergency_budget(self, minter, cap_per_quarter)
+    }
+
+    fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<(), PSP22Error> {
+        TokenApi::set_emission_schedule(self, schedule)
+    }
+
+    fn set_minter_emission_bucket(&mut self, minter: AccountId, bucket: EmissionBucket) -> Result<(), PSP22Error> {
+        TokenApi::set_minter_emission_bucket(self, minter, bucket)
+    }
+}
+
+/// Registry calls made by other contracts
+pub trait RegistryClient {
+    fn get_device(&self, account: AccountId) -> Option<Device>;
+    fn get_device_reputation(&self, account: AccountId) -> Option<u32>;
+    fn get_device_sla(&self, account: AccountId) -> Option<DeviceSla>;
+    fn get_reputation_threshold(&self) -> u32;
+    fn is_eligible(&self, account: AccountId) -> bool;
+    fn is_blacklisted(&self, account: AccountId) -> bool;
+    fn get_min_stake(&self) -> Balance;
+    fn get_payout_address(&self, account: AccountId) -> AccountId;
+    fn get_unavailable_time(&self, account: AccountId, from: u64, to: u64) -> u64;
+    fn get_slashed_stake(&self, account: AccountId, period: u64) -> Balance;
+    fn get_device_key(&self, account: AccountId) -> Option<AccountId>;
+    fn is_authorized_caller(&self, account: AccountId) -> bool;
+    fn get_governance_address(&self) -> Option<AccountId>;
+    fn increase_stake_for(&mut self, account: AccountId, amount: Balance) -> Result<(), String>;
+    fn record_event_performance(&mut self, account: AccountId, event_id: u64, energy_contributed: u64, success: bool, correlation_id: CorrelationId) -> Result<(), String>;
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+    fn set_device_type_policy(&mut self, device_type: DeviceType, policy: Option<DeviceTypePolicy>) -> Result<(), String>;
+    fn set_min_firmware_version(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String>;
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String>;
+    fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String>;
+    fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String>;
+}
+
+impl Connect for RegistryRef {
+    fn connect(address: AccountId) -> Option<Self> {
+        Some(address.into())
+    }
+}
+
+impl RegistryClient for RegistryRef {
+    fn get_device(&self, account: AccountId) -> Option<Device> {
+        RegistryApi::get_device(self, account)
+    }
+
+    fn get_device_reputation(&self, account: AccountId) -> Option<u32> {
+        RegistryApi::get_device_reputation(self, account)
+    }
+
+    fn get_device_sla(&self, account: AccountId) -> Option<DeviceSla> {
+        RegistryApi::get_device_sla(self, account)
+    }
+
+    fn get_reputation_threshold(&self) -> u32 {
+        RegistryApi::get_reputation_threshold(self)
+    }
+
+    fn is_eligible(&self, account: AccountId) -> bool {
+        RegistryApi::is_eligible(self, account)
+    }
+
+    fn is_blacklisted(&self, account: AccountId) -> bool {
+        RegistryApi::is_blacklisted(self, account)
+    }
+
+    fn get_min_stake(&self) -> Balance {
+        RegistryApi::get_min_stake(self)
+    }
+
+    fn get_payout_address(&self, account: AccountId) -> AccountId {
+        RegistryApi::get_payout_address(self, account)
+    }
+
+    fn get_unavailable_time(&self, account: AccountId, from: u64, to: u64) -> u64 {
+        RegistryApi::get_unavailable_time(self, account, from, to)
+    }
+
+    fn get_slashed_stake(&self, account: AccountId, period: u64) -> Balance {
+        RegistryApi::get_slashed_stake(self, account, period)
+    }
+
+    fn get_device_key(&self, account: AccountId) -> Option<AccountId> {
+        RegistryApi::get_device_key(self, account)
+    }
+
+    fn is_authorized_caller(&self, account: AccountId) -> bool {
+        RegistryApi::is_authorized_caller(self, account)
+    }
+
+    fn get_governance_address(&self) -> Option<AccountId> {
+        RegistryApi::get_governance_address(self)
+    }
+
+    fn increase_stake_for(&mut self, account: AccountId, amount: Balance) -> Result<(), String> {
+        RegistryApi::increase_stake_for(self, account, amount)
+    }
+
+    fn record_event_performance(&mut self, account: AccountId, event_id: u64, energy_contributed: u64, success: bool, correlation_id: CorrelationId) -> Result<(), String> {
+        RegistryApi::record_event_performance(self, account, event_id, energy_contributed, success, correlation_id)
+    }
+
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        RegistryApi::add_authorized_caller(self, caller)
+    }
+
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        RegistryApi::remove_authorized_caller(self, caller)
+    }
+
+    fn set_device_type_policy(&mut self, device_type: DeviceType, policy: Option<DeviceTypePolicy>) -> Result<(), String> {
+        RegistryApi::set_device_type_policy(self, device_type, policy)
+    }
+
+    fn set_min_firmware_version(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String> {
+        RegistryApi::set_min_firmware_version(self, manufacturer, model, version)
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String> {
+        RegistryApi::set_guardian(self, guardian)
+    }
+
+    fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String> {
+        RegistryApi::update_min_stake(self, new_min_stake)
+    }
+
+    fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String> {
+        RegistryApi::update_reputation_threshold(self, new_threshold)
+    }
+}
+
+/// Grid service calls made by governance
+pub trait GridClient {
+    fn get_governance_address(&self) -> AccountId;
+    fn get_default_compensation_rate(&self) -> Balance;
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+    fn update_default_compensation_rate(&mut self, new_rate: Balance) -> Result<(), String>;
+    fn set_custom_event_type(&mut self, type_id: u16, descriptor: Option<CustomEventTypeDescriptor>) -> Result<(), String>;
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String>;
+    fn set_auto_trigger_enabled(&mut self, enabled: bool) -> Result<(), String>;
+    fn create_trigger_rule(&mut self, params: TriggerRuleParams) -> Result<u64, String>;
+    fn set_trigger_rule_active(&mut self, rule_id: u64, active: bool) -> Result<(), String>;
+    fn remove_trigger_rule(&mut self, rule_id: u64) -> Result<(), String>;
+    fn add_data_feed(&mut self, feed_address: AccountId) -> Result<(), String>;
+    fn remove_data_feed(&mut self, feed_address: AccountId) -> Result<(), String>;
+    fn set_elected_data_feeds(&mut self, feeds: Vec<AccountId>) -> Result<(), String>;
+    fn get_alarm(&self, alarm_id: u64) -> Option<Alarm>;
+    fn set_reward_config(&mut self, config: RewardConfig) -> Result<(), String>;
+    fn set_feed_rate_limit(&mut self, feed: Option<AccountId>, limit: Option<FeedRateLimit>) -> Result<(), String>;
+    fn set_caller_permissions(&mut self, caller: AccountId, permissions: Option<CallerPermissions>) -> Result<(), String>;
+    fn set_type_verifier(&mut self, event_type: GridEventType, verifier: AccountId, approved: bool) -> Result<(), String>;
+}
+
+impl Connect for GridRef {
+    fn connect(address: AccountId) -> Option<Self> {
+        Some(address.into())
+    }
+}
+
+impl GridClient for GridRef {
+    fn get_governance_address(&self) -> AccountId {
+        GridApi::get_governance_address(self)
+    }
+
+    fn get_default_compensation_rate(&self) -> Balance {
+        GridApi::get_default_compensation_rate(self)
+    }
+
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        GridApi::add_authorized_caller(self, caller)
+    }
+
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        GridApi::remove_authorized_caller(self, caller)
+    }
+
+    fn update_default_compensation_rate(&mut self, new_rate: Balance) -> Result<(), String> {
+        GridApi::update_default_compensation_rate(self, new_rate)
+    }
+
+    fn set_custom_event_type(&mut self, type_id: u16, descriptor: Option<CustomEventTypeDescriptor>) -> Result<(), String> {
+        GridApi::set_custom_event_type(self, type_id, descriptor)
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String> {
+        GridApi::set_guardian(self, guardian)
+    }
+
+    fn set_auto_trigger_enabled(&mut self, enabled: bool) -> Result<(), String> {
+        GridApi::set_auto_trigger_enabled(self, enabled)
+    }
+
+    fn create_trigger_rule(&mut self, params: TriggerRuleParams) -> Result<u64, String> {
+        GridApi::create_trigger_rule(self, params)
+    }
+
+    fn set_trigger_rule_active(&mut self, rule_id: u64, active: bool) -> Result<(), String> {
+        GridApi::set_trigger_rule_active(self, rule_id, active)
+    }
+
+    fn remove_trigger_rule(&mut self, rule_id: u64) -> Result<(), String> {
+        GridApi::remove_trigger_rule(self, rule_id)
+    }
+
+    fn add_data_feed(&mut self, feed_address: AccountId) -> Result<(), String> {
+        GridApi::add_data_feed(self, feed_address)
+    }
+
+    fn remove_data_feed(&mut self, feed_address: AccountId) -> Result<(), String> {
+        GridApi::remove_data_feed(self, feed_address)
+    }
+
+    fn set_elected_data_feeds(&mut self, feeds: Vec<AccountId>) -> Result<(), String> {
+        GridApi::set_elected_data_feeds(self, feeds)
+    }
+
+    fn get_alarm(&self, alarm_id: u64) -> Option<Alarm> {
+        GridApi::get_alarm(self, alarm_id)
+    }
+
+    fn set_reward_config(&mut self, config: RewardConfig) -> Result<(), String> {
+        GridApi::set_reward_config(self, config)
+    }
+
+    fn set_feed_rate_limit(&mut self, feed: Option<AccountId>, limit: Option<FeedRateLimit>) -> Result<(), String> {
+        GridApi::set_feed_rate_limit(self, feed, limit)
+    }
+
+    fn set_caller_permissions(&mut self, caller: AccountId, permissions: Option<CallerPermissions>) -> Result<(), String> {
+        GridApi::set_caller_permissions(self, caller, permissions)
+    }
+
+    fn set_type_verifier(&mut self, event_type: GridEventType, verifier: AccountId, approved: bool) -> Result<(), String> {
+        GridApi::set_type_verifier(self, event_type, verifier, approved)
+    }
+}
@@ -8,6 +8,27 @@ pub const SUBSTRATE_UNIT: u128 = 1_000_000_000_000; // 10^12
 pub const ONE_TOKEN: u128 = SUBSTRATE_UNIT;
 pub const MIN_STAKE_DEFAULT: u128 = ONE_TOKEN; // 1 token minimum
 
+/// Longest zone accepted on devices (geohash precision 5, ~5km cells)
+pub const MAX_ZONE_LENGTH: usize = 5;
+
+/// Heartbeats are counted at most once per interval (1 hour)
+pub const HEARTBEAT_INTERVAL_MS: u64 = 60 * 60 * 1000;
+
+/// Rolling availability window for device SLAs (30 days)
+pub const SLA_WINDOW_MS: u64 = 30 * 24 * HEARTBEAT_INTERVAL_MS;
+
+/// Length of an account statement period (365 days); period `n` covers `[n, n + 1) * STATEMENT_PERIOD_MS`
+pub const STATEMENT_PERIOD_MS: u64 = 365 * 24 * HEARTBEAT_INTERVAL_MS;
+
+/// Block time assumed when a block-count window also gets a timestamp deadline
+pub const EXPECTED_BLOCK_TIME_MS: u64 = 6_000;
+
+/// Most items returned by one page of an export message
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Fewest blocks between two health snapshots of a contract (~1 hour at 6s blocks)
+pub const HEALTH_SNAPSHOT_INTERVAL_BLOCKS: u32 = 600;
+
 /// Helper functions for unit conversion
 pub fn tokens_to_native(tokens: u128) -> u128 {
     tokens.saturating_mul(SUBSTRATE_UNIT)
@@ -17,3 +38,9 @@ pub fn native_to_tokens(native: u128) -> u128 {
     native / SUBSTRATE_UNIT
 }
 
+/// Split an amount into (recipient share, protocol fee) at `fee_bps` basis points
+pub fn split_protocol_fee(amount: u128, fee_bps: u16) -> (u128, u128) {
+    let fee = amount.saturating_mul(u128::from(fee_bps)) / 10_000;
+    (amount.saturating_sub(fee), fee)
+}
+
@@ -0,0 +1,17 @@
+//! Correlation IDs tying together the events one settlement emits across contracts.
+//!
+//! GridService tags a reward payout with the ID of its grid event (or one the
+//! event's authority supplied) and passes it along to the token mint and the
+//! registry's performance update, which include it as a topic in `Minted` and
+//! `ReputationUpdated`. Indexers filter on that topic to follow a payout from
+//! `RewardPaid` through every contract it touched.
+
+/// Opaque ID shared by the events of one cross-contract flow
+pub type CorrelationId = [u8; 32];
+
+/// Default correlation ID of a grid event: blake2_256(SCALE("grid-event", event_id))
+pub fn event_correlation_id(event_id: u64) -> CorrelationId {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&("grid-event", event_id), &mut output);
+    output
+}
@@ -0,0 +1,53 @@
+//! Environment types shared by every contract and conversions between them.
+//!
+//! Contracts use ink's `DefaultEnvironment`, so `Balance` and `Timestamp` here
+//! match the `Balance`/`Timestamp` seen inside `#[ink::contract]` modules.
+//! Public messages and shared types take accounts as `AccountId`; raw 32-byte
+//! values only appear in hashes and signatures, converted with the traits below.
+
+pub use ink::primitives::AccountId;
+
+pub type Balance = u128;
+pub type Timestamp = u64;
+
+/// Conversion into the raw 32-byte form of an account
+pub trait IntoBytes32 {
+    fn into_bytes32(self) -> [u8; 32];
+}
+
+/// Conversion from the raw 32-byte form of an account
+pub trait FromBytes32 {
+    fn from_bytes32(bytes: [u8; 32]) -> Self;
+}
+
+impl IntoBytes32 for AccountId {
+    fn into_bytes32(self) -> [u8; 32] {
+        *AsRef::<[u8; 32]>::as_ref(&self)
+    }
+}
+
+impl IntoBytes32 for [u8; 32] {
+    fn into_bytes32(self) -> [u8; 32] {
+        self
+    }
+}
+
+impl FromBytes32 for AccountId {
+    fn from_bytes32(bytes: [u8; 32]) -> Self {
+        AccountId::from(bytes)
+    }
+}
+
+impl FromBytes32 for [u8; 32] {
+    fn from_bytes32(bytes: [u8; 32]) -> Self {
+        bytes
+    }
+}
+
+pub fn ink_account_to_bytes(account: AccountId) -> [u8; 32] {
+    account.into_bytes32()
+}
+
+pub fn bytes_to_ink_account(bytes: [u8; 32]) -> AccountId {
+    AccountId::from_bytes32(bytes)
+}
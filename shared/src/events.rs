@@ -0,0 +1,36 @@
+//! Typed decoding of contract events for off-chain clients and tests.
+//!
+//! ink! emits each event with its signature topic first, followed by the
+//! SCALE-encoded event struct as data. These helpers match the signature
+//! topic and decode the payload into the contract's event type, e.g.
+//! `decode_event::<RewardPaid, _>(&topics, &data)`.
+
+use ink::env::Event;
+use ink::prelude::vec::Vec;
+use scale::Decode;
+
+/// Decode `data` as `Ev` if the event's first topic is `Ev`'s signature topic
+pub fn decode_event<Ev, T>(topics: &[T], data: &[u8]) -> Option<Ev>
+where
+    Ev: Event + Decode,
+    T: AsRef<[u8]>,
+{
+    let signature = Ev::SIGNATURE_TOPIC?;
+    if topics.first().map(|t| t.as_ref()) != Some(&signature[..]) {
+        return None;
+    }
+    Ev::decode(&mut &data[..]).ok()
+}
+
+/// Decode every event of type `Ev` from an iterator of (topics, data) pairs
+pub fn decode_events<'a, Ev, T, I>(events: I) -> Vec<Ev>
+where
+    Ev: Event + Decode,
+    T: AsRef<[u8]> + 'a,
+    I: IntoIterator<Item = (&'a [T], &'a [u8])>,
+{
+    events
+        .into_iter()
+        .filter_map(|(topics, data)| decode_event::<Ev, T>(topics, data))
+        .collect()
+}
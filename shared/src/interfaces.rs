@@ -0,0 +1,228 @@
+//! Cross-contract interfaces of the PowerGrid contracts.
+//!
+//! Contracts call each other through these trait definitions instead of the
+//! concrete `…Ref` types, so a dependency can be replaced by any contract (or
+//! test mock) exposing the same messages, and contracts no longer need each
+//! other as build dependencies. Selectors are pinned to the names of the
+//! contracts' inherent messages, which the deployed contracts already answer.
+
+use crate::{Alarm, CorrelationId, CallerPermissions, CustomEventTypeDescriptor, Device, DeviceSla, DeviceType, DeviceTypePolicy, EmissionBucket, EmissionSchedule, FeedRateLimit, GridEventType, RewardConfig, TriggerRuleParams};
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+use crate::env::Balance;
+
+/// PSP22 error
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+/// PSP22 surface of the PGT token plus its minter, locker and guardian roles
+#[ink::trait_definition]
+pub trait TokenApi {
+    #[ink(message, selector = 0xDB6375A8)]
+    fn total_supply(&self) -> Balance;
+
+    #[ink(message, selector = 0x0F755A56)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    #[ink(message, selector = 0x84A15DA1)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x0B396F18)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0xCFDD9AA2)]
+    fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x8FE312F8)]
+    fn mint_correlated(&mut self, account: AccountId, amount: Balance, correlation_id: CorrelationId) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x13FC1F2D)]
+    fn is_minter(&self, account: AccountId) -> bool;
+
+    #[ink(message, selector = 0xE55FB130)]
+    fn add_minter(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0xE4873304)]
+    fn remove_minter(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0xC3D602A6)]
+    fn is_locker(&self, account: AccountId) -> bool;
+
+    #[ink(message, selector = 0xBBAA6540)]
+    fn lock(&mut self, account: AccountId, amount: Balance, until_block: u32) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x5133ED53)]
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x833CE427)]
+    fn set_emergency_budget(&mut self, minter: Option<AccountId>, cap_per_quarter: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x0CF53459)]
+    fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<(), PSP22Error>;
+
+    #[ink(message, selector = 0x0CA18943)]
+    fn set_minter_emission_bucket(&mut self, minter: AccountId, bucket: EmissionBucket) -> Result<(), PSP22Error>;
+}
+
+/// Device data and governance hooks of the resource registry
+#[ink::trait_definition]
+pub trait RegistryApi {
+    #[ink(message, selector = 0x5F31A3E9)]
+    fn get_device(&self, account: AccountId) -> Option<Device>;
+
+    #[ink(message, selector = 0x0D3AD485)]
+    fn get_device_reputation(&self, account: AccountId) -> Option<u32>;
+
+    #[ink(message, selector = 0xC3CCC835)]
+    fn get_device_sla(&self, account: AccountId) -> Option<DeviceSla>;
+
+    #[ink(message, selector = 0x9E7AECA8)]
+    fn get_reputation_threshold(&self) -> u32;
+
+    #[ink(message, selector = 0xE3395D8A)]
+    fn is_eligible(&self, account: AccountId) -> bool;
+
+    #[ink(message, selector = 0x5663FE67)]
+    fn is_blacklisted(&self, account: AccountId) -> bool;
+
+    #[ink(message, selector = 0x0E7CDD56)]
+    fn get_min_stake(&self) -> Balance;
+
+    #[ink(message, selector = 0x9D125B8F)]
+    fn get_payout_address(&self, account: AccountId) -> AccountId;
+
+    #[ink(message, selector = 0x392DE6CD)]
+    fn get_unavailable_time(&self, account: AccountId, from: u64, to: u64) -> u64;
+
+    #[ink(message, selector = 0x8B618015)]
+    fn get_slashed_stake(&self, account: AccountId, period: u64) -> Balance;
+
+    #[ink(message, selector = 0x49F45788)]
+    fn get_device_key(&self, account: AccountId) -> Option<AccountId>;
+
+    #[ink(message, selector = 0xBE7D65EF)]
+    fn is_authorized_caller(&self, account: AccountId) -> bool;
+
+    #[ink(message, selector = 0x58311718)]
+    fn get_governance_address(&self) -> Option<AccountId>;
+
+    #[ink(message, selector = 0xC1D9A74D)]
+    fn increase_stake_for(&mut self, account: AccountId, amount: Balance) -> Result<(), String>;
+
+    #[ink(message, selector = 0x338C37F5)]
+    fn record_event_performance(&mut self, account: AccountId, event_id: u64, energy_contributed: u64, success: bool, correlation_id: CorrelationId) -> Result<(), String>;
+
+    #[ink(message, selector = 0xEB795B78)]
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x535ACB74)]
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x4D05D8CF)]
+    fn set_device_type_policy(&mut self, device_type: DeviceType, policy: Option<DeviceTypePolicy>) -> Result<(), String>;
+
+    #[ink(message, selector = 0x665CCA1D)]
+    fn set_min_firmware_version(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String>;
+
+    #[ink(message, selector = 0x5133ED53)]
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String>;
+
+    #[ink(message, selector = 0xF10277E6)]
+    fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String>;
+
+    #[ink(message, selector = 0xDB686F69)]
+    fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String>;
+}
+
+/// Governance hooks of the grid service
+#[ink::trait_definition]
+pub trait GridApi {
+    #[ink(message, selector = 0x58311718)]
+    fn get_governance_address(&self) -> AccountId;
+
+    #[ink(message, selector = 0xE0B1882D)]
+    fn get_default_compensation_rate(&self) -> Balance;
+
+    #[ink(message, selector = 0xEB795B78)]
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x535ACB74)]
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x8D2FC128)]
+    fn update_default_compensation_rate(&mut self, new_rate: Balance) -> Result<(), String>;
+
+    #[ink(message, selector = 0x92B9E880)]
+    fn set_custom_event_type(&mut self, type_id: u16, descriptor: Option<CustomEventTypeDescriptor>) -> Result<(), String>;
+
+    #[ink(message, selector = 0x5133ED53)]
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String>;
+
+    #[ink(message, selector = 0x2804311E)]
+    fn set_auto_trigger_enabled(&mut self, enabled: bool) -> Result<(), String>;
+
+    #[ink(message, selector = 0x0174ED3C)]
+    fn create_trigger_rule(&mut self, params: TriggerRuleParams) -> Result<u64, String>;
+
+    #[ink(message, selector = 0x303A44D3)]
+    fn set_trigger_rule_active(&mut self, rule_id: u64, active: bool) -> Result<(), String>;
+
+    #[ink(message, selector = 0xE2A2A7B2)]
+    fn remove_trigger_rule(&mut self, rule_id: u64) -> Result<(), String>;
+
+    #[ink(message, selector = 0xB62722D8)]
+    fn add_data_feed(&mut self, feed_address: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x1D993973)]
+    fn remove_data_feed(&mut self, feed_address: AccountId) -> Result<(), String>;
+
+    #[ink(message, selector = 0x36900600)]
+    fn set_elected_data_feeds(&mut self, feeds: Vec<AccountId>) -> Result<(), String>;
+
+    #[ink(message, selector = 0x07074A1D)]
+    fn get_alarm(&self, alarm_id: u64) -> Option<Alarm>;
+
+    #[ink(message, selector = 0x20C8850B)]
+    fn set_reward_config(&mut self, config: RewardConfig) -> Result<(), String>;
+
+    #[ink(message, selector = 0xF56807E7)]
+    fn set_feed_rate_limit(&mut self, feed: Option<AccountId>, limit: Option<FeedRateLimit>) -> Result<(), String>;
+
+    #[ink(message, selector = 0xB8FF6C00)]
+    fn set_caller_permissions(&mut self, caller: AccountId, permissions: Option<CallerPermissions>) -> Result<(), String>;
+
+    #[ink(message, selector = 0xD6597D13)]
+    fn set_type_verifier(&mut self, event_type: GridEventType, verifier: AccountId, approved: bool) -> Result<(), String>;
+}
+
+/// Read surface of the governance contract
+#[ink::trait_definition]
+pub trait GovernanceApi {
+    /// (min_voting_power, voting_duration_blocks, quorum_percentage)
+    #[ink(message, selector = 0xFFCC1F1E)]
+    fn get_governance_params(&self) -> (Balance, u64, u32);
+
+    #[ink(message, selector = 0x3CB54501)]
+    fn get_vote_nonce(&self, voter: AccountId) -> u64;
+
+    #[ink(message, selector = 0xCC049358)]
+    fn get_guardian(&self) -> Option<AccountId>;
+}
+
+/// Call builder for a `TokenApi` contract: `TokenRef::from(address)`
+pub type TokenRef = ink::contract_ref!(TokenApi, ink::env::DefaultEnvironment);
+/// Call builder for a `RegistryApi` contract
+pub type RegistryRef = ink::contract_ref!(RegistryApi, ink::env::DefaultEnvironment);
+/// Call builder for a `GridApi` contract
+pub type GridRef = ink::contract_ref!(GridApi, ink::env::DefaultEnvironment);
+/// Call builder for a `GovernanceApi` contract
+pub type GovernanceRef = ink::contract_ref!(GovernanceApi, ink::env::DefaultEnvironment);
@@ -1,10 +1,33 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod env;
 pub mod types;
 pub mod traits;
 pub mod constants;
+pub mod events;
+pub mod signatures;
+pub mod nonces;
+pub mod merkle;
+pub mod payloads;
+pub mod address_book;
+pub mod correlation;
+pub mod interfaces;
+pub mod clients;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod units;
 
 // Re-export everything for easy importing
+pub use env::{bytes_to_ink_account, ink_account_to_bytes, FromBytes32, IntoBytes32};
 pub use types::*;
 pub use traits::*;
-pub use constants::*;
\ No newline at end of file
+pub use constants::*;
+pub use units::*;
+pub use interfaces::*;
+pub use clients::*;
+pub use signatures::verify_signature;
+pub use nonces::{signed_message_hash, NonceDomain, NonceRegistry};
+pub use merkle::{claim_leaf_hash, hash_pair, verify_claim_proof, ClaimLeaf};
+pub use payloads::{decode_participation_payload, ParticipationPayload, PeakWindowData, RampData, MAX_EXTRA_DATA_LEN};
+pub use correlation::{event_correlation_id, CorrelationId};
+pub use address_book::{AddressChangeError, AddressTimelock, ExternalContract, PendingAddressChange, ADDRESS_CHANGE_DELAY_MS};
@@ -0,0 +1,42 @@
+//! Merkle commitments for batch reward claims.
+//!
+//! A verifier settles a large event by committing a single root over one leaf
+//! per participant instead of verifying each participation on chain. Leaves
+//! are hashed together with the event ID so a proof for one event cannot be
+//! replayed against another, and inner nodes hash their children in sorted
+//! order so a proof is just the list of sibling hashes from leaf to root.
+
+use crate::types::Balance;
+use crate::units::WattHours;
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// One participant's verified outcome in a committed event
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub struct ClaimLeaf {
+    pub participant: AccountId,
+    pub reduction_wh: WattHours,
+    pub reward: Balance,
+}
+
+/// Leaf hash: blake2_256(SCALE(event_id, leaf))
+pub fn claim_leaf_hash(event_id: u64, leaf: &ClaimLeaf) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(event_id, leaf), &mut output);
+    output
+}
+
+/// Parent of two nodes, independent of their order
+pub fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(left, right), &mut output);
+    output
+}
+
+/// Whether `proof` links `leaf` of `event_id` to `root`
+pub fn verify_claim_proof(root: [u8; 32], event_id: u64, leaf: &ClaimLeaf, proof: &[[u8; 32]]) -> bool {
+    proof.iter().fold(claim_leaf_hash(event_id, leaf), |node, sibling| hash_pair(node, *sibling)) == root
+}
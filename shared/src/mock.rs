@@ -0,0 +1,541 @@
+//! Programmable stand-ins for the PowerGrid contracts in off-chain unit tests.
+//!
+//! `install` a mock at a dependency's address and `Connect` hands the contract
+//! under test a client backed by that state; without one, `connect` returns
+//! `None` and the cross-contract branch is skipped. Every call is logged in
+//! `calls`, and a call named in `failing` returns an error instead of running.
+//! An `on_call` hook runs before each call is answered, standing in for a
+//! callback into the contract under test: it sees that contract's storage as a
+//! reentrant call would. State lives per test thread, like the ink off-chain
+//! environment.
+
+use crate::clients::{Connect, GridClient, RegistryClient, TokenClient};
+use crate::interfaces::PSP22Error;
+use crate::{parse_firmware_version, Alarm, CorrelationId, CallerPermissions, CustomEventTypeDescriptor, Device, DeviceSla, DeviceType, DeviceTypePolicy, EmissionBucket, EmissionSchedule, FeedRateLimit, GridEventType, RewardConfig, TriggerRuleParams};
+use ink::primitives::AccountId;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::env::Balance;
+
+/// Error returned by calls listed in `failing`
+pub const MOCK_FAILURE: &str = "MockFailure";
+
+/// Callback run with the call name before a mock answers it
+type CallHook = Box<dyn FnMut(&'static str)>;
+
+macro_rules! mock_contract {
+    ($mock:ident, $state:ident, $registry:ident, $hooks:ident) => {
+        thread_local! {
+            static $registry: RefCell<HashMap<AccountId, $state>> = RefCell::new(HashMap::new());
+            static $hooks: RefCell<HashMap<AccountId, CallHook>> = RefCell::new(HashMap::new());
+        }
+
+        /// Client for an installed mock
+        #[derive(Debug, Clone, Copy)]
+        pub struct $mock {
+            address: AccountId,
+        }
+
+        impl $mock {
+            /// Answer calls to `address` with `state`
+            pub fn install(address: AccountId, state: $state) {
+                $registry.with(|mocks| mocks.borrow_mut().insert(address, state));
+            }
+
+            /// Current state of the mock at `address`
+            pub fn state(address: AccountId) -> $state {
+                Self::update(address, |state| state.clone())
+            }
+
+            /// Inspect or reprogram the mock at `address`
+            pub fn update<R>(address: AccountId, f: impl FnOnce(&mut $state) -> R) -> R {
+                $registry.with(|mocks| {
+                    let mut mocks = mocks.borrow_mut();
+                    let state = mocks.get_mut(&address).expect("no mock installed at this address");
+                    f(state)
+                })
+            }
+
+            /// Run `hook` before every call to the mock at `address`, replacing any earlier hook
+            pub fn on_call(address: AccountId, hook: impl FnMut(&'static str) + 'static) {
+                $hooks.with(|hooks| hooks.borrow_mut().insert(address, Box::new(hook)));
+            }
+
+            fn run_hook(&self, call: &'static str) {
+                // Taken out while it runs so the hook may itself use this mock
+                let hook = $hooks.with(|hooks| hooks.borrow_mut().remove(&self.address));
+                if let Some(mut hook) = hook {
+                    hook(call);
+                    $hooks.with(|hooks| {
+                        hooks.borrow_mut().entry(self.address).or_insert(hook);
+                    });
+                }
+            }
+
+            /// Log `call` and run `f`, or fail if the call is programmed to
+            fn call<R>(&self, call: &'static str, f: impl FnOnce(&mut $state) -> R) -> Result<R, ()> {
+                self.run_hook(call);
+                Self::update(self.address, |state| {
+                    state.calls.push(call);
+                    if state.failing.contains(call) {
+                        return Err(());
+                    }
+                    Ok(f(state))
+                })
+            }
+
+            fn query<R>(&self, call: &'static str, f: impl FnOnce(&$state) -> R) -> R {
+                self.run_hook(call);
+                Self::update(self.address, |state| {
+                    state.calls.push(call);
+                    f(state)
+                })
+            }
+        }
+
+        impl Connect for $mock {
+            fn connect(address: AccountId) -> Option<Self> {
+                $registry
+                    .with(|mocks| mocks.borrow().contains_key(&address))
+                    .then_some(Self { address })
+            }
+        }
+    };
+}
+
+fn this() -> AccountId {
+    ink::env::account_id::<ink::env::DefaultEnvironment>()
+}
+
+fn token_failure() -> PSP22Error {
+    PSP22Error::Custom(MOCK_FAILURE.into())
+}
+
+/// Ledger and roles of a mock PGT token
+#[derive(Debug, Clone, Default)]
+pub struct MockTokenState {
+    pub balances: HashMap<AccountId, Balance>,
+    pub total_supply: Balance,
+    pub minters: HashSet<AccountId>,
+    pub lockers: HashSet<AccountId>,
+    pub locks: HashMap<AccountId, (Balance, u32)>,
+    pub guardian: Option<AccountId>,
+    /// (emergency minter, cap per quarter)
+    pub emergency_budget: (Option<AccountId>, Balance),
+    pub emission_schedule: Option<EmissionSchedule>,
+    /// `(account, amount, correlation_id)` per `mint_correlated`
+    pub correlated_mints: Vec<(AccountId, Balance, CorrelationId)>,
+    pub minter_buckets: HashMap<AccountId, EmissionBucket>,
+    pub failing: HashSet<&'static str>,
+    pub calls: Vec<&'static str>,
+}
+
+impl MockTokenState {
+    pub fn balance(&self, account: AccountId) -> Balance {
+        self.balances.get(&account).copied().unwrap_or(0)
+    }
+
+    fn move_balance(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+        let from_balance = self.balance(from);
+        if from_balance < value {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+        self.balances.insert(from, from_balance - value);
+        *self.balances.entry(to).or_default() += value;
+        Ok(())
+    }
+}
+
+mock_contract!(MockToken, MockTokenState, MOCK_TOKENS, MOCK_TOKEN_HOOKS);
+
+impl TokenClient for MockToken {
+    fn total_supply(&self) -> Balance {
+        self.query("total_supply", |state| state.total_supply)
+    }
+
+    fn balance_of(&self, owner: AccountId) -> Balance {
+        self.query("balance_of", |state| state.balance(owner))
+    }
+
+    fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+        self.call("transfer", |state| state.move_balance(this(), to, value))
+            .unwrap_or_else(|()| Err(token_failure()))
+    }
+
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+        self.call("transfer_from", |state| state.move_balance(from, to, value))
+            .unwrap_or_else(|()| Err(token_failure()))
+    }
+
+    fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        self.call("mint", |state| {
+            *state.balances.entry(account).or_default() += amount;
+            state.total_supply += amount;
+        })
+        .map_err(|()| token_failure())
+    }
+
+    fn mint_correlated(&mut self, account: AccountId, amount: Balance, correlation_id: CorrelationId) -> Result<(), PSP22Error> {
+        self.call("mint_correlated", |state| {
+            *state.balances.entry(account).or_default() += amount;
+            state.total_supply += amount;
+            state.correlated_mints.push((account, amount, correlation_id));
+        })
+        .map_err(|()| token_failure())
+    }
+
+    fn is_minter(&self, account: AccountId) -> bool {
+        self.query("is_minter", |state| state.minters.contains(&account))
+    }
+
+    fn add_minter(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        self.call("add_minter", |state| {
+            state.minters.insert(account);
+        })
+        .map_err(|()| token_failure())
+    }
+
+    fn remove_minter(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        self.call("remove_minter", |state| {
+            state.minters.remove(&account);
+        })
+        .map_err(|()| token_failure())
+    }
+
+    fn is_locker(&self, account: AccountId) -> bool {
+        self.query("is_locker", |state| state.lockers.contains(&account))
+    }
+
+    fn lock(&mut self, account: AccountId, amount: Balance, until_block: u32) -> Result<(), PSP22Error> {
+        self.call("lock", |state| {
+            state.locks.insert(account, (amount, until_block));
+        })
+        .map_err(|()| token_failure())
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), PSP22Error> {
+        self.call("set_guardian", |state| state.guardian = guardian)
+            .map_err(|()| token_failure())
+    }
+
+    fn set_emergency_budget(&mut self, minter: Option<AccountId>, cap_per_quarter: Balance) -> Result<(), PSP22Error> {
+        self.call("set_emergency_budget", |state| state.emergency_budget = (minter, cap_per_quarter))
+            .map_err(|()| token_failure())
+    }
+
+    fn set_emission_schedule(&mut self, schedule: Option<EmissionSchedule>) -> Result<(), PSP22Error> {
+        self.call("set_emission_schedule", |state| state.emission_schedule = schedule)
+            .map_err(|()| token_failure())
+    }
+
+    fn set_minter_emission_bucket(&mut self, minter: AccountId, bucket: EmissionBucket) -> Result<(), PSP22Error> {
+        self.call("set_minter_emission_bucket", |state| {
+            state.minter_buckets.insert(minter, bucket);
+        })
+        .map_err(|()| token_failure())
+    }
+}
+
+/// Devices and recorded updates of a mock resource registry
+#[derive(Debug, Clone, Default)]
+pub struct MockRegistryState {
+    pub devices: HashMap<AccountId, Device>,
+    pub slas: HashMap<AccountId, DeviceSla>,
+    /// Effective payout address per device (the device key when absent)
+    pub payout_addresses: HashMap<AccountId, AccountId>,
+    /// Unavailable `(start, end)` windows per device
+    pub unavailable_windows: HashMap<AccountId, Vec<(u64, u64)>>,
+    /// Stake slashed per (device, statement period)
+    pub slashed: HashMap<(AccountId, u64), Balance>,
+    /// Hardware-bound keys registered at attestation
+    pub device_keys: HashMap<AccountId, AccountId>,
+    pub reputation_threshold: u32,
+    /// Minimum firmware version per (manufacturer, model)
+    pub min_firmware_versions: HashMap<(String, String), String>,
+    /// Accounts under a blacklisting still in force
+    pub blacklisted: HashSet<AccountId>,
+    pub authorized_callers: HashSet<AccountId>,
+    pub governance_address: Option<AccountId>,
+    pub guardian: Option<AccountId>,
+    pub min_stake: Balance,
+    /// `(account, amount)` per `increase_stake_for`
+    pub stake_increases: Vec<(AccountId, Balance)>,
+    /// `(account, event_id, energy_contributed, success)` per `record_event_performance`
+    pub performance_records: Vec<(AccountId, u64, u64, bool)>,
+    /// Correlation ID of each entry of `performance_records`
+    pub performance_correlations: Vec<CorrelationId>,
+    pub failing: HashSet<&'static str>,
+    pub calls: Vec<&'static str>,
+}
+
+mock_contract!(MockRegistry, MockRegistryState, MOCK_REGISTRIES, MOCK_REGISTRY_HOOKS);
+
+impl RegistryClient for MockRegistry {
+    fn get_device(&self, account: AccountId) -> Option<Device> {
+        self.query("get_device", |state| state.devices.get(&account).cloned())
+    }
+
+    fn get_device_reputation(&self, account: AccountId) -> Option<u32> {
+        self.query("get_device_reputation", |state| state.devices.get(&account).map(|device| device.reputation))
+    }
+
+    fn get_device_sla(&self, account: AccountId) -> Option<DeviceSla> {
+        self.query("get_device_sla", |state| state.slas.get(&account).cloned())
+    }
+
+    fn get_reputation_threshold(&self) -> u32 {
+        self.query("get_reputation_threshold", |state| state.reputation_threshold)
+    }
+
+    fn is_eligible(&self, account: AccountId) -> bool {
+        self.query("is_eligible", |state| {
+            !state.blacklisted.contains(&account) && state.devices.get(&account).is_some_and(|device| {
+                let meta = &device.metadata;
+                let firmware_ok = match state.min_firmware_versions.get(&(meta.manufacturer.clone(), meta.model.clone())) {
+                    None => true,
+                    Some(min) => match (parse_firmware_version(&meta.firmware_version), parse_firmware_version(min)) {
+                        (Some(current), Some(required)) => current >= required,
+                        _ => false,
+                    },
+                };
+                device.active && device.reputation >= state.reputation_threshold && firmware_ok
+            })
+        })
+    }
+
+    fn is_blacklisted(&self, account: AccountId) -> bool {
+        self.query("is_blacklisted", |state| state.blacklisted.contains(&account))
+    }
+
+    fn get_min_stake(&self) -> Balance {
+        self.query("get_min_stake", |state| state.min_stake)
+    }
+
+    fn get_payout_address(&self, account: AccountId) -> AccountId {
+        self.query("get_payout_address", |state| state.payout_addresses.get(&account).copied().unwrap_or(account))
+    }
+
+    fn get_unavailable_time(&self, account: AccountId, from: u64, to: u64) -> u64 {
+        self.query("get_unavailable_time", |state| {
+            state.unavailable_windows.get(&account).map_or(0, |windows| {
+                windows.iter().map(|(start, end)| (*end).min(to).saturating_sub((*start).max(from))).sum()
+            })
+        })
+    }
+
+    fn get_slashed_stake(&self, account: AccountId, period: u64) -> Balance {
+        self.query("get_slashed_stake", |state| state.slashed.get(&(account, period)).copied().unwrap_or(0))
+    }
+
+    fn get_device_key(&self, account: AccountId) -> Option<AccountId> {
+        self.query("get_device_key", |state| state.device_keys.get(&account).copied())
+    }
+
+    fn is_authorized_caller(&self, account: AccountId) -> bool {
+        self.query("is_authorized_caller", |state| state.authorized_callers.contains(&account))
+    }
+
+    fn get_governance_address(&self) -> Option<AccountId> {
+        self.query("get_governance_address", |state| state.governance_address)
+    }
+
+    fn increase_stake_for(&mut self, account: AccountId, amount: Balance) -> Result<(), String> {
+        self.call("increase_stake_for", |state| state.stake_increases.push((account, amount)))
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn record_event_performance(&mut self, account: AccountId, event_id: u64, energy_contributed: u64, success: bool, correlation_id: CorrelationId) -> Result<(), String> {
+        self.call("record_event_performance", |state| {
+            state.performance_records.push((account, event_id, energy_contributed, success));
+            state.performance_correlations.push(correlation_id);
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        self.call("add_authorized_caller", |state| {
+            state.authorized_callers.insert(caller);
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        self.call("remove_authorized_caller", |state| {
+            state.authorized_callers.remove(&caller);
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_device_type_policy(&mut self, _device_type: DeviceType, _policy: Option<DeviceTypePolicy>) -> Result<(), String> {
+        self.call("set_device_type_policy", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_min_firmware_version(&mut self, manufacturer: String, model: String, version: Option<String>) -> Result<(), String> {
+        self.call("set_min_firmware_version", |state| match version {
+            Some(version) => { state.min_firmware_versions.insert((manufacturer, model), version); }
+            None => { state.min_firmware_versions.remove(&(manufacturer, model)); }
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String> {
+        self.call("set_guardian", |state| state.guardian = guardian)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn update_min_stake(&mut self, new_min_stake: Balance) -> Result<(), String> {
+        self.call("update_min_stake", |state| state.min_stake = new_min_stake)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn update_reputation_threshold(&mut self, new_threshold: u32) -> Result<(), String> {
+        self.call("update_reputation_threshold", |state| state.reputation_threshold = new_threshold)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+}
+
+/// Recorded governance hooks of a mock grid service
+#[derive(Debug, Clone)]
+pub struct MockGridState {
+    pub governance_address: AccountId,
+    pub authorized_callers: HashSet<AccountId>,
+    pub guardian: Option<AccountId>,
+    pub default_compensation_rate: Balance,
+    pub auto_trigger_enabled: bool,
+    pub next_rule_id: u64,
+    pub caller_permissions: HashMap<AccountId, CallerPermissions>,
+    pub type_verifiers: Vec<(GridEventType, AccountId)>,
+    pub data_feeds: Vec<AccountId>,
+    pub alarms: HashMap<u64, Alarm>,
+    pub failing: HashSet<&'static str>,
+    pub calls: Vec<&'static str>,
+}
+
+impl Default for MockGridState {
+    fn default() -> Self {
+        Self {
+            governance_address: AccountId::from([0u8; 32]),
+            authorized_callers: HashSet::new(),
+            guardian: None,
+            default_compensation_rate: 0,
+            auto_trigger_enabled: false,
+            next_rule_id: 0,
+            caller_permissions: HashMap::new(),
+            type_verifiers: Vec::new(),
+            data_feeds: Vec::new(),
+            alarms: HashMap::new(),
+            failing: HashSet::new(),
+            calls: Vec::new(),
+        }
+    }
+}
+
+mock_contract!(MockGrid, MockGridState, MOCK_GRIDS, MOCK_GRID_HOOKS);
+
+impl GridClient for MockGrid {
+    fn get_governance_address(&self) -> AccountId {
+        self.query("get_governance_address", |state| state.governance_address)
+    }
+
+    fn get_default_compensation_rate(&self) -> Balance {
+        self.query("get_default_compensation_rate", |state| state.default_compensation_rate)
+    }
+
+    fn add_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        self.call("add_authorized_caller", |state| {
+            state.authorized_callers.insert(caller);
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn remove_authorized_caller(&mut self, caller: AccountId) -> Result<(), String> {
+        self.call("remove_authorized_caller", |state| {
+            state.authorized_callers.remove(&caller);
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn update_default_compensation_rate(&mut self, new_rate: Balance) -> Result<(), String> {
+        self.call("update_default_compensation_rate", |state| state.default_compensation_rate = new_rate)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_custom_event_type(&mut self, _type_id: u16, _descriptor: Option<CustomEventTypeDescriptor>) -> Result<(), String> {
+        self.call("set_custom_event_type", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<(), String> {
+        self.call("set_guardian", |state| state.guardian = guardian)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_auto_trigger_enabled(&mut self, enabled: bool) -> Result<(), String> {
+        self.call("set_auto_trigger_enabled", |state| state.auto_trigger_enabled = enabled)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn create_trigger_rule(&mut self, _params: TriggerRuleParams) -> Result<u64, String> {
+        self.call("create_trigger_rule", |state| {
+            let rule_id = state.next_rule_id;
+            state.next_rule_id += 1;
+            rule_id
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_trigger_rule_active(&mut self, _rule_id: u64, _active: bool) -> Result<(), String> {
+        self.call("set_trigger_rule_active", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn remove_trigger_rule(&mut self, _rule_id: u64) -> Result<(), String> {
+        self.call("remove_trigger_rule", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn add_data_feed(&mut self, _feed_address: AccountId) -> Result<(), String> {
+        self.call("add_data_feed", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn remove_data_feed(&mut self, _feed_address: AccountId) -> Result<(), String> {
+        self.call("remove_data_feed", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_elected_data_feeds(&mut self, feeds: Vec<AccountId>) -> Result<(), String> {
+        self.call("set_elected_data_feeds", |state| state.data_feeds = feeds)
+            .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn get_alarm(&self, alarm_id: u64) -> Option<Alarm> {
+        self.query("get_alarm", |state| state.alarms.get(&alarm_id).cloned())
+    }
+
+    fn set_reward_config(&mut self, _config: RewardConfig) -> Result<(), String> {
+        self.call("set_reward_config", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_feed_rate_limit(&mut self, _feed: Option<AccountId>, _limit: Option<FeedRateLimit>) -> Result<(), String> {
+        self.call("set_feed_rate_limit", |_| ()).map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_caller_permissions(&mut self, caller: AccountId, permissions: Option<CallerPermissions>) -> Result<(), String> {
+        self.call("set_caller_permissions", |state| match permissions {
+            Some(permissions) => {
+                state.caller_permissions.insert(caller, permissions);
+            }
+            None => {
+                state.caller_permissions.remove(&caller);
+            }
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+
+    fn set_type_verifier(&mut self, event_type: GridEventType, verifier: AccountId, approved: bool) -> Result<(), String> {
+        self.call("set_type_verifier", |state| {
+            state.type_verifiers.retain(|entry| *entry != (event_type.clone(), verifier));
+            if approved {
+                state.type_verifiers.push((event_type, verifier));
+            }
+        })
+        .map_err(|()| MOCK_FAILURE.into())
+    }
+}
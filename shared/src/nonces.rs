@@ -0,0 +1,65 @@
+//! Replay protection for off-chain signed operations.
+//!
+//! Every signed operation consumes the next nonce of its signer in its own
+//! domain, so a permit cannot be replayed as a ballot and vice versa. The
+//! message a signer signs is built by `signed_message_hash`, which binds the
+//! domain, the verifying contract and the nonce to the operation payload.
+
+use ink::primitives::AccountId;
+use ink::storage::Mapping;
+use scale::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Kind of signed operation a nonce sequence belongs to
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum NonceDomain {
+    /// Token allowance permits
+    Permit,
+    /// Governance ballots submitted by relayers
+    Vote,
+    /// Manufacturer firmware attestations, sequenced per device
+    FirmwareAttestation,
+    /// Signed oracle or telemetry feeds
+    Feed,
+    /// Generic meta-transactions
+    MetaTransaction,
+    /// Device liveness proofs; the nonce is the liveness epoch
+    Liveness,
+}
+
+/// Per-account, per-domain sequential nonces; embed as a contract storage field
+#[ink::storage_item]
+#[derive(Default, Debug)]
+pub struct NonceRegistry {
+    nonces: Mapping<(AccountId, NonceDomain), u64>,
+}
+
+impl NonceRegistry {
+    /// Next nonce `account` must sign in `domain`
+    pub fn current(&self, account: AccountId, domain: NonceDomain) -> u64 {
+        self.nonces.get((account, domain)).unwrap_or(0)
+    }
+
+    /// Consume `nonce` if it is the next expected one; false leaves the sequence untouched
+    pub fn use_nonce(&mut self, account: AccountId, domain: NonceDomain, nonce: u64) -> bool {
+        let expected = self.current(account, domain);
+        if nonce != expected {
+            return false;
+        }
+        self.nonces.insert((account, domain), &expected.saturating_add(1));
+        true
+    }
+}
+
+/// Message signed for an operation: blake2_256(SCALE(domain, verifying_contract, nonce, payload))
+pub fn signed_message_hash<T: Encode>(
+    domain: NonceDomain,
+    verifying_contract: AccountId,
+    nonce: u64,
+    payload: &T,
+) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(domain, verifying_contract, nonce, payload), &mut output);
+    output
+}
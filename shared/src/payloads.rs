@@ -0,0 +1,71 @@
+//! Event-type-specific evidence attached to a participation.
+//!
+//! A participation may carry an `extra_data` blob: the SCALE encoding of the
+//! payload its event type defines. Frequency regulation reports ramp data,
+//! peak shaving the peak window it shaved. Built-in types without a payload
+//! schema accept no data; custom types carry opaque bytes their operator
+//! interprets off chain.
+
+use crate::types::{GridEventType, Timestamp};
+use ink::prelude::vec::Vec;
+use scale::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Largest `extra_data` blob accepted on a participation
+pub const MAX_EXTRA_DATA_LEN: usize = 256;
+
+/// How fast a frequency regulation resource followed the signal
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct RampData {
+    /// Delay between the signal and the first power change
+    pub response_delay_ms: u32,
+    pub ramp_up_watts_per_second: u32,
+    pub ramp_down_watts_per_second: u32,
+}
+
+/// Window of a site's demand peak and the load held during it
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct PeakWindowData {
+    pub peak_start: Timestamp,
+    pub peak_end: Timestamp,
+    pub peak_demand_watts: u64,
+}
+
+/// Decoded `extra_data` of a participation
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub enum ParticipationPayload {
+    Ramp(RampData),
+    PeakWindow(PeakWindowData),
+    /// Custom event types: bytes interpreted by the type's operator
+    Opaque(Vec<u8>),
+}
+
+/// Decode and sanity-check `extra_data` against the payload its event type defines
+pub fn decode_participation_payload(event_type: &GridEventType, data: &[u8]) -> Result<ParticipationPayload, &'static str> {
+    if data.len() > MAX_EXTRA_DATA_LEN {
+        return Err("Extra data too large");
+    }
+    let mut input = data;
+    let payload = match event_type {
+        GridEventType::FrequencyRegulation => {
+            let ramp = RampData::decode(&mut input).map_err(|_| "Malformed ramp data")?;
+            if ramp.ramp_up_watts_per_second == 0 && ramp.ramp_down_watts_per_second == 0 {
+                return Err("Ramp data reports no ramp");
+            }
+            ParticipationPayload::Ramp(ramp)
+        }
+        GridEventType::PeakShaving => {
+            let window = PeakWindowData::decode(&mut input).map_err(|_| "Malformed peak window data")?;
+            if window.peak_start >= window.peak_end {
+                return Err("Peak window ends before it starts");
+            }
+            ParticipationPayload::PeakWindow(window)
+        }
+        GridEventType::Custom(_) => return Ok(ParticipationPayload::Opaque(data.to_vec())),
+        _ => return Err("Event type takes no extra data"),
+    };
+    if !input.is_empty() {
+        return Err("Trailing bytes in extra data");
+    }
+    Ok(payload)
+}
@@ -0,0 +1,37 @@
+//! Signature checks for plain and contract accounts.
+//!
+//! Plain accounts sign with sr25519. Smart wallets and multisigs cannot, so a
+//! signer that is a contract is asked instead, ERC-1271 style: it must expose
+//! an `is_valid_signature(hash: [u8; 32], signature: Vec<u8>) -> [u8; 4]`
+//! message returning `VALID_SIGNATURE_MAGIC` for signatures it approves.
+
+use ink::env::call::{build_call, ExecutionInput, Selector};
+use ink::env::DefaultEnvironment;
+use ink::primitives::AccountId;
+
+/// Selector of the `is_valid_signature` message contract accounts implement
+pub const IS_VALID_SIGNATURE_SELECTOR: [u8; 4] = ink::selector_bytes!("is_valid_signature");
+
+/// Value `is_valid_signature` returns to approve a signature (its own selector)
+pub const VALID_SIGNATURE_MAGIC: [u8; 4] = IS_VALID_SIGNATURE_SELECTOR;
+
+/// Whether `signer` signed `hash`: contract accounts are asked through
+/// `is_valid_signature`, other accounts must provide a 64-byte sr25519 signature
+pub fn verify_signature(signer: AccountId, hash: [u8; 32], signature: &[u8]) -> bool {
+    if ink::env::is_contract::<DefaultEnvironment>(&signer) {
+        let response = build_call::<DefaultEnvironment>()
+            .call(signer)
+            .exec_input(
+                ExecutionInput::new(Selector::new(IS_VALID_SIGNATURE_SELECTOR))
+                    .push_arg(hash)
+                    .push_arg(signature),
+            )
+            .returns::<[u8; 4]>()
+            .try_invoke();
+        return matches!(response, Ok(Ok(magic)) if magic == VALID_SIGNATURE_MAGIC);
+    }
+    let Ok(signature) = <[u8; 64]>::try_from(signature) else {
+        return false;
+    };
+    ink::env::sr25519_verify(&signature, &hash, signer.as_ref()).is_ok()
+}
@@ -1,26 +1,53 @@
 #![allow(clippy::cast_possible_truncation)]
-use ink::prelude::string::String;
+use ink::prelude::{string::String, vec::Vec};
+use crate::units::{Kilowatts, Megawatts, WattHours};
 use scale::{Decode, Encode};
 use scale_info::TypeInfo;
 
 #[cfg(feature = "std")]
 use ink::storage::traits::StorageLayout;
 
-// Use [u8; 32] directly - no type alias to avoid confusion
-pub type Balance = u128;
-pub type Timestamp = u64;
+use crate::env::AccountId;
+pub use crate::env::{Balance, Timestamp};
 
-// Helper functions for AccountId conversion
-pub fn ink_account_to_bytes(account: ink::primitives::AccountId) -> [u8; 32] {
-    let bytes: &[u8] = account.as_ref();
-    bytes.try_into().unwrap_or([0u8; 32])
+/// Parse a "major.minor.patch" firmware version (missing parts default to 0)
+pub fn parse_firmware_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() { Some(p) => p.parse().ok()?, None => 0 };
+    let patch = match parts.next() { Some(p) => p.parse().ok()?, None => 0 };
+    if parts.next().is_some() { return None; }
+    Some((major, minor, patch))
 }
 
-pub fn bytes_to_ink_account(bytes: [u8; 32]) -> ink::primitives::AccountId {
-    ink::primitives::AccountId::from(bytes)
+/// Semantic version of a deployed contract's logic
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct ContractVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ContractVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// A client built against `required` can talk to this version: same major, no older minor
+    pub fn is_compatible_with(&self, required: &ContractVersion) -> bool {
+        self.major == required.major && (self.minor, self.patch) >= (required.minor, required.patch)
+    }
+}
+
+/// Commitment to a device's precise location, revealed only for audits
+pub fn location_commitment(salt: [u8; 32], location: &str) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(salt, location), &mut output);
+    output
 }
 
-#[derive(Decode, Encode, Clone, TypeInfo, PartialEq, Debug)]
+#[derive(Decode, Encode, Clone, TypeInfo, PartialEq, Eq, Debug)]
 #[cfg_attr(feature = "std", derive(StorageLayout))]
 pub enum DeviceType {
     SmartPlug,
@@ -37,13 +64,27 @@ pub enum DeviceType {
 pub struct DeviceMetadata {
     pub device_type: DeviceType,
     pub capacity_watts: u64,
-    pub location: String,
+    /// blake2_256(SCALE(salt, location)) - the plaintext location stays off-chain
+    pub location_commitment: [u8; 32],
+    /// Coarse zone (geohash prefix) used for zone-targeted events
+    pub zone: String,
     pub manufacturer: String,
     pub model: String,
     pub firmware_version: String,
     pub installation_date: Timestamp,
 }
 
+/// Registration policy for a device type (governance managed)
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct DeviceTypePolicy {
+    pub enabled: bool,
+    /// Minimum stake for this type, applied on top of the global minimum
+    pub min_stake: Balance,
+    /// Maximum registrable capacity in watts (0 = unlimited)
+    pub max_capacity_watts: u64,
+}
+
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
 #[cfg_attr(feature = "std", derive(StorageLayout))]
 pub struct Device {
@@ -59,6 +100,19 @@ pub struct Device {
     pub last_updated: Timestamp,
 }
 
+/// Availability of a device over the trailing SLA window
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct DeviceSla {
+    /// Uptime over the window, 0-100
+    pub uptime_percent: u8,
+    /// Heartbeat intervals observed in the window (estimated across the window boundary)
+    pub heartbeats: u32,
+    /// Heartbeat intervals expected in the window
+    pub expected_heartbeats: u32,
+    pub last_heartbeat: Timestamp,
+}
+
 #[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(StorageLayout))]
 pub enum GridEventType {
@@ -67,6 +121,249 @@ pub enum GridEventType {
     PeakShaving,
     LoadBalancing,
     Emergency,
+    /// Operator-defined event class, described in GridService's custom type registry
+    Custom(u16),
+}
+
+/// Governance-managed descriptor of a custom grid event type
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct CustomEventTypeDescriptor {
+    pub name: String,
+    pub active: bool,
+    /// Defaults used when an event of this type is created with zero values
+    pub default_duration_minutes: u64,
+    pub default_compensation_rate: Balance,
+    pub default_target_reduction_kw: Kilowatts,
+}
+
+/// Parameters for creating GridService auto-trigger rules
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct TriggerRuleParams {
+    pub event_type: GridEventType,
+    pub load_threshold_percentage: u8,
+    pub frequency_low_threshold: u32,
+    pub frequency_high_threshold: u32,
+    pub compensation_rate: Balance,
+    pub target_reduction_percentage: u8,
+    pub duration_minutes: u64,
+    pub zone: Option<String>,
+}
+
+impl TriggerRuleParams {
+    /// Whether a reading crosses the rule's load or frequency thresholds
+    pub fn thresholds_crossed(&self, load_percentage: u8, frequency_hz: u32) -> bool {
+        load_percentage >= self.load_threshold_percentage
+            || frequency_hz < self.frequency_low_threshold
+            || frequency_hz > self.frequency_high_threshold
+    }
+
+    /// Reduction an event triggered by the rule targets: its percentage of the current
+    /// load, or 1 MW when no load reading is available
+    pub fn target_reduction(&self, load: Option<Megawatts>) -> Kilowatts {
+        load.and_then(Megawatts::to_kilowatts)
+            .map(|load| load.percent(self.target_reduction_percentage))
+            .unwrap_or(Kilowatts(1000))
+    }
+}
+
+/// How many auto-created events may be active at once per (event type, zone)
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum AutoEventConcurrency {
+    /// Every matching rule creates its own event
+    Unlimited,
+    /// At most one active auto-created event per event type and zone
+    OnePerTypeAndZone,
+}
+
+impl AutoEventConcurrency {
+    /// Whether a rule may fire while an event it triggered for the same (type, zone) still runs
+    pub fn allows_duplicates(self) -> bool {
+        self == AutoEventConcurrency::Unlimited
+    }
+}
+
+/// Step of the reward pipeline
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum RewardModifierKind {
+    /// `efficiency_bonus_bps` when delivery beats the event target
+    EfficiencyBonus,
+    /// Flexibility score (0-1000) mapped to a 500-1500 multiplier within the configured bounds
+    Flexibility,
+    /// `reputation_base_bps` plus `reputation_step_bps` per reputation point
+    Reputation,
+    /// Loyalty bonus for consecutive events
+    Streak,
+    /// Settlement-time scaling of pro-rata events to their target; event budgets apply regardless
+    ProRata,
+}
+
+/// Pipeline entry; disabled entries are skipped
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct RewardModifier {
+    pub kind: RewardModifierKind,
+    pub enabled: bool,
+}
+
+/// Per-participant inputs of the reward pipeline
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct RewardInputs {
+    pub delivered: WattHours,
+    pub target: WattHours,
+    pub flexibility_score: Option<u16>,
+    pub reputation: Option<u32>,
+    pub streak: u32,
+}
+
+/// One applied pipeline step
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct RewardStep {
+    pub kind: RewardModifierKind,
+    /// Multiplier applied, in basis points (10_000 = unchanged)
+    pub multiplier_bps: u32,
+    /// Reward after this step
+    pub reward: Balance,
+}
+
+/// How a participant's reward was derived from the base compensation
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct RewardBreakdown {
+    /// Delivered energy priced at the event's base rate
+    pub base_reward: Balance,
+    pub steps: Vec<RewardStep>,
+    /// Reward booked for the participant, after program budget caps
+    pub final_reward: Balance,
+}
+
+/// Reward shaping applied by GridService on top of the base compensation
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct RewardConfig {
+    /// Bonus when a participant exceeds the event target, in basis points
+    pub efficiency_bonus_bps: u32,
+    /// Flexibility multiplier bounds, in thousandths (1000 = 100%)
+    pub min_flexibility_multiplier: u32,
+    pub max_flexibility_multiplier: u32,
+    /// Loyalty bonus per consecutive event after the first, in basis points
+    pub streak_bonus_bps: u32,
+    pub max_streak_bonus_bps: u32,
+    /// Reputation multiplier at reputation 0 and its increase per point, in basis points
+    pub reputation_base_bps: u32,
+    pub reputation_step_bps: u32,
+    /// Steps applied to the base reward, in order
+    pub modifiers: Vec<RewardModifier>,
+}
+
+impl RewardConfig {
+    /// Multiplier of a per-participant step in basis points; None for `ProRata`,
+    /// which is applied when the event settles
+    pub fn modifier_bps(&self, kind: RewardModifierKind, inputs: &RewardInputs) -> Option<u32> {
+        match kind {
+            RewardModifierKind::EfficiencyBonus if inputs.delivered > inputs.target => {
+                Some(10_000_u32.saturating_add(self.efficiency_bonus_bps))
+            }
+            RewardModifierKind::EfficiencyBonus => Some(10_000),
+            RewardModifierKind::Flexibility => Some(inputs.flexibility_score.map_or(10_000, |score| {
+                500_u32
+                    .saturating_add(u32::from(score))
+                    .clamp(self.min_flexibility_multiplier, self.max_flexibility_multiplier)
+                    .saturating_mul(10)
+            })),
+            RewardModifierKind::Reputation => Some(inputs.reputation.map_or(10_000, |reputation| {
+                self.reputation_base_bps.saturating_add(reputation.saturating_mul(self.reputation_step_bps))
+            })),
+            RewardModifierKind::Streak => Some(10_000_u32.saturating_add(self.streak_bonus(inputs.streak))),
+            RewardModifierKind::ProRata => None,
+        }
+    }
+
+    /// Reward for one participant: `delivered` priced at the base rate per kWh, then each
+    /// enabled per-participant modifier in order
+    pub fn compute_reward(&self, base_compensation_rate: Balance, inputs: &RewardInputs) -> RewardBreakdown {
+        let base_reward = inputs.delivered.priced_at(base_compensation_rate);
+        let mut reward = base_reward;
+        let mut steps = Vec::new();
+        for modifier in self.modifiers.iter().filter(|m| m.enabled) {
+            if let Some(multiplier_bps) = self.modifier_bps(modifier.kind, inputs) {
+                reward = reward.saturating_mul(u128::from(multiplier_bps)) / 10_000;
+                steps.push(RewardStep { kind: modifier.kind, multiplier_bps, reward });
+            }
+        }
+        RewardBreakdown { base_reward, steps, final_reward: reward }
+    }
+
+    /// Whether the pipeline applies `kind`
+    pub fn is_enabled(&self, kind: RewardModifierKind) -> bool {
+        self.modifiers.iter().any(|m| m.kind == kind && m.enabled)
+    }
+
+    /// Streak bonus in basis points; the first event of a streak earns none
+    pub fn streak_bonus(&self, streak: u32) -> u32 {
+        streak
+            .saturating_sub(1)
+            .saturating_mul(self.streak_bonus_bps)
+            .min(self.max_streak_bonus_bps)
+    }
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        let modifier = |kind| RewardModifier { kind, enabled: true };
+        Self {
+            efficiency_bonus_bps: 2000,
+            min_flexibility_multiplier: 500,
+            max_flexibility_multiplier: 1500,
+            streak_bonus_bps: 100,
+            max_streak_bonus_bps: 1000,
+            // Reputation 0-100 maps to 0.8x-1.2x
+            reputation_base_bps: 8000,
+            reputation_step_bps: 40,
+            modifiers: Vec::from([
+                modifier(RewardModifierKind::EfficiencyBonus),
+                modifier(RewardModifierKind::Flexibility),
+                modifier(RewardModifierKind::Streak),
+                modifier(RewardModifierKind::Reputation),
+                modifier(RewardModifierKind::ProRata),
+            ]),
+        }
+    }
+}
+
+/// Asset an event's rewards are paid in
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum RewardAsset {
+    /// PGT minted by the grid service
+    #[default]
+    Pgt,
+    /// A PSP22 token transferred from the grid service's own balance
+    Psp22(AccountId),
+    /// The chain's native token, paid from the grid service's balance
+    Native,
+}
+
+/// Second-precision timestamp packed into 4 bytes (valid until 2106)
+pub type CompactTimestamp = u32;
+
+/// Millisecond timestamp truncated to whole seconds, saturating past `u32::MAX`
+pub fn compact_timestamp(timestamp: Timestamp) -> CompactTimestamp {
+    u32::try_from(timestamp / 1000).unwrap_or(u32::MAX)
+}
+
+/// Lifecycle of a grid event; replaces the former active/completed/cancelled flags
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum GridEventStatus {
+    #[default]
+    Active,
+    Completed,
+    Cancelled,
 }
 
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
@@ -74,15 +371,34 @@ pub enum GridEventType {
 pub struct GridEvent {
     pub event_type: GridEventType,
     pub duration_minutes: u64,
+    /// Per kWh delivered
     pub base_compensation_rate: Balance,
-    pub target_reduction_kw: u64,
-    pub created_at: Timestamp,
+    /// Power reduction sought for the whole event duration
+    pub target_reduction_kw: Kilowatts,
     pub start_time: Timestamp,
     pub end_time: Timestamp,
-    pub active: bool,
+    pub status: GridEventStatus,
     pub total_participants: u32,
-    pub total_energy_reduced: u64,
-    pub completed: bool,
+    pub total_energy_reduced: WattHours,
+}
+
+impl GridEvent {
+    pub fn is_active(&self) -> bool {
+        self.status == GridEventStatus::Active
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status == GridEventStatus::Completed
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status == GridEventStatus::Cancelled
+    }
+
+    /// Completed or cancelled
+    pub fn is_settled(&self) -> bool {
+        self.status != GridEventStatus::Active
+    }
 }
 
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
@@ -90,7 +406,7 @@ pub struct GridEvent {
 pub struct GridSignal {
     pub event_type: GridEventType,
     pub duration_minutes: u64,
-    pub target_reduction_kw: u64,
+    pub target_reduction_kw: Kilowatts,
     /// Severity scale 1-5 used to scale compensation rate
     pub severity: u8,
     /// If true, create/start an event with the given parameters
@@ -99,16 +415,274 @@ pub struct GridSignal {
     pub complete_event_id: Option<u64>,
 }
 
+/// How often a data feed may push grid conditions (governance managed)
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct FeedRateLimit {
+    /// Minimum time between accepted updates (0 = no minimum)
+    pub min_interval_ms: u64,
+    /// Accepted updates per UTC day (0 = unlimited)
+    pub max_updates_per_day: u32,
+}
+
+/// Event types and compensation an authorized GridService caller may launch (governance managed)
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct CallerPermissions {
+    /// Event types the caller may create (empty = any type)
+    pub allowed_event_types: Vec<GridEventType>,
+    /// Highest compensation rate per kWh the caller may set (0 = unlimited)
+    pub max_compensation_rate: Balance,
+}
+
+impl CallerPermissions {
+    /// Whether an event of `event_type` paying `compensation_rate` is within these permissions
+    pub fn permits(&self, event_type: &GridEventType, compensation_rate: Balance) -> bool {
+        (self.allowed_event_types.is_empty() || self.allowed_event_types.contains(event_type))
+            && (self.max_compensation_rate == 0 || compensation_rate <= self.max_compensation_rate)
+    }
+}
+
+/// What `ingest_grid_signal` actually did on-chain
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq, Default)]
+pub struct SignalOutcome {
+    pub created_event: Option<u64>,
+    pub completed_event: Option<u64>,
+    /// Failures of the requested actions, in the order they were attempted
+    pub errors: Vec<String>,
+}
+
+/// Settlement progress of a participation; replaces the former verified/paid flags
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum ParticipationStatus {
+    #[default]
+    Pending,
+    /// Delivery verified, reward not yet paid out
+    Verified,
+    /// Verified and paid
+    Paid,
+}
+
+/// Event participation as stored per event; evidence payloads are kept in a
+/// separate mapping so loading an event's participants does not read them
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
 #[cfg_attr(feature = "std", derive(StorageLayout))]
 pub struct Participation {
-    pub participant: [u8; 32],
-    pub energy_contributed_wh: u64,
+    pub participant: AccountId,
+    pub energy_contributed_wh: WattHours,
+    pub participation_start: CompactTimestamp,
+    /// Zero until verified
+    pub participation_end: CompactTimestamp,
+    pub reward_earned: Balance,
+    pub status: ParticipationStatus,
+}
+
+impl Participation {
+    pub fn is_verified(&self) -> bool {
+        self.status != ParticipationStatus::Pending
+    }
+
+    pub fn is_paid(&self) -> bool {
+        self.status == ParticipationStatus::Paid
+    }
+}
+
+/// Grid event layout before storage packing, accepted when importing old state
+#[derive(Decode, Encode, Clone, TypeInfo, Debug)]
+pub struct LegacyGridEvent {
+    pub event_type: GridEventType,
+    pub duration_minutes: u64,
+    pub base_compensation_rate: Balance,
+    pub target_reduction_kw: Kilowatts,
+    pub created_at: Timestamp,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub active: bool,
+    pub total_participants: u32,
+    pub total_energy_reduced: WattHours,
+    pub completed: bool,
+    pub cancelled: bool,
+}
+
+impl From<LegacyGridEvent> for GridEvent {
+    fn from(legacy: LegacyGridEvent) -> Self {
+        let status = if legacy.cancelled {
+            GridEventStatus::Cancelled
+        } else if legacy.completed {
+            GridEventStatus::Completed
+        } else {
+            GridEventStatus::Active
+        };
+        GridEvent {
+            event_type: legacy.event_type,
+            duration_minutes: legacy.duration_minutes,
+            base_compensation_rate: legacy.base_compensation_rate,
+            target_reduction_kw: legacy.target_reduction_kw,
+            start_time: legacy.start_time,
+            end_time: legacy.end_time,
+            status,
+            total_participants: legacy.total_participants,
+            total_energy_reduced: legacy.total_energy_reduced,
+        }
+    }
+}
+
+/// Participation layout before storage packing, accepted when importing old state
+#[derive(Decode, Encode, Clone, TypeInfo, Debug)]
+pub struct LegacyParticipation {
+    pub participant: AccountId,
+    pub energy_contributed_wh: WattHours,
     pub participation_start: Timestamp,
     pub participation_end: Timestamp,
     pub reward_earned: Balance,
     pub verified: bool,
     pub paid: bool,
+    pub extra_data: Option<Vec<u8>>,
+}
+
+impl LegacyParticipation {
+    /// Packed participation plus the evidence payload that is now stored separately
+    pub fn into_packed(self) -> (Participation, Option<Vec<u8>>) {
+        let status = match (self.verified, self.paid) {
+            (true, true) => ParticipationStatus::Paid,
+            (true, false) => ParticipationStatus::Verified,
+            _ => ParticipationStatus::Pending,
+        };
+        let participation = Participation {
+            participant: self.participant,
+            energy_contributed_wh: self.energy_contributed_wh,
+            participation_start: compact_timestamp(self.participation_start),
+            participation_end: compact_timestamp(self.participation_end),
+            reward_earned: self.reward_earned,
+            status,
+        };
+        (participation, self.extra_data)
+    }
+}
+
+/// Interval power reading reported by a device or gateway during an event
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct PowerSample {
+    pub timestamp: Timestamp,
+    /// Average power drawn over the interval, in watts
+    pub power_watts: u64,
+}
+
+/// One dependency check performed by a contract's `verify_wiring`
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct WiringCheck {
+    pub name: String,
+    pub ok: bool,
+}
+
+/// Readiness report returned by `verify_wiring`; `ready` is true only when every check passed
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct WiringReport {
+    pub checks: Vec<WiringCheck>,
+    pub ready: bool,
+}
+
+impl WiringReport {
+    pub fn new() -> Self {
+        Self { checks: Vec::new(), ready: true }
+    }
+
+    pub fn check(&mut self, name: &str, ok: bool) {
+        self.ready &= ok;
+        self.checks.push(WiringCheck { name: String::from(name), ok });
+    }
+
+    /// Result of a named check, if it was performed
+    pub fn get(&self, name: &str) -> Option<bool> {
+        self.checks.iter().find(|c| c.name == name).map(|c| c.ok)
+    }
+}
+
+impl Default for WiringReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recurring treasury allowance of a program council, spent by k-of-n member approvals
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct CouncilBudget {
+    pub name: String,
+    pub members: Vec<AccountId>,
+    /// Member approvals needed to pay out a spend
+    pub threshold: u8,
+    /// Amount spendable per period; unspent allowance does not roll over
+    pub allowance_per_period: Balance,
+    pub period_ms: u64,
+}
+
+/// Purpose a PGT minter mints for; each has its own share of the emission budget
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum EmissionBucket {
+    #[default]
+    ParticipationRewards,
+    StakingRewards,
+    Treasury,
+}
+
+impl EmissionBucket {
+    pub const ALL: [EmissionBucket; 3] = [
+        EmissionBucket::ParticipationRewards,
+        EmissionBucket::StakingRewards,
+        EmissionBucket::Treasury,
+    ];
+
+    /// Position of the bucket in `ALL`
+    pub fn index(self) -> usize {
+        match self {
+            EmissionBucket::ParticipationRewards => 0,
+            EmissionBucket::StakingRewards => 1,
+            EmissionBucket::Treasury => 2,
+        }
+    }
+}
+
+/// Annual inflation cap on PGT minting and its split across emission buckets
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct EmissionSchedule {
+    /// Most the supply may grow per year through minting, in basis points
+    pub annual_inflation_bps: u16,
+    /// Shares of the budget per bucket, in basis points summing to 10 000
+    pub participation_bps: u16,
+    pub staking_bps: u16,
+    pub treasury_bps: u16,
+}
+
+impl EmissionSchedule {
+    /// Shares add up to the whole budget
+    pub fn is_valid(&self) -> bool {
+        u32::from(self.participation_bps) + u32::from(self.staking_bps) + u32::from(self.treasury_bps) == 10_000
+    }
+
+    pub fn bucket_bps(&self, bucket: EmissionBucket) -> u16 {
+        match bucket {
+            EmissionBucket::ParticipationRewards => self.participation_bps,
+            EmissionBucket::StakingRewards => self.staking_bps,
+            EmissionBucket::Treasury => self.treasury_bps,
+        }
+    }
+}
+
+/// Quadratic-funding grant round opened by governance from the treasury
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct GrantRoundParams {
+    /// Treasury funds split among projects by quadratic matching
+    pub matching_pool: Balance,
+    /// Blocks during which projects register and receive donations
+    pub duration_blocks: u64,
+    /// Donations per contributor and project counted toward matching
+    pub contributor_cap: Balance,
 }
 
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
@@ -117,19 +691,96 @@ pub enum ProposalType {
     UpdateMinStake(Balance),
     UpdateCompensationRate(Balance),
     UpdateReputationThreshold(u32),
-    TreasurySpend([u8; 32], Balance),
+    TreasurySpend(AccountId, Balance),
     SystemUpgrade,
     Other(String),
     /// Governance role management
-    SetTokenMinter([u8; 32], bool),
-    SetRegistryAuthorizedCaller([u8; 32], bool),
-    SetGridAuthorizedCaller([u8; 32], bool),
+    SetTokenMinter(AccountId, bool),
+    SetRegistryAuthorizedCaller(AccountId, bool),
+    SetGridAuthorizedCaller(AccountId, bool),
+    /// Set or clear the registry policy for a device type
+    SetDeviceTypePolicy(DeviceType, Option<DeviceTypePolicy>),
+    /// Set or clear the minimum firmware version for (manufacturer, model)
+    SetMinFirmwareVersion(String, String, Option<String>),
+    /// Register, update or remove a custom grid event type
+    SetCustomEventType(u16, Option<CustomEventTypeDescriptor>),
+    /// Rotate (or remove) the protocol guardian on all contracts
+    SetGuardian(Option<AccountId>),
+    /// GridService automation policy
+    SetAutoTriggerEnabled(bool),
+    CreateTriggerRule(TriggerRuleParams),
+    SetTriggerRuleActive(u64, bool),
+    RemoveTriggerRule(u64),
+    SetDataFeed(AccountId, bool),
+    SetRewardConfig(RewardConfig),
+    /// Set the default feed rate limit (no feed) or a per-feed override (None clears it)
+    SetFeedRateLimit(Option<AccountId>, Option<FeedRateLimit>),
+    /// Restrict (or, with None, unrestrict) the events an authorized GridService caller may create
+    SetCallerPermissions(AccountId, Option<CallerPermissions>),
+    /// Approve or revoke a GridService verifier for an event type
+    SetTypeVerifier(GridEventType, AccountId, bool),
+    /// Fund (or, with None, dissolve) a program council budget
+    SetCouncilBudget(u32, Option<CouncilBudget>),
+    /// Take back up to this much of a council's unspent allowance for the current period
+    ClawbackCouncilBudget(u32, Balance),
+    /// Designate (or, with None, revoke) the token's emergency minter and its cap per quarter
+    SetEmergencyMintBudget(Option<AccountId>, Balance),
+    /// Open a quadratic-funding grant round
+    OpenGrantRound(GrantRoundParams),
+    /// Set (or, with None, lift) the PGT inflation cap
+    SetEmissionSchedule(Option<EmissionSchedule>),
+    /// Assign a token minter to an emission bucket
+    SetMinterEmissionBucket(AccountId, EmissionBucket),
+}
+
+impl ProposalType {
+    /// This action with its numeric parameter replaced by `value`; None for actions
+    /// without a single numeric parameter or values that do not fit it
+    pub fn with_value(&self, value: Balance) -> Option<ProposalType> {
+        match self {
+            ProposalType::UpdateMinStake(_) => Some(ProposalType::UpdateMinStake(value)),
+            ProposalType::UpdateCompensationRate(_) => Some(ProposalType::UpdateCompensationRate(value)),
+            ProposalType::UpdateReputationThreshold(_) => {
+                u32::try_from(value).ok().map(ProposalType::UpdateReputationThreshold)
+            }
+            ProposalType::TreasurySpend(recipient, _) => Some(ProposalType::TreasurySpend(*recipient, value)),
+            _ => None,
+        }
+    }
+}
+
+/// Broken protocol invariant reported by GridService
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub enum AlarmKind {
+    /// PGT rewards minted in a 30-day period exceeded the configured cap
+    RewardCapExceeded { period: u64, minted: Balance, cap: Balance },
+    /// Two feeds reported frequencies further apart than the threshold within a short window
+    FeedDisagreement { feed: AccountId, other_feed: AccountId, frequency_hz: u32, other_frequency_hz: u32 },
+}
+
+impl AlarmKind {
+    /// Pre-formed governance response: halt automatic events on overspending,
+    /// drop the feed whose report disagreed on feed disagreement
+    pub fn proposal(&self) -> ProposalType {
+        match self {
+            AlarmKind::RewardCapExceeded { .. } => ProposalType::SetAutoTriggerEnabled(false),
+            AlarmKind::FeedDisagreement { feed, .. } => ProposalType::SetDataFeed(*feed, false),
+        }
+    }
+}
+
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Alarm {
+    pub kind: AlarmKind,
+    pub raised_at: Timestamp,
 }
 
 #[derive(Decode, Encode, Clone, TypeInfo, Debug)]
 #[cfg_attr(feature = "std", derive(StorageLayout))]
 pub struct Proposal {
-    pub proposer: [u8; 32],
+    pub proposer: AccountId,
     pub proposal_type: ProposalType,
     pub description: String,
     pub yes_votes: u64,
@@ -140,3 +791,21 @@ pub struct Proposal {
     pub executed: bool,
     pub active: bool,
 }
+
+/// One page of an export message and the cursor to pass for the next (None = complete)
+#[derive(Decode, Encode, Clone, TypeInfo, Debug, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Read positions from `cursor` up to `end` with `read`, stopping after `limit` of them
+    /// (clamped to `MAX_PAGE_SIZE`, 0 = the maximum); positions `read` yields nothing for are skipped
+    pub fn collect(cursor: u64, limit: u32, end: u64, mut read: impl FnMut(u64) -> Option<T>) -> Self {
+        let limit = if limit == 0 { crate::constants::MAX_PAGE_SIZE } else { limit.min(crate::constants::MAX_PAGE_SIZE) };
+        let stop = cursor.saturating_add(u64::from(limit)).min(end);
+        let items = (cursor..stop).filter_map(&mut read).collect();
+        Page { items, next_cursor: (stop < end).then_some(stop) }
+    }
+}
@@ -0,0 +1,92 @@
+//! Energy and power units.
+//!
+//! Grid targets are expressed as power (kW, MW) while delivered reductions are
+//! energy (Wh). Keeping each in its own type forces an explicit conversion,
+//! with the event duration, wherever the two are compared or priced.
+
+use crate::types::Balance;
+use scale::{Decode, Encode};
+use scale_info::TypeInfo;
+
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+/// Energy in watt-hours
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct WattHours(pub u64);
+
+/// Energy in kilowatt-hours
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct KilowattHours(pub u64);
+
+/// Power in kilowatts
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Kilowatts(pub u64);
+
+/// Power in megawatts
+#[derive(Decode, Encode, Clone, Copy, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Megawatts(pub u64);
+
+impl WattHours {
+    pub fn saturating_add(self, other: WattHours) -> WattHours {
+        WattHours(self.0.saturating_add(other.0))
+    }
+
+    /// Whole kilowatt-hours (rounded down)
+    pub fn to_kilowatt_hours(self) -> KilowattHours {
+        KilowattHours(self.0 / 1000)
+    }
+
+    /// Average power needed to deliver this energy over `minutes` (None for a zero window)
+    pub fn average_over_minutes(self, minutes: u64) -> Option<Kilowatts> {
+        self.0.saturating_mul(60).checked_div(minutes).map(|watts| Kilowatts(watts / 1000))
+    }
+
+    /// Value of this energy at a price per kWh
+    pub fn priced_at(self, rate_per_kwh: Balance) -> Balance {
+        rate_per_kwh.saturating_mul(Balance::from(self.0)) / 1000
+    }
+}
+
+impl KilowattHours {
+    pub fn to_watt_hours(self) -> Option<WattHours> {
+        self.0.checked_mul(1000).map(WattHours)
+    }
+}
+
+impl Kilowatts {
+    /// Energy delivered by sustaining this power for `minutes`
+    pub fn over_minutes(self, minutes: u64) -> Option<WattHours> {
+        self.0
+            .checked_mul(1000)?
+            .checked_mul(minutes)
+            .map(|wh_minutes| WattHours(wh_minutes / 60))
+    }
+
+    /// `percent`% of this power (rounded down)
+    pub fn percent(self, percent: u8) -> Kilowatts {
+        Kilowatts(self.0.saturating_mul(u64::from(percent)) / 100)
+    }
+}
+
+impl Megawatts {
+    pub fn to_kilowatts(self) -> Option<Kilowatts> {
+        self.0.checked_mul(1000).map(Kilowatts)
+    }
+
+    /// This load as a percentage of `capacity`, capped at 100 (0 when capacity is 0)
+    pub fn percent_of(self, capacity: Megawatts) -> u8 {
+        if capacity.0 == 0 {
+            return 0;
+        }
+        match self.0.checked_mul(100) {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(load_times_100) => (load_times_100 / capacity.0).min(100) as u8,
+            None => 100, // overflow means very high load
+        }
+    }
+}
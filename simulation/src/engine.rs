@@ -0,0 +1,224 @@
+//! Step-based driver applying GridService's trigger, reward and fee rules
+
+use crate::fleet::{generate_fleet, FleetConfig};
+use crate::grid::{sample, GridConfig};
+use crate::report::{RewardDistribution, SimulationReport};
+use crate::rng::Rng;
+use powergrid_shared::{
+    split_protocol_fee, AutoEventConcurrency, Balance, GridEventType, Kilowatts, Megawatts, RewardConfig, RewardInputs,
+    TriggerRuleParams, WattHours,
+};
+
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub seed: u64,
+    pub steps: u32,
+    pub step_minutes: u64,
+    pub fleet: FleetConfig,
+    pub grid: GridConfig,
+    pub rules: Vec<TriggerRuleParams>,
+    /// GridService's auto-event concurrency policy
+    pub concurrency: AutoEventConcurrency,
+    pub reward_config: RewardConfig,
+    pub protocol_fee_bps: u16,
+    /// Token budget available for rewards over the whole run
+    pub budget: Balance,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            // One week at 15 minute resolution
+            steps: 7 * 96,
+            step_minutes: 15,
+            fleet: FleetConfig::default(),
+            grid: GridConfig::default(),
+            rules: vec![TriggerRuleParams {
+                event_type: GridEventType::PeakShaving,
+                load_threshold_percentage: 90,
+                frequency_low_threshold: 4980,
+                frequency_high_threshold: 5020,
+                compensation_rate: 1_000_000_000_000_000,
+                target_reduction_percentage: 1,
+                duration_minutes: 60,
+                zone: None,
+            }],
+            concurrency: AutoEventConcurrency::OnePerTypeAndZone,
+            reward_config: RewardConfig::default(),
+            protocol_fee_bps: 0,
+            budget: 1_000_000_000_000_000_000_000,
+        }
+    }
+}
+
+struct ActiveEvent {
+    /// (event type, zone) GridService deduplicates auto events by
+    key: (GridEventType, Option<String>),
+    ends_at_step: u32,
+    compensation_rate: Balance,
+    duration_minutes: u64,
+    target_reduction_kw: Kilowatts,
+    /// (device index, reduction kW)
+    participants: Vec<(usize, u64)>,
+}
+
+/// Run a scenario to completion. Rules fire in order through the same threshold check,
+/// target and concurrency policy as GridService's `check_auto_triggers`; a device takes
+/// part in one event at a time.
+pub fn run(scenario: &Scenario) -> SimulationReport {
+    let mut rng = Rng::new(scenario.seed);
+    let mut devices = generate_fleet(&scenario.fleet, &mut rng);
+    let mut report = SimulationReport {
+        steps: scenario.steps,
+        triggers_by_rule: vec![0; scenario.rules.len()],
+        budget: scenario.budget,
+        min_frequency_hz: u32::MAX,
+        ..Default::default()
+    };
+    let mut active: Vec<ActiveEvent> = Vec::new();
+
+    for step in 0..scenario.steps {
+        let minute_of_day = (step as u64).saturating_mul(scenario.step_minutes) % 1440;
+        let curtailed_kw: u64 = active
+            .iter()
+            .flat_map(|e| e.participants.iter().map(|(_, kw)| kw))
+            .sum();
+        let state = sample(&scenario.grid, minute_of_day, curtailed_kw, &mut rng);
+        let load_percentage = state.load_percentage(&scenario.grid);
+        report.peak_load_percentage = report.peak_load_percentage.max(load_percentage);
+        report.min_frequency_hz = report.min_frequency_hz.min(state.frequency_hz);
+        report.max_frequency_hz = report.max_frequency_hz.max(state.frequency_hz);
+        if curtailed_kw > 0 {
+            report.curtailed_kwh = report
+                .curtailed_kwh
+                .saturating_add(curtailed_kw.saturating_mul(scenario.step_minutes) / 60);
+        }
+
+        let (ended, running): (Vec<ActiveEvent>, Vec<ActiveEvent>) =
+            active.into_iter().partition(|e| step >= e.ends_at_step);
+        active = running;
+        for event in &ended {
+            settle(scenario, event, &mut devices, &mut report, step);
+        }
+
+        for (index, rule) in scenario.rules.iter().enumerate() {
+            if !rule.thresholds_crossed(load_percentage, state.frequency_hz) {
+                continue;
+            }
+            let key = (rule.event_type.clone(), rule.zone.clone());
+            if !scenario.concurrency.allows_duplicates() && active.iter().any(|e| e.key == key) {
+                continue;
+            }
+            report.triggers_by_rule[index] += 1;
+            report.events_triggered += 1;
+            let steps = rule.duration_minutes.div_ceil(scenario.step_minutes.max(1)).max(1);
+            let busy: Vec<usize> = active.iter().flat_map(|e| e.participants.iter().map(|&(i, _)| i)).collect();
+            let mut participants: Vec<(usize, u64)> = Vec::new();
+            for (i, device) in devices.iter_mut().enumerate() {
+                if busy.contains(&i) {
+                    continue;
+                }
+                match device.respond(&mut rng) {
+                    Some(kw) => participants.push((i, kw)),
+                    // Sitting an event out breaks a device's streak
+                    None => device.streak = 0,
+                }
+            }
+            active.push(ActiveEvent {
+                key,
+                ends_at_step: step.saturating_add(steps as u32),
+                compensation_rate: rule.compensation_rate,
+                duration_minutes: rule.duration_minutes,
+                target_reduction_kw: rule.target_reduction(Some(Megawatts(state.load_mw))),
+                participants,
+            });
+        }
+    }
+
+    let earnings: Vec<Balance> = devices.iter().map(|d| d.earned).collect();
+    report.rewards = RewardDistribution::from_earnings(&earnings);
+    report.idle_devices = devices.iter().filter(|d| d.events_joined == 0).count() as u32;
+    if report.min_frequency_hz == u32::MAX {
+        report.min_frequency_hz = 0;
+    }
+    report
+}
+
+fn settle(
+    scenario: &Scenario,
+    event: &ActiveEvent,
+    devices: &mut [crate::fleet::SimDevice],
+    report: &mut SimulationReport,
+    step: u32,
+) {
+    report.events_completed += 1;
+    let target = event
+        .target_reduction_kw
+        .over_minutes(event.duration_minutes)
+        .unwrap_or(WattHours(u64::MAX));
+    for &(index, reduction_kw) in &event.participants {
+        let device = &mut devices[index];
+        device.streak = device.streak.saturating_add(1);
+        let delivered = Kilowatts(reduction_kw)
+            .over_minutes(event.duration_minutes)
+            .unwrap_or(WattHours(u64::MAX));
+        let inputs = RewardInputs {
+            delivered,
+            target,
+            flexibility_score: device.flexibility_score,
+            reputation: None,
+            streak: device.streak,
+        };
+        let reward = scenario.reward_config.compute_reward(event.compensation_rate, &inputs).final_reward;
+        report.participations += 1;
+        device.events_joined += 1;
+        let remaining = report.budget.saturating_sub(report.budget_spent);
+        if reward > remaining {
+            report.unpaid_rewards = report.unpaid_rewards.saturating_add(reward);
+            report.budget_exhausted_at_step.get_or_insert(step);
+            continue;
+        }
+        let (share, fee) = split_protocol_fee(reward, scenario.protocol_fee_bps);
+        report.budget_spent = report.budget_spent.saturating_add(reward);
+        report.protocol_fees = report.protocol_fees.saturating_add(fee);
+        device.earned = device.earned.saturating_add(share);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_are_deterministic_and_respect_budget() {
+        let scenario = Scenario { budget: 1_000_000_000_000_000_000, ..Default::default() };
+        let first = run(&scenario);
+        let second = run(&scenario);
+        assert_eq!(first, second);
+
+        assert!(first.events_triggered > 0);
+        assert!(first.budget_spent <= scenario.budget);
+        assert_eq!(
+            first.rewards.total.saturating_add(first.protocol_fees),
+            first.budget_spent
+        );
+    }
+
+    #[test]
+    fn test_concurrency_policy_deduplicates_by_type_and_zone() {
+        let mut scenario = Scenario { steps: 96, ..Default::default() };
+        let rule = scenario.rules[0].clone();
+        scenario.rules.push(rule.clone());
+        scenario.rules.push(TriggerRuleParams { zone: Some("u4pr".into()), ..rule });
+
+        let deduplicated = run(&scenario);
+        // The duplicate rule never fires alongside the first; the zoned one is a separate key
+        assert!(deduplicated.triggers_by_rule[0] > 0);
+        assert_eq!(deduplicated.triggers_by_rule[1], 0);
+        assert_eq!(deduplicated.triggers_by_rule[2], deduplicated.triggers_by_rule[0]);
+
+        let unlimited = run(&Scenario { concurrency: AutoEventConcurrency::Unlimited, ..scenario });
+        assert_eq!(unlimited.triggers_by_rule[1], unlimited.triggers_by_rule[0]);
+    }
+}
@@ -0,0 +1,72 @@
+//! Device fleet model
+
+use crate::rng::Rng;
+use powergrid_shared::Balance;
+
+#[derive(Debug, Clone)]
+pub struct FleetConfig {
+    pub size: u32,
+    pub min_capacity_kw: u64,
+    pub max_capacity_kw: u64,
+    /// Probability a device is online and opted in when an event starts
+    pub availability: f64,
+    /// Mean fraction of capacity actually curtailed when participating
+    pub reliability: f64,
+    /// Fraction of devices with a flexibility score on-chain
+    pub scored_fraction: f64,
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self {
+            size: 200,
+            min_capacity_kw: 1,
+            max_capacity_kw: 20,
+            availability: 0.6,
+            reliability: 0.8,
+            scored_fraction: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimDevice {
+    pub id: u32,
+    pub capacity_kw: u64,
+    pub availability: f64,
+    pub reliability: f64,
+    /// Flexibility score (0-1000) as GridService would hold it
+    pub flexibility_score: Option<u16>,
+    pub earned: Balance,
+    pub events_joined: u32,
+    /// Consecutive events responded to
+    pub streak: u32,
+}
+
+impl SimDevice {
+    /// Reduction (kW) delivered for one event, or None if the device sits it out
+    pub fn respond(&self, rng: &mut Rng) -> Option<u64> {
+        if !rng.chance(self.availability) {
+            return None;
+        }
+        let delivered = (self.reliability + rng.jitter(0.15)).clamp(0.0, 1.0);
+        Some((self.capacity_kw as f64 * delivered).round() as u64)
+    }
+}
+
+pub fn generate_fleet(config: &FleetConfig, rng: &mut Rng) -> Vec<SimDevice> {
+    (0..config.size)
+        .map(|id| SimDevice {
+            id,
+            capacity_kw: rng.range(config.min_capacity_kw, config.max_capacity_kw),
+            availability: (config.availability + rng.jitter(0.2)).clamp(0.0, 1.0),
+            reliability: (config.reliability + rng.jitter(0.1)).clamp(0.0, 1.0),
+            flexibility_score: rng
+                .chance(config.scored_fraction)
+                .then(|| rng.range(0, 1000) as u16),
+            earned: 0,
+            events_joined: 0,
+            streak: 0,
+        })
+        .collect()
+}
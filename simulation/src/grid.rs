@@ -0,0 +1,64 @@
+//! Grid load and frequency dynamics
+
+use crate::rng::Rng;
+use powergrid_shared::Megawatts;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone)]
+pub struct GridConfig {
+    pub capacity_mw: u64,
+    /// Overnight load
+    pub base_load_mw: f64,
+    /// Evening peak load
+    pub peak_load_mw: f64,
+    /// Random load fluctuation amplitude
+    pub noise_mw: f64,
+    /// Nominal frequency in 0.01 Hz (5000 = 50.00 Hz)
+    pub nominal_frequency: u32,
+    /// Frequency drop (0.01 Hz) per MW of load above the scheduled level
+    pub frequency_sensitivity: f64,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            capacity_mw: 1_000,
+            base_load_mw: 550.0,
+            peak_load_mw: 950.0,
+            noise_mw: 40.0,
+            nominal_frequency: 5000,
+            frequency_sensitivity: 0.5,
+        }
+    }
+}
+
+/// Grid reading in the units GridService's `update_grid_condition` takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridState {
+    pub load_mw: u64,
+    pub frequency_hz: u32,
+}
+
+impl GridState {
+    /// Load as a percentage of capacity, capped at 100 like the contract
+    pub fn load_percentage(&self, config: &GridConfig) -> u8 {
+        Megawatts(self.load_mw).percent_of(Megawatts(config.capacity_mw))
+    }
+}
+
+/// Sample the grid at `minute_of_day`, after `curtailed_kw` of demand response
+pub fn sample(config: &GridConfig, minute_of_day: u64, curtailed_kw: u64, rng: &mut Rng) -> GridState {
+    // Daily curve peaking at 18:00
+    let phase = (minute_of_day as f64 / 1440.0 - 0.75) * 2.0 * PI;
+    let scheduled = config.base_load_mw
+        + (config.peak_load_mw - config.base_load_mw) * (phase.cos() + 1.0) / 2.0;
+    let noise = rng.jitter(config.noise_mw);
+    let load = (scheduled + noise - curtailed_kw as f64 / 1000.0).max(0.0);
+    // Unscheduled demand pulls frequency down, curtailment pushes it back up
+    let deviation = (load - scheduled) * config.frequency_sensitivity;
+    let frequency = (config.nominal_frequency as f64 - deviation).max(0.0);
+    GridState {
+        load_mw: load.round() as u64,
+        frequency_hz: frequency.round() as u32,
+    }
+}
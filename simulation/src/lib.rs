@@ -0,0 +1,18 @@
+//! Off-chain simulation of PowerGrid Network economics
+//!
+//! Models a fleet of devices with stochastic availability and a grid with
+//! load/frequency dynamics, and drives them through the same trigger rules,
+//! reward formula and protocol fee split that GridService applies on-chain.
+//! Used to tune rule thresholds, compensation rates and budgets before they
+//! are put to a governance vote.
+
+pub mod engine;
+pub mod fleet;
+pub mod grid;
+pub mod report;
+pub mod rng;
+
+pub use engine::{run, Scenario};
+pub use fleet::FleetConfig;
+pub use grid::GridConfig;
+pub use report::SimulationReport;
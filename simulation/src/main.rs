@@ -0,0 +1,31 @@
+//! Run the default scenario and print the report as JSON
+//!
+//! Overrides are read from the environment: `SEED`, `STEPS`, `FLEET_SIZE`,
+//! `LOAD_THRESHOLD`, `COMPENSATION_RATE`, `PROTOCOL_FEE_BPS` and `BUDGET`.
+
+use powergrid_simulation::{run, Scenario};
+use std::env;
+use std::str::FromStr;
+
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn main() {
+    let mut scenario = Scenario::default();
+    scenario.seed = env_or("SEED", scenario.seed);
+    scenario.steps = env_or("STEPS", scenario.steps);
+    scenario.fleet.size = env_or("FLEET_SIZE", scenario.fleet.size);
+    scenario.protocol_fee_bps = env_or("PROTOCOL_FEE_BPS", scenario.protocol_fee_bps);
+    scenario.budget = env_or("BUDGET", scenario.budget);
+    for rule in &mut scenario.rules {
+        rule.load_threshold_percentage = env_or("LOAD_THRESHOLD", rule.load_threshold_percentage);
+        rule.compensation_rate = env_or("COMPENSATION_RATE", rule.compensation_rate);
+    }
+
+    let report = run(&scenario);
+    match serde_json::to_string_pretty(&report.to_json()) {
+        Ok(rendered) => println!("{rendered}"),
+        Err(err) => eprintln!("❌ failed to render report: {err}"),
+    }
+}
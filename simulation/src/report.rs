@@ -0,0 +1,116 @@
+//! Aggregated simulation results
+
+use powergrid_shared::Balance;
+use serde_json::{json, Value};
+
+/// How rewards ended up spread across the fleet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RewardDistribution {
+    pub total: Balance,
+    pub min: Balance,
+    pub max: Balance,
+    pub mean: Balance,
+    pub median: Balance,
+    /// Gini coefficient of device earnings (0 = equal, 1 = one device earns everything)
+    pub gini: f64,
+    /// Share of rewards earned by the top 10% of devices
+    pub top_decile_share: f64,
+}
+
+impl RewardDistribution {
+    pub fn from_earnings(earnings: &[Balance]) -> Self {
+        if earnings.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = earnings.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let total: Balance = sorted.iter().sum();
+        let (gini, top_decile_share) = if total == 0 {
+            (0.0, 0.0)
+        } else {
+            let weighted: f64 = sorted
+                .iter()
+                .enumerate()
+                .map(|(i, &e)| (2.0 * (i as f64 + 1.0) - n as f64 - 1.0) * e as f64)
+                .sum();
+            let top = n.div_ceil(10);
+            let top_sum: Balance = sorted[n - top..].iter().sum();
+            (
+                weighted / (n as f64 * total as f64),
+                top_sum as f64 / total as f64,
+            )
+        };
+        Self {
+            total,
+            min: sorted[0],
+            max: sorted[n - 1],
+            mean: total / n as Balance,
+            median: sorted[n / 2],
+            gini,
+            top_decile_share,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    pub steps: u32,
+    pub events_triggered: u32,
+    pub events_completed: u32,
+    pub triggers_by_rule: Vec<u32>,
+    pub participations: u32,
+    /// Devices that never took part in an event
+    pub idle_devices: u32,
+    pub curtailed_kwh: u64,
+    pub peak_load_percentage: u8,
+    pub min_frequency_hz: u32,
+    pub max_frequency_hz: u32,
+    pub budget: Balance,
+    pub budget_spent: Balance,
+    /// First step at which a reward could not be paid from the remaining budget
+    pub budget_exhausted_at_step: Option<u32>,
+    pub unpaid_rewards: Balance,
+    pub protocol_fees: Balance,
+    pub rewards: RewardDistribution,
+}
+
+impl SimulationReport {
+    /// JSON rendering; balances are strings since they exceed JSON's safe integer range
+    pub fn to_json(&self) -> Value {
+        json!({
+            "steps": self.steps,
+            "events": {
+                "triggered": self.events_triggered,
+                "completed": self.events_completed,
+                "triggers_by_rule": self.triggers_by_rule,
+            },
+            "participation": {
+                "participations": self.participations,
+                "idle_devices": self.idle_devices,
+                "curtailed_kwh": self.curtailed_kwh,
+            },
+            "grid": {
+                "peak_load_percentage": self.peak_load_percentage,
+                "min_frequency_hz": self.min_frequency_hz,
+                "max_frequency_hz": self.max_frequency_hz,
+            },
+            "budget": {
+                "total": self.budget.to_string(),
+                "spent": self.budget_spent.to_string(),
+                "exhausted_at_step": self.budget_exhausted_at_step,
+                "unpaid_rewards": self.unpaid_rewards.to_string(),
+                "protocol_fees": self.protocol_fees.to_string(),
+            },
+            "rewards": {
+                "total": self.rewards.total.to_string(),
+                "min": self.rewards.min.to_string(),
+                "max": self.rewards.max.to_string(),
+                "mean": self.rewards.mean.to_string(),
+                "median": self.rewards.median.to_string(),
+                "gini": self.rewards.gini,
+                "top_decile_share": self.rewards.top_decile_share,
+            },
+        })
+    }
+}